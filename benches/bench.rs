@@ -1,13 +1,31 @@
-#![feature(test)]
+//! Plain timing loop so the bench builds on stable (`cargo bench`).
 
-extern crate test;
-extern crate wasm_game_of_life;
+use std::hint::black_box;
+use std::time::Instant;
 
-#[bench]
-fn universe_tick(b: &mut test::Bencher) {
+fn bench<F: FnMut()>(name: &str, iters: u32, mut f: F) {
+    // warm up before measuring
+    for _ in 0..iters / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..iters {
+        f();
+    }
+    let per_iter = start.elapsed().as_nanos() / iters as u128;
+    println!("test {} ... bench: {:>12} ns/iter", name, per_iter);
+}
+
+fn universe_tick() {
     let mut universe = wasm_game_of_life::Universe::new(64, 64);
 
-    b.iter(|| {
+    bench("universe_tick", 1_000, || {
         universe.tick();
+        black_box(universe.get_cells());
     });
 }
+
+fn main() {
+    universe_tick();
+}
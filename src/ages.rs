@@ -0,0 +1,154 @@
+use fixedbitset::{Block, FixedBitSet};
+use wasm_bindgen::prelude::*;
+
+use crate::hibernate::Counters;
+use crate::Universe;
+
+/// For how many generations each cell has been alive, see
+/// `Universe::ages()`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Ages {
+    ages: Vec<u16>,
+    max: u16,
+}
+
+impl Ages {
+    fn starting_at(cells: &FixedBitSet) -> Ages {
+        let mut ages = vec![0; cells.len()];
+        for idx in cells.ones() {
+            ages[idx] = 1;
+        }
+        Ages {
+            ages,
+            max: (cells.count_ones(..) > 0) as u16,
+        }
+    }
+
+    /// Age the survivors of a tick from `previous` to `cells`; newborns
+    /// start at 1 and dead cells are 0.
+    pub(crate) fn record(&mut self, previous: &FixedBitSet, cells: &FixedBitSet) {
+        let bits = Block::BITS as usize;
+        let words = previous.as_slice().iter().zip(cells.as_slice());
+        self.max = 0;
+        for (i, (&old, &new)) in words.enumerate() {
+            // ages of cells dead before and after are already 0
+            if old | new == 0 {
+                continue;
+            }
+            let end = ((i + 1) * bits).min(self.ages.len());
+            for idx in i * bits..end {
+                let age = &mut self.ages[idx];
+                *age = if cells[idx] { age.saturating_add(1) } else { 0 };
+                self.max = self.max.max(*age);
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "render"), allow(dead_code))]
+    pub(crate) fn values(&self) -> &[u16] {
+        &self.ages
+    }
+
+    /// Reorder the ages like `layout::transpose_into()` does cells.
+    pub(crate) fn transpose(&mut self, stride: u32, lines: u32) {
+        let mut transposed = vec![0; self.ages.len()];
+        for (idx, &age) in self.ages.iter().enumerate() {
+            let (line, offset) = (idx as u32 / stride, idx as u32 % stride);
+            transposed[(offset * lines + line) as usize] = age;
+        }
+        self.ages = transposed;
+    }
+
+    pub(crate) fn compress(self) -> CompressedAges {
+        CompressedAges {
+            ages: Counters::new(self.ages.into_iter().map(u32::from)),
+            max: self.max,
+        }
+    }
+
+    pub(crate) fn byte_len(&self) -> usize {
+        self.ages.capacity() * std::mem::size_of::<u16>()
+    }
+}
+
+/// `Ages` squeezed while its universe hibernates.
+#[derive(Clone, Debug)]
+pub(crate) struct CompressedAges {
+    ages: Counters,
+    max: u16,
+}
+
+impl CompressedAges {
+    pub(crate) fn restore(&self) -> Ages {
+        Ages {
+            ages: self
+                .ages
+                .restore()
+                .into_iter()
+                .map(|age| age as u16)
+                .collect(),
+            max: self.max,
+        }
+    }
+
+    pub(crate) fn byte_len(&self) -> usize {
+        self.ages.byte_len()
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Start or stop tracking cell ages, e.g. to color long-lived cells
+    /// differently from newborns. Tracking starts with every live cell at
+    /// age 1 and costs a pass over the non-empty storage words every tick.
+    pub fn set_track_ages(&mut self, enabled: bool) {
+        self.wake();
+        self.ages = enabled.then(|| Ages::starting_at(&self.cells));
+    }
+
+    pub fn is_tracking_ages(&self) -> bool {
+        self.current_ages().is_some()
+    }
+
+    /// Pointer to one `u16` per cell, in storage order: the number of
+    /// generations the cell has been alive for (saturating at 65535), 0
+    /// when dead. Read `ages_len()` values; changes with every tick and is
+    /// empty while hibernating.
+    pub fn ages(&self) -> *const u16 {
+        self.ages
+            .as_ref()
+            .map_or(std::ptr::null(), |ages| ages.ages.as_ptr())
+    }
+
+    /// Number of values behind `ages()`, 0 when ages are not tracked.
+    pub fn ages_len(&self) -> usize {
+        self.ages.as_ref().map_or(0, |ages| ages.ages.len())
+    }
+
+    /// Age of the oldest live cell.
+    pub fn max_age(&self) -> u16 {
+        self.current_ages().map_or(0, |ages| ages.max)
+    }
+}
+
+impl Universe {
+    /// Restart tracked ages from the current cells, after edits that break
+    /// the chain of ticks (resets, jumps to other generations).
+    pub(crate) fn restart_ages(&mut self) {
+        if self.ages.is_some() {
+            self.ages = Some(Ages::starting_at(&self.cells));
+        }
+    }
+
+    /// Account for an edit of the cell at storage index `idx`.
+    pub(crate) fn edit_age(&mut self, idx: usize) {
+        let alive = self.cells[idx];
+        if let Some(ages) = &mut self.ages {
+            let before = std::mem::replace(&mut ages.ages[idx], alive as u16);
+            if before == ages.max && !alive {
+                ages.max = ages.ages.iter().copied().max().unwrap_or(0);
+            }
+            ages.max = ages.max.max(alive as u16);
+        }
+    }
+}
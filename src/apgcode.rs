@@ -0,0 +1,53 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Pattern};
+
+#[wasm_bindgen]
+impl Pattern {
+    /// Decode an apgcode, the extended Wechsler format Catagolue names
+    /// objects by, e.g. `xs4_33` (block) or `xq4_153` (glider).
+    ///
+    /// Only the `x` codes spell out their cells; others such as `yl`
+    /// (linear growth) or `ov_` (oversized) are rejected. The code
+    /// becomes the pattern's name.
+    pub fn from_apgcode(code: &str) -> Result<Pattern, Error> {
+        let error = |reason| Error::InvalidPattern { line: 1, reason };
+        let code = code.trim();
+        let body = match code.split_once('_') {
+            Some((prefix, body)) if prefix.starts_with('x') => body,
+            _ => return Err(error("expected an 'xs', 'xp' or 'xq' apgcode")),
+        };
+
+        let mut cells = Vec::new();
+        for (strip, columns) in body.split('z').enumerate() {
+            let top = strip as u32 * 5;
+            let mut col = 0u32;
+            let mut chars = columns.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    'w' => col += 2,
+                    'x' => col += 3,
+                    'y' => {
+                        let run = chars
+                            .next()
+                            .and_then(|c| c.to_digit(36))
+                            .ok_or_else(|| error("expected a run length after 'y'"))?;
+                        col += 4 + run;
+                    }
+                    _ => {
+                        let bits = c
+                            .to_digit(32)
+                            .ok_or_else(|| error("unexpected character in apgcode"))?;
+                        cells.extend(
+                            (0..5)
+                                .filter(|bit| bits & (1 << bit) != 0)
+                                .map(|bit| (top + bit, col)),
+                        );
+                        col += 1;
+                    }
+                }
+            }
+        }
+        Ok(Pattern::from_cells(code, cells))
+    }
+}
@@ -0,0 +1,63 @@
+use fixedbitset::FixedBitSet;
+
+use crate::hibernate;
+
+/// Most spare buffers kept around for reuse.
+const MAX_SPARE: usize = 4;
+
+/// Spare cell buffers kept across resizes and resets, so parameter sweeps
+/// that keep reshaping a universe don't churn (and fragment) the allocator.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Arena {
+    spare: Vec<FixedBitSet>,
+}
+
+impl Arena {
+    /// A cleared buffer of `len` cells, reusing a spare one when possible.
+    pub(crate) fn take(&mut self, len: usize) -> FixedBitSet {
+        let reusable = self
+            .spare
+            .iter()
+            .position(|bits| bits.len() == len)
+            .or_else(|| (0..self.spare.len()).max_by_key(|&i| self.spare[i].len()));
+
+        match reusable {
+            Some(i) => {
+                let mut bits = self.spare.swap_remove(i);
+                resize_in_place(&mut bits, len);
+                bits
+            }
+            None => FixedBitSet::with_capacity(len),
+        }
+    }
+
+    /// Bytes held by the spare buffers.
+    pub(crate) fn byte_len(&self) -> usize {
+        self.spare.iter().map(hibernate::bitset_byte_len).sum()
+    }
+
+    /// Hand a buffer back for later reuse.
+    pub(crate) fn recycle(&mut self, bits: FixedBitSet) {
+        if bits.is_empty() {
+            return;
+        }
+        if self.spare.len() == MAX_SPARE {
+            // keep the larger buffers, they can serve more requests
+            let smallest = (0..MAX_SPARE)
+                .min_by_key(|&i| self.spare[i].len())
+                .expect("spare list is full");
+            if self.spare[smallest].len() >= bits.len() {
+                return;
+            }
+            self.spare.swap_remove(smallest);
+        }
+        self.spare.push(bits);
+    }
+}
+
+/// Clear `bits` and give it `len` cells, keeping its allocation when it
+/// is large enough (`FixedBitSet` never shrinks its buffer).
+pub(crate) fn resize_in_place(bits: &mut FixedBitSet, len: usize) {
+    bits.clone_from(&FixedBitSet::new());
+    bits.grow(len);
+}
@@ -0,0 +1,175 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// Births and deaths of edge cells that had live neighbours wrapped
+/// around from the opposite edge, see `Universe::boundary_traffic()`.
+///
+/// A corner cell's diagonal neighbour wraps across two edges and counts
+/// for both.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BoundaryTraffic {
+    pub north_births: u32,
+    pub north_deaths: u32,
+    pub south_births: u32,
+    pub south_deaths: u32,
+    pub west_births: u32,
+    pub west_deaths: u32,
+    pub east_births: u32,
+    pub east_deaths: u32,
+    /// Edge cells whose next state would have been different with the
+    /// wrapped neighbours dead, as on an unbounded plane. Includes cells
+    /// that stayed the same only because of the wrap.
+    pub distorted: u32,
+}
+
+impl BoundaryTraffic {
+    fn add(&mut self, other: &BoundaryTraffic) {
+        self.north_births += other.north_births;
+        self.north_deaths += other.north_deaths;
+        self.south_births += other.south_births;
+        self.south_deaths += other.south_deaths;
+        self.west_births += other.west_births;
+        self.west_deaths += other.west_deaths;
+        self.east_births += other.east_births;
+        self.east_deaths += other.east_deaths;
+        self.distorted += other.distorted;
+    }
+}
+
+/// Traffic of the last tick and since the last reset.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct BoundaryTracker {
+    last: BoundaryTraffic,
+    total: BoundaryTraffic,
+}
+
+const NORTH: u8 = 1;
+const SOUTH: u8 = 2;
+const WEST: u8 = 4;
+const EAST: u8 = 8;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Start or stop counting births and deaths that depend on the torus
+    /// wrapping, to judge how much the topology distorts a run compared
+    /// to an unbounded one. Counting costs a pass over the edge cells
+    /// every tick.
+    pub fn set_track_boundary_traffic(&mut self, enabled: bool) {
+        self.boundary = enabled.then(BoundaryTracker::default);
+    }
+
+    pub fn is_tracking_boundary_traffic(&self) -> bool {
+        self.boundary.is_some()
+    }
+
+    /// Boundary traffic of the most recent tick; all zero when not
+    /// tracked.
+    pub fn boundary_traffic(&self) -> BoundaryTraffic {
+        self.boundary
+            .map_or_else(BoundaryTraffic::default, |b| b.last)
+    }
+
+    /// Boundary traffic summed over the ticks since tracking started or
+    /// the universe was last reset.
+    pub fn boundary_traffic_total(&self) -> BoundaryTraffic {
+        self.boundary
+            .map_or_else(BoundaryTraffic::default, |b| b.total)
+    }
+}
+
+impl Universe {
+    /// Count the boundary traffic of the tick from `previous` to the
+    /// current cells.
+    pub(crate) fn record_boundary_traffic(&mut self, previous: &FixedBitSet) {
+        let mut tracker = match self.boundary {
+            Some(tracker) if self.width > 0 && self.height > 0 => tracker,
+            _ => return,
+        };
+        let mut traffic = BoundaryTraffic::default();
+        let (last_row, last_col) = (self.height - 1, self.width - 1);
+        for row in 0..self.height {
+            if row == 0 || row == last_row {
+                for col in 0..self.width {
+                    self.count_edge_cell(previous, row, col, &mut traffic);
+                }
+            } else {
+                self.count_edge_cell(previous, row, 0, &mut traffic);
+                if last_col > 0 {
+                    self.count_edge_cell(previous, row, last_col, &mut traffic);
+                }
+            }
+        }
+        tracker.last = traffic;
+        tracker.total.add(&traffic);
+        self.boundary = Some(tracker);
+    }
+
+    /// Restart the totals, e.g. after a reset.
+    pub(crate) fn restart_boundary_traffic(&mut self) {
+        if self.boundary.is_some() {
+            self.boundary = Some(BoundaryTracker::default());
+        }
+    }
+
+    fn count_edge_cell(
+        &self,
+        previous: &FixedBitSet,
+        row: u32,
+        col: u32,
+        traffic: &mut BoundaryTraffic,
+    ) {
+        // live neighbours that do not wrap, as a 3 x 3 block (see
+        // `Rule::apply()`), and the edges the others wrap across
+        let (mut local, mut edges) = (0, 0);
+        for bit in 0..9 {
+            if bit == 4 {
+                continue;
+            }
+            let (r, c) = (row as i64 + bit / 3 - 1, col as i64 + bit % 3 - 1);
+            let mut wraps = 0;
+            if r < 0 {
+                wraps |= NORTH;
+            } else if r >= self.height as i64 {
+                wraps |= SOUTH;
+            }
+            if c < 0 {
+                wraps |= WEST;
+            } else if c >= self.width as i64 {
+                wraps |= EAST;
+            }
+            let idx = match self.resolve(r, c) {
+                Some((r, c)) => self.get_index(r, c),
+                None => continue,
+            };
+            if previous[idx] {
+                if wraps == 0 {
+                    local |= 1 << bit;
+                }
+                edges |= wraps;
+            }
+        }
+
+        let idx = self.get_index(row, col);
+        let (before, after) = (previous[idx], self.cells[idx]);
+        if self.rule.apply(local | (before as u16) << 4) != after {
+            traffic.distorted += 1;
+        }
+        if before == after {
+            return;
+        }
+        let counters = [
+            (NORTH, &mut traffic.north_births, &mut traffic.north_deaths),
+            (SOUTH, &mut traffic.south_births, &mut traffic.south_deaths),
+            (WEST, &mut traffic.west_births, &mut traffic.west_deaths),
+            (EAST, &mut traffic.east_births, &mut traffic.east_deaths),
+        ];
+        for (edge, births, deaths) in counters {
+            if edges & edge != 0 {
+                *if after { births } else { deaths } += 1;
+            }
+        }
+    }
+}
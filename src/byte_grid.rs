@@ -0,0 +1,99 @@
+use crate::{check_dimensions, Error};
+
+/// Cells of a byte each in row-major order, for the multi-state automata
+/// whose states do not fit the bitsets of `Universe`.
+#[derive(Clone, Debug)]
+pub(crate) struct ByteGrid {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) cells: Vec<u8>,
+    scratch: Vec<u8>,
+}
+
+impl ByteGrid {
+    pub(crate) fn new(height: u32, width: u32) -> Result<ByteGrid, Error> {
+        check_dimensions(width, height)?;
+        Ok(ByteGrid {
+            width,
+            height,
+            cells: vec![0; width as usize * height as usize],
+            scratch: Vec::new(),
+        })
+    }
+
+    pub(crate) fn index(&self, row: u32, column: u32) -> Result<usize, Error> {
+        if row >= self.height || column >= self.width {
+            return Err(Error::OutOfBounds {
+                row: row as u64,
+                column: column as u64,
+                width: self.width,
+                height: self.height,
+            });
+        }
+        Ok(row as usize * self.width as usize + column as usize)
+    }
+
+    /// Set each listed `(row, column)` cell to `state`.
+    ///
+    /// Fails without changing anything when a cell is outside the grid.
+    pub(crate) fn set_cells(&mut self, cells: &[(u32, u32)], state: u8) -> Result<(), Error> {
+        for &(row, column) in cells {
+            self.index(row, column)?;
+        }
+        for &(row, column) in cells {
+            let idx = self.index(row, column)?;
+            self.cells[idx] = state;
+        }
+        Ok(())
+    }
+
+    /// Replace every cell by `next(state, neighbours)`, where `neighbours`
+    /// counts the cells of the Moore neighbourhood for which `counts`
+    /// holds. Opposite edges are joined when `wrap` is set; otherwise the
+    /// outside counts as cells for which `counts` does not hold.
+    pub(crate) fn step(
+        &mut self,
+        wrap: bool,
+        counts: impl Fn(u8) -> bool,
+        next: impl Fn(u8, u8) -> u8,
+    ) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut out = std::mem::take(&mut self.scratch);
+        out.clear();
+        out.reserve(self.cells.len());
+        // the rows and columns around each one, `None` beyond an edge
+        let around = |at: usize, len: usize| {
+            let before = if at > 0 {
+                Some(at - 1)
+            } else if wrap {
+                Some(len - 1)
+            } else {
+                None
+            };
+            let after = if at + 1 < len {
+                Some(at + 1)
+            } else if wrap {
+                Some(0)
+            } else {
+                None
+            };
+            [before, Some(at), after]
+        };
+        for row in 0..height {
+            let rows = around(row, height);
+            for col in 0..width {
+                let cols = around(col, width);
+                let mut neighbours = 0;
+                for (i, r) in rows.iter().enumerate() {
+                    for (j, c) in cols.iter().enumerate() {
+                        if let (Some(r), Some(c), false) = (r, c, i == 1 && j == 1) {
+                            neighbours += counts(self.cells[r * width + c]) as u8;
+                        }
+                    }
+                }
+                out.push(next(self.cells[row * width + col], neighbours));
+            }
+        }
+        self.scratch = std::mem::replace(&mut self.cells, out);
+    }
+}
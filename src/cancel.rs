@@ -0,0 +1,67 @@
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// A flag that makes long-running calls (`tick_many()`, `run_until()`,
+/// `goto_generation()`, ...) stop early with `Error::Cancelled`.
+///
+/// Code on the main thread cannot run while such a call is busy, so the
+/// flag is a word in wasm memory: a worker sharing that memory can set it
+/// with `Atomics.store()` at `ptr()`. It stays set until `reset()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    flag: Rc<AtomicU32>,
+}
+
+#[wasm_bindgen]
+impl CancelToken {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.flag.store(1, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.flag.store(0, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed) != 0
+    }
+
+    /// Address of the flag word in wasm memory; any non-zero value
+    /// cancels.
+    pub fn ptr(&self) -> *const u32 {
+        self.flag.as_ptr()
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Let `token` cancel this universe's long-running calls.
+    pub fn set_cancel_token(&mut self, token: &CancelToken) {
+        self.cancel = Some(token.clone());
+    }
+
+    pub fn clear_cancel_token(&mut self) {
+        self.cancel = None;
+    }
+}
+
+impl Universe {
+    /// Fails once the cancel token is set; `generations` is how far the
+    /// calling loop got.
+    pub(crate) fn check_cancelled(&self, generations: u32) -> Result<(), Error> {
+        match &self.cancel {
+            Some(token) if token.is_cancelled() => Err(Error::Cancelled { generations }),
+            _ => Ok(()),
+        }
+    }
+}
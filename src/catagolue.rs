@@ -0,0 +1,78 @@
+use wasm_bindgen::prelude::*;
+
+use crate::fetch::fetch_text;
+use crate::{Error, Pattern};
+
+const CATAGOLUE: &str = "https://catagolue.hatsya.com";
+const DEFAULT_RULE: &str = "b3s23";
+
+/// What the Catagolue census knows about an object: its cells, decoded
+/// from the apgcode, and the soups it was seen in.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct CatagolueObject {
+    pattern: Pattern,
+    samples: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl CatagolueObject {
+    pub fn pattern(&self) -> Pattern {
+        self.pattern.clone()
+    }
+
+    /// Sample soups as `symmetry/seed` ids, for `fetch_catagolue_soup()`.
+    pub fn samples(&self) -> Vec<String> {
+        self.samples.clone()
+    }
+}
+
+/// Look up `apgcode` in the Catagolue census of `rule` (B3/S23, written
+/// `b3s23`, by default).
+#[wasm_bindgen]
+pub async fn fetch_catagolue_object(
+    apgcode: String,
+    rule: Option<String>,
+) -> Result<CatagolueObject, Error> {
+    let pattern = Pattern::from_apgcode(&apgcode)?;
+    let rule = rule.as_deref().unwrap_or(DEFAULT_RULE);
+    let text = fetch_text(&format!(
+        "{}/textsamples/{}/{}",
+        CATAGOLUE,
+        apgcode.trim(),
+        rule
+    ))
+    .await?;
+    Ok(CatagolueObject {
+        pattern,
+        samples: parse_samples(&text),
+    })
+}
+
+/// Download the soup `sample` (a `symmetry/seed` id) of the census of
+/// `rule`, named after the sample.
+#[wasm_bindgen]
+pub async fn fetch_catagolue_soup(sample: String, rule: Option<String>) -> Result<Pattern, Error> {
+    let rule = rule.as_deref().unwrap_or(DEFAULT_RULE);
+    let text = fetch_text(&format!(
+        "{}/hashsoup/{}/{}",
+        CATAGOLUE,
+        sample.trim(),
+        rule
+    ))
+    .await?;
+    let mut pattern = Pattern::from_rle(&text)?;
+    if pattern.name().is_empty() {
+        pattern.set_name(sample.trim());
+    }
+    Ok(pattern)
+}
+
+/// One `symmetry/seed` id per non-empty line.
+fn parse_samples(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| line.contains('/'))
+        .map(str::to_owned)
+        .collect()
+}
@@ -0,0 +1,595 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Pattern};
+
+/// Widest and tallest `Thumbnail`, in pixels: 64 MiB of RGBA.
+const MAX_THUMBNAIL_SIZE: u32 = 4096;
+
+/// Kind of object a catalog pattern is.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PatternCategory {
+    /// Unchanged from one generation to the next.
+    StillLife,
+    /// Returns to its initial state after a fixed period, in place.
+    Oscillator,
+    /// Returns to its initial state displaced, so it travels.
+    Spaceship,
+    /// Oscillates while emitting spaceships.
+    Gun,
+    /// Small pattern that takes long to stabilize.
+    Methuselah,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    pattern: Pattern,
+    category: PatternCategory,
+    /// 0 when unknown or not periodic
+    period: u32,
+    /// Columns and rows travelled per period
+    displacement: (i32, i32),
+}
+
+/// A browsable set of patterns for picker UIs: lookup by index, category
+/// or name, with thumbnails.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct PatternCatalog {
+    entries: Vec<Entry>,
+}
+
+#[wasm_bindgen]
+impl PatternCatalog {
+    /// An empty catalog.
+    pub fn new() -> PatternCatalog {
+        PatternCatalog::default()
+    }
+
+    /// The patterns bundled with the crate.
+    pub fn builtin() -> PatternCatalog {
+        let mut catalog = PatternCatalog::new();
+        for builtin in BUILTIN {
+            let mut pattern =
+                Pattern::from_plaintext(builtin.cells).expect("built-in patterns are valid");
+            pattern.set_name(builtin.name);
+            let index = catalog.add(pattern, builtin.category);
+            let (dx, dy) = builtin.displacement;
+            catalog.set_motion(index, builtin.period, dx, dy);
+            catalog.set_discoverer(index, builtin.discoverer);
+        }
+        catalog
+    }
+
+    /// Add `pattern` and return its index.
+    pub fn add(&mut self, pattern: Pattern, category: PatternCategory) -> u32 {
+        self.entries.push(Entry {
+            pattern,
+            category,
+            period: 0,
+            displacement: (0, 0),
+        });
+        self.entries.len() as u32 - 1
+    }
+
+    /// Record that the pattern at `index` repeats every `period`
+    /// generations, moving `dx` columns and `dy` rows each time.
+    pub fn set_motion(&mut self, index: u32, period: u32, dx: i32, dy: i32) {
+        if let Some(entry) = self.entries.get_mut(index as usize) {
+            entry.period = period;
+            entry.displacement = (dx, dy);
+        }
+    }
+
+    /// Set the author in the metadata of the pattern at `index`.
+    pub fn set_discoverer(&mut self, index: u32, discoverer: &str) {
+        if let Some(entry) = self.entries.get_mut(index as usize) {
+            entry.pattern.meta.author = discoverer.to_owned();
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.entries.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn pattern(&self, index: u32) -> Option<Pattern> {
+        self.entry(index).map(|entry| entry.pattern.clone())
+    }
+
+    pub fn category(&self, index: u32) -> Option<PatternCategory> {
+        self.entry(index).map(|entry| entry.category)
+    }
+
+    /// Period of the pattern at `index`, 0 when unknown or not periodic.
+    pub fn period(&self, index: u32) -> Option<u32> {
+        self.entry(index).map(|entry| entry.period)
+    }
+
+    /// Speed of the pattern at `index` in the usual notation (`c/4`,
+    /// `2c/5`), or an empty string when it does not move.
+    pub fn speed(&self, index: u32) -> Option<String> {
+        self.entry(index).map(|entry| match entry.speed() {
+            Some((distance, period)) => format_speed(distance, period),
+            None => String::new(),
+        })
+    }
+
+    /// Author from the metadata of the pattern at `index`.
+    pub fn discoverer(&self, index: u32) -> Option<String> {
+        self.entry(index)
+            .map(|entry| entry.pattern.meta.author.clone())
+    }
+
+    /// Names of all patterns, by index.
+    pub fn names(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|entry| entry.pattern.name())
+            .collect()
+    }
+
+    /// Indices of the patterns in `category`.
+    pub fn in_category(&self, category: PatternCategory) -> Vec<u32> {
+        self.indices(|entry| entry.category == category)
+    }
+
+    /// Indices of the patterns whose name contains `query`, ignoring case:
+    /// exact matches first, then prefix matches, then the rest.
+    pub fn search(&self, query: &str) -> Vec<u32> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(u8, u32)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let name = entry.pattern.name().to_lowercase();
+                let rank = if name == query {
+                    0
+                } else if name.starts_with(&query) {
+                    1
+                } else if name.contains(&query) {
+                    2
+                } else {
+                    return None;
+                };
+                Some((rank, index as u32))
+            })
+            .collect();
+        matches.sort_unstable();
+        matches.into_iter().map(|(_, index)| index).collect()
+    }
+
+    /// Indices of the patterns matching every term of `query`, in catalog
+    /// order. Terms are separated by whitespace:
+    ///
+    /// * `name:<text>` or a bare word: the name contains the text,
+    /// * `discoverer:<text>`: the discoverer contains the text,
+    /// * `period:<n>`: the period, optionally compared with `<`, `<=`, `>`
+    ///   or `>=` (`period:>=3`),
+    /// * `speed:<speed>`: moves at that speed (`speed:c/4`),
+    /// * `bbox:<w>x<h>`: bounding box, optionally compared on both sides
+    ///   (`bbox:<=5x5`).
+    ///
+    /// Text matches ignore case. Fails on unknown fields or malformed
+    /// values.
+    pub fn search_patterns(&self, query: &str) -> Result<Vec<u32>, Error> {
+        let terms = query
+            .split_whitespace()
+            .map(Term::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.indices(|entry| terms.iter().all(|term| term.matches(entry))))
+    }
+
+    /// Preview of a pattern fitting in `max_size` x `max_size` pixels; see
+    /// `Thumbnail`. Fails for a `max_size` above 4096.
+    pub fn thumbnail(&self, index: u32, max_size: u32) -> Result<Option<Thumbnail>, Error> {
+        self.entry(index)
+            .map(|entry| Thumbnail::render(&entry.pattern, max_size))
+            .transpose()
+    }
+}
+
+impl Entry {
+    /// Reduced `(cells, generations)` of the speed, if the pattern moves.
+    fn speed(&self) -> Option<(u32, u32)> {
+        let (dx, dy) = self.displacement;
+        let distance = dx.unsigned_abs().max(dy.unsigned_abs());
+        if distance == 0 || self.period == 0 {
+            return None;
+        }
+        let divisor = gcd(distance, self.period);
+        Some((distance / divisor, self.period / divisor))
+    }
+}
+
+impl PatternCatalog {
+    fn entry(&self, index: u32) -> Option<&Entry> {
+        self.entries.get(index as usize)
+    }
+
+    fn indices(&self, filter: impl Fn(&Entry) -> bool) -> Vec<u32> {
+        (0..self.len())
+            .filter(|&index| filter(&self.entries[index as usize]))
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    /// Split a leading comparison operator off `value`.
+    fn parse(value: &str) -> (Comparison, &str) {
+        for (prefix, comparison) in [
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::Equal),
+        ] {
+            if let Some(rest) = value.strip_prefix(prefix) {
+                return (comparison, rest);
+            }
+        }
+        (Comparison::Equal, value)
+    }
+
+    fn holds(self, actual: u32, expected: u32) -> bool {
+        match self {
+            Comparison::Less => actual < expected,
+            Comparison::LessOrEqual => actual <= expected,
+            Comparison::Equal => actual == expected,
+            Comparison::GreaterOrEqual => actual >= expected,
+            Comparison::Greater => actual > expected,
+        }
+    }
+}
+
+/// One term of a `search_patterns()` query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Term {
+    Name(String),
+    Discoverer(String),
+    Period(Comparison, u32),
+    Speed(u32, u32),
+    BoundingBox(Comparison, u32, u32),
+}
+
+impl Term {
+    fn parse(term: &str) -> Result<Term, Error> {
+        let (field, value) = term.split_once(':').unwrap_or(("name", term));
+        match field.to_lowercase().as_str() {
+            "name" => Ok(Term::Name(value.to_lowercase())),
+            "discoverer" => Ok(Term::Discoverer(value.to_lowercase())),
+            "period" => {
+                let (comparison, value) = Comparison::parse(value);
+                let period = value
+                    .parse()
+                    .map_err(|_| Error::InvalidQuery("expected a period like 4 or >=3"))?;
+                Ok(Term::Period(comparison, period))
+            }
+            "speed" => parse_speed(value)
+                .map(|(distance, period)| Term::Speed(distance, period))
+                .ok_or(Error::InvalidQuery("expected a speed like c/4 or 2c/5")),
+            "bbox" => {
+                let (comparison, value) = Comparison::parse(value);
+                value
+                    .split_once('x')
+                    .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+                    .map(|(width, height)| Term::BoundingBox(comparison, width, height))
+                    .ok_or(Error::InvalidQuery(
+                        "expected a bounding box like 5x5 or <=5x5",
+                    ))
+            }
+            _ => Err(Error::InvalidQuery("unknown search field")),
+        }
+    }
+
+    fn matches(&self, entry: &Entry) -> bool {
+        match self {
+            Term::Name(text) => entry.pattern.name().to_lowercase().contains(text),
+            Term::Discoverer(text) => entry.pattern.meta.author.to_lowercase().contains(text),
+            Term::Period(comparison, period) => comparison.holds(entry.period, *period),
+            Term::Speed(distance, period) => entry.speed() == Some((*distance, *period)),
+            Term::BoundingBox(comparison, width, height) => {
+                comparison.holds(entry.pattern.width(), *width)
+                    && comparison.holds(entry.pattern.height(), *height)
+            }
+        }
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn format_speed(distance: u32, period: u32) -> String {
+    let cells = if distance == 1 {
+        "c".to_owned()
+    } else {
+        format!("{}c", distance)
+    };
+    if period == 1 {
+        cells
+    } else {
+        format!("{}/{}", cells, period)
+    }
+}
+
+/// Parse `c`, `c/4`, `2c/5`, ... into a reduced `(cells, generations)`.
+fn parse_speed(speed: &str) -> Option<(u32, u32)> {
+    let (cells, period) = speed.split_once('/').unwrap_or((speed, "1"));
+    let distance = match cells.strip_suffix('c')? {
+        "" => 1,
+        count => count.parse().ok()?,
+    };
+    let period: u32 = period.parse().ok()?;
+    if distance == 0 || period == 0 {
+        return None;
+    }
+    let divisor = gcd(distance, period);
+    Some((distance / divisor, period / divisor))
+}
+
+/// RGBA pixels (`width * height * 4` bytes, ready for `ImageData`) of a
+/// pattern preview: live cells black, dead cells white.
+///
+/// Small patterns are scaled up to whole pixels per cell; large ones are
+/// scaled down, a pixel being live when any cell it covers is.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Thumbnail {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Thumbnail {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> Vec<u8> {
+        self.pixels.clone()
+    }
+}
+
+impl Thumbnail {
+    fn render(pattern: &Pattern, max_size: u32) -> Result<Thumbnail, Error> {
+        if max_size > MAX_THUMBNAIL_SIZE {
+            return Err(Error::InvalidConfig(
+                "thumbnails are at most 4096 pixels wide",
+            ));
+        }
+        let max_size = max_size.max(1);
+        let extent = pattern.width().max(pattern.height()).max(1);
+        // cells per pixel when shrinking, pixels per cell when growing
+        let (shrink, grow) = if extent > max_size {
+            (extent.div_ceil(max_size), 1)
+        } else {
+            (1, max_size / extent)
+        };
+        let width = pattern.width().div_ceil(shrink) * grow;
+        let height = pattern.height().div_ceil(shrink) * grow;
+
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|area| area.checked_mul(4))
+            .ok_or(Error::DimensionsTooLarge {
+                width: width as u64,
+                height: height as u64,
+            })?;
+        let mut pixels = vec![0xff; len];
+        for &(row, col) in pattern.cells() {
+            let top = (row / shrink * grow) as usize;
+            let left = (col / shrink * grow) as usize;
+            for y in top..top + grow as usize {
+                for x in left..left + grow as usize {
+                    let offset = (y * width as usize + x) * 4;
+                    pixels[offset..offset + 3].fill(0);
+                }
+            }
+        }
+        Ok(Thumbnail {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+/// A bundled pattern; `period` 0 means not periodic.
+struct Builtin {
+    name: &'static str,
+    category: PatternCategory,
+    period: u32,
+    displacement: (i32, i32),
+    discoverer: &'static str,
+    cells: &'static str,
+}
+
+const BUILTIN: &[Builtin] = &[
+    Builtin {
+        name: "Block",
+        category: PatternCategory::StillLife,
+        period: 1,
+        displacement: (0, 0),
+        discoverer: "",
+        cells: "OO\nOO",
+    },
+    Builtin {
+        name: "Beehive",
+        category: PatternCategory::StillLife,
+        period: 1,
+        displacement: (0, 0),
+        discoverer: "",
+        cells: ".OO.\nO..O\n.OO.",
+    },
+    Builtin {
+        name: "Loaf",
+        category: PatternCategory::StillLife,
+        period: 1,
+        displacement: (0, 0),
+        discoverer: "",
+        cells: ".OO.\nO..O\n.O.O\n..O.",
+    },
+    Builtin {
+        name: "Boat",
+        category: PatternCategory::StillLife,
+        period: 1,
+        displacement: (0, 0),
+        discoverer: "",
+        cells: "OO.\nO.O\n.O.",
+    },
+    Builtin {
+        name: "Tub",
+        category: PatternCategory::StillLife,
+        period: 1,
+        displacement: (0, 0),
+        discoverer: "",
+        cells: ".O.\nO.O\n.O.",
+    },
+    Builtin {
+        name: "Blinker",
+        category: PatternCategory::Oscillator,
+        period: 2,
+        displacement: (0, 0),
+        discoverer: "John Conway",
+        cells: "OOO",
+    },
+    Builtin {
+        name: "Toad",
+        category: PatternCategory::Oscillator,
+        period: 2,
+        displacement: (0, 0),
+        discoverer: "Simon Norton",
+        cells: ".OOO\nOOO.",
+    },
+    Builtin {
+        name: "Beacon",
+        category: PatternCategory::Oscillator,
+        period: 2,
+        displacement: (0, 0),
+        discoverer: "John Conway",
+        cells: "OO..\nOO..\n..OO\n..OO",
+    },
+    Builtin {
+        name: "Pulsar",
+        category: PatternCategory::Oscillator,
+        period: 3,
+        displacement: (0, 0),
+        discoverer: "John Conway",
+        cells: "..OOO...OOO..\n\
+            .............\n\
+            O....O.O....O\n\
+            O....O.O....O\n\
+            O....O.O....O\n\
+            ..OOO...OOO..\n\
+            .............\n\
+            ..OOO...OOO..\n\
+            O....O.O....O\n\
+            O....O.O....O\n\
+            O....O.O....O\n\
+            .............\n\
+            ..OOO...OOO..",
+    },
+    Builtin {
+        name: "Pentadecathlon",
+        category: PatternCategory::Oscillator,
+        period: 15,
+        displacement: (0, 0),
+        discoverer: "John Conway",
+        cells: "..O....O..\nOO.OOOO.OO\n..O....O..",
+    },
+    Builtin {
+        name: "Glider",
+        category: PatternCategory::Spaceship,
+        period: 4,
+        displacement: (1, 1),
+        discoverer: "Richard K. Guy",
+        cells: ".O.\n..O\nOOO",
+    },
+    Builtin {
+        name: "Lightweight spaceship",
+        category: PatternCategory::Spaceship,
+        period: 4,
+        displacement: (-2, 0),
+        discoverer: "John Conway",
+        cells: ".O..O\nO....\nO...O\nOOOO.",
+    },
+    Builtin {
+        name: "Middleweight spaceship",
+        category: PatternCategory::Spaceship,
+        period: 4,
+        displacement: (-2, 0),
+        discoverer: "John Conway",
+        cells: "...O..\n.O...O\nO.....\nO....O\nOOOOO.",
+    },
+    Builtin {
+        name: "Heavyweight spaceship",
+        category: PatternCategory::Spaceship,
+        period: 4,
+        displacement: (-2, 0),
+        discoverer: "John Conway",
+        cells: "...OO..\n.O....O\nO......\nO.....O\nOOOOOO.",
+    },
+    Builtin {
+        name: "Gosper glider gun",
+        category: PatternCategory::Gun,
+        period: 30,
+        displacement: (0, 0),
+        discoverer: "Bill Gosper",
+        cells: "........................O...........\n\
+            ......................O.O...........\n\
+            ............OO......OO............OO\n\
+            ...........O...O....OO............OO\n\
+            OO........O.....O...OO..............\n\
+            OO........O...O.OO....O.O...........\n\
+            ..........O.....O.......O...........\n\
+            ...........O...O....................\n\
+            ............OO......................",
+    },
+    Builtin {
+        name: "R-pentomino",
+        category: PatternCategory::Methuselah,
+        period: 0,
+        displacement: (0, 0),
+        discoverer: "John Conway",
+        cells: ".OO\nOO.\n.O.",
+    },
+    Builtin {
+        name: "Diehard",
+        category: PatternCategory::Methuselah,
+        period: 0,
+        displacement: (0, 0),
+        discoverer: "",
+        cells: "......O.\nOO......\n.O...OOO",
+    },
+    Builtin {
+        name: "Acorn",
+        category: PatternCategory::Methuselah,
+        period: 0,
+        displacement: (0, 0),
+        discoverer: "Charles Corderman",
+        cells: ".O.....\n...O...\nOO..OOO",
+    },
+];
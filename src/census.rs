@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, HashMap};
+
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::components::Components;
+use crate::{Error, Neighborhood, Pattern, Rule, Universe};
+
+/// Objects `census()` knows by name in Life, by apgcode, with their
+/// period.
+const NAMED_OBJECTS: &[(&str, &str, u32)] = &[
+    ("xs4_33", "block", 1),
+    ("xs4_252", "tub", 1),
+    ("xs5_253", "boat", 1),
+    ("xs6_696", "beehive", 1),
+    ("xs6_356", "ship", 1),
+    ("xs6_25a4", "barge", 1),
+    ("xs6_39c", "aircraft carrier", 1),
+    ("xs6_bd", "snake", 1),
+    ("xs7_2596", "loaf", 1),
+    ("xs7_25ac", "long boat", 1),
+    ("xs7_178c", "eater 1", 1),
+    ("xs8_6996", "pond", 1),
+    ("xs8_69ic", "mango", 1),
+    ("xp2_7", "blinker", 2),
+    ("xp2_7e", "toad", 2),
+    ("xp2_318c", "beacon", 2),
+    ("xq4_153", "glider", 4),
+    ("xq4_6frc", "lightweight spaceship", 4),
+    ("xq4_27dee6", "middleweight spaceship", 4),
+    ("xq4_27deee6", "heavyweight spaceship", 4),
+];
+
+/// Longest period `census()` looks for in objects it has no name for.
+const MAX_PERIOD: u32 = 30;
+
+/// A cell shape moved to the top left corner, its cells sorted.
+type Shape = Vec<(u32, u32)>;
+
+/// Maps `(row, column)` of a shape whose last row and column are `bottom`
+/// and `right` to one of its orientations.
+type Orientation = fn(u32, u32, u32, u32) -> (u32, u32);
+
+/// Objects on the board by name, see `Universe::census()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    counts: BTreeMap<String, u32>,
+}
+
+#[wasm_bindgen]
+impl Census {
+    /// The names found, sorted.
+    pub fn names(&self) -> Vec<String> {
+        self.counts.keys().cloned().collect()
+    }
+
+    /// How many objects called `name` were found.
+    pub fn count(&self, name: &str) -> u32 {
+        self.counts.get(name).copied().unwrap_or(0)
+    }
+
+    /// Objects found, named or not.
+    pub fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+
+    /// The counts as a `Map` from name to count, for JavaScript.
+    pub fn to_map(&self) -> js_sys::Map {
+        let map = js_sys::Map::new();
+        for (name, &count) in &self.counts {
+            map.set(&JsValue::from_str(name), &JsValue::from(count));
+        }
+        map
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Split the live cells into objects and count them by kind, as soup
+    /// searches do.
+    ///
+    /// In Life, common objects are recognised in any phase and orientation
+    /// by their name (`"block"`, `"glider"`, ...). Other objects are run on
+    /// their own for up to 30 generations and counted under an apgcode
+    /// prefix: `xs` and the population for still lifes, `xp` and the period
+    /// for oscillators, `xq` and the period for spaceships, and `"other"`
+    /// when they do not repeat.
+    ///
+    /// Cells up to two rows and columns apart are taken as one object, so
+    /// an object whose pieces do not touch in some phase (the beacon, the
+    /// lightweight spaceship) still counts once. Such a group is split
+    /// into its 8-connected pieces again when all of those have names,
+    /// e.g. two blocks a cell apart.
+    ///
+    /// Fails only if the built-in table of named objects does not decode.
+    pub fn census(&self) -> Result<Census, Error> {
+        let cells = self.current_cells();
+        let (stride, lines) = self.storage_shape();
+        let groups = Components::label_within(&cells, stride, lines, self.edges(), 2);
+        let pieces = Components::label(&cells, stride, lines, self.edges());
+        let coordinates = |idx: usize| (idx as u32 / stride, idx as u32 % stride);
+        let mut objects = vec![Vec::new(); groups.count() as usize];
+        for idx in cells.ones() {
+            objects[groups.labels[idx] as usize - 1].push(idx);
+        }
+
+        let named = if self.rule == Rule::conway() && self.neighborhood == Neighborhood::Moore {
+            named_shapes()?
+        } else {
+            HashMap::new()
+        };
+        let shape_of = |cells: &[(u32, u32)]| normalized(unwrapped(cells.to_vec(), lines, stride));
+        let name_of = |cells: &[(u32, u32)]| named.get(&canonical(&shape_of(cells))).copied();
+
+        let mut census = Census::default();
+        for object in objects {
+            let mut count = |name: &str| *census.counts.entry(name.to_owned()).or_insert(0) += 1;
+            let whole: Vec<(u32, u32)> = object.iter().map(|&idx| coordinates(idx)).collect();
+            if let Some(name) = name_of(&whole) {
+                count(name);
+                continue;
+            }
+            let mut split: BTreeMap<u32, Vec<(u32, u32)>> = BTreeMap::new();
+            for &idx in &object {
+                split
+                    .entry(pieces.labels[idx])
+                    .or_default()
+                    .push(coordinates(idx));
+            }
+            let names: Option<Vec<&str>> = split.values().map(|piece| name_of(piece)).collect();
+            match names {
+                Some(names) => names.into_iter().for_each(&mut count),
+                None => count(&self.classify(&shape_of(&whole))),
+            }
+        }
+        Ok(census)
+    }
+}
+
+impl Universe {
+    /// The apgcode prefix of `shape` run on its own, or `"other"`.
+    fn classify(&self, shape: &[(u32, u32)]) -> String {
+        let phases = evolve(shape, self.rule, self.neighborhood, MAX_PERIOD);
+        let (start, first) = &phases[0];
+        let repeat = phases
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find(|(_, (_, phase))| phase == first);
+        match repeat {
+            Some((1, (offset, _))) if offset == start => format!("xs{}", shape.len()),
+            Some((period, (offset, _))) if offset == start => format!("xp{}", period),
+            Some((period, _)) => format!("xq{}", period),
+            None => "other".to_owned(),
+        }
+    }
+}
+
+/// Every phase and orientation of the named objects, by canonical form.
+fn named_shapes() -> Result<HashMap<Shape, &'static str>, Error> {
+    let mut shapes = HashMap::new();
+    for &(code, name, period) in NAMED_OBJECTS {
+        let pattern = Pattern::from_apgcode(code)?;
+        let cells: Vec<(u32, u32)> = pattern
+            .cell_coords()
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+        let phases = evolve(&cells, Rule::conway(), Neighborhood::Moore, period - 1);
+        for (_, phase) in phases {
+            shapes.insert(canonical(&phase), name);
+        }
+    }
+    Ok(shapes)
+}
+
+/// The top left corner and the shape of `cells` for each of the first
+/// `generations` generations, run alone in a universe wide enough that
+/// nothing wraps around in that time.
+fn evolve(
+    cells: &[(u32, u32)],
+    rule: Rule,
+    neighborhood: Neighborhood,
+    generations: u32,
+) -> Vec<((u32, u32), Shape)> {
+    let extent = cells
+        .iter()
+        .map(|&(row, col)| row.max(col) + 1)
+        .max()
+        .unwrap_or(0);
+    let margin = generations + 2;
+    let side = extent + 2 * margin;
+    let mut grid = FixedBitSet::with_capacity((side * side) as usize);
+    for &(row, col) in cells {
+        grid.insert(((row + margin) * side + col + margin) as usize);
+    }
+    let mut universe = Universe::from_cells(side, side, grid);
+    universe.rule = rule;
+    universe.neighborhood = neighborhood;
+
+    let mut phases = Vec::with_capacity(generations as usize + 1);
+    for generation in 0..=generations {
+        if generation > 0 {
+            universe.tick();
+        }
+        let cells: Vec<(u32, u32)> = universe
+            .live_cells()
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+        let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+        let left = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+        phases.push(((top, left), normalized(cells)));
+    }
+    phases
+}
+
+/// `cells` without the wrap, for an object lying across the edges of a
+/// `lines` x `stride` torus and spanning less than half of it.
+fn unwrapped(mut cells: Vec<(u32, u32)>, lines: u32, stride: u32) -> Vec<(u32, u32)> {
+    let (rows, columns): (Vec<u32>, Vec<u32>) = cells.iter().copied().unzip();
+    let across = |values: &[u32], size: u32| {
+        let low = values.iter().min().copied().unwrap_or(0);
+        let high = values.iter().max().copied().unwrap_or(0);
+        high - low > size / 2
+    };
+    let (across_rows, across_columns) = (across(&rows, lines), across(&columns, stride));
+    for (row, col) in cells.iter_mut() {
+        if across_rows && *row < lines / 2 {
+            *row += lines;
+        }
+        if across_columns && *col < stride / 2 {
+            *col += stride;
+        }
+    }
+    cells
+}
+
+/// `cells` moved to the top left corner and sorted.
+fn normalized(mut cells: Vec<(u32, u32)>) -> Shape {
+    let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+    for cell in cells.iter_mut() {
+        *cell = (cell.0 - top, cell.1 - left);
+    }
+    cells.sort_unstable();
+    cells
+}
+
+/// The least of the eight orientations of `shape`, the same for all of
+/// them.
+fn canonical(shape: &[(u32, u32)]) -> Shape {
+    let height = shape.iter().map(|&(row, _)| row + 1).max().unwrap_or(0);
+    let width = shape.iter().map(|&(_, col)| col + 1).max().unwrap_or(0);
+    let (bottom, right) = (height.saturating_sub(1), width.saturating_sub(1));
+    let orientations: [Orientation; 8] = [
+        |r, c, _, _| (r, c),
+        |r, c, b, _| (c, b - r),
+        |r, c, b, w| (b - r, w - c),
+        |r, c, _, w| (w - c, r),
+        |r, c, _, w| (r, w - c),
+        |r, c, b, _| (b - r, c),
+        |r, c, _, _| (c, r),
+        |r, c, b, w| (w - c, b - r),
+    ];
+    orientations
+        .iter()
+        .map(|orient| {
+            let mut cells: Shape = shape
+                .iter()
+                .map(|&(row, col)| orient(row, col, bottom, right))
+                .collect();
+            cells.sort_unstable();
+            cells
+        })
+        .min()
+        .unwrap_or_default()
+}
@@ -0,0 +1,291 @@
+use std::mem;
+use std::rc::Rc;
+
+use fixedbitset::{Block, FixedBitSet};
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// Storage words per tile. Checkpoints share unchanged tiles.
+const TILE_WORDS: usize = 16;
+const TILE_BITS: usize = TILE_WORDS * Block::BITS as usize;
+const TILE_BYTES: usize = TILE_WORDS * mem::size_of::<Block>();
+
+#[derive(Clone, Debug)]
+struct Checkpoint {
+    generation: u32,
+    tiles: Vec<Rc<[Block]>>,
+}
+
+/// Copy-on-write checkpoints of the cells, sorted by generation.
+///
+/// A new checkpoint only copies the tiles that changed since the
+/// checkpoint it is based on (tracked by `dirty`) and shares all others,
+/// so saving costs O(changed tiles) and memory grows with the changes.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Checkpoints {
+    list: Vec<Checkpoint>,
+    /// Index of the checkpoint the live cells are diffed against.
+    base: Option<usize>,
+    /// Tiles changed since `base`.
+    dirty: FixedBitSet,
+    /// Save a checkpoint every `interval` generations during `tick()`.
+    interval: u32,
+    /// Bytes held by the tiles of all checkpoints.
+    bytes: usize,
+}
+
+impl Checkpoints {
+    /// Record a change to cell `idx` made by `tick()`.
+    pub(crate) fn mark(&mut self, idx: usize) {
+        if self.base.is_some() {
+            self.dirty.insert(idx / TILE_BITS);
+        }
+    }
+
+    /// Record that any cell may have changed.
+    pub(crate) fn mark_all(&mut self) {
+        if self.base.is_some() {
+            self.dirty.insert_range(..);
+        }
+    }
+
+    fn contains(&self, generation: u32) -> bool {
+        self.list
+            .binary_search_by_key(&generation, |c| c.generation)
+            .is_ok()
+    }
+
+    fn clear(&mut self) {
+        self.list.clear();
+        self.base = None;
+        self.bytes = 0;
+    }
+
+    /// Drop the checkpoints from `generation` on.
+    fn truncate_from(&mut self, generation: u32) {
+        let keep = self.list.partition_point(|c| c.generation < generation);
+        while self.list.len() > keep {
+            self.remove(self.list.len() - 1);
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        let checkpoint = self.list.remove(index);
+        self.bytes -= checkpoint
+            .tiles
+            .iter()
+            .filter(|tile| Rc::strong_count(tile) == 1)
+            .count()
+            * TILE_BYTES;
+        self.base = match self.base {
+            Some(base) if base == index => None,
+            Some(base) if base > index => Some(base - 1),
+            base => base,
+        };
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Save a checkpoint of the current generation, replacing an existing
+    /// one for the same generation. Returns the generation.
+    ///
+    /// The oldest checkpoints are dropped when the total exceeds
+    /// `Limits::max_history_bytes`.
+    pub fn checkpoint(&mut self) -> u32 {
+        self.wake();
+        let generation = self.generation;
+        let words = self.cells.as_slice();
+        let tile_count = words.len().div_ceil(TILE_WORDS);
+        let checkpoints = &mut self.checkpoints;
+
+        let base = checkpoints.base.map(|base| &checkpoints.list[base]);
+        let mut fresh = 0;
+        let tiles: Vec<Rc<[Block]>> = (0..tile_count)
+            .map(|tile| match base {
+                Some(base) if !checkpoints.dirty.contains(tile) => Rc::clone(&base.tiles[tile]),
+                _ => {
+                    fresh += 1;
+                    let end = words.len().min((tile + 1) * TILE_WORDS);
+                    Rc::from(&words[tile * TILE_WORDS..end])
+                }
+            })
+            .collect();
+
+        let index = match checkpoints
+            .list
+            .binary_search_by_key(&generation, |c| c.generation)
+        {
+            Ok(index) => {
+                checkpoints.remove(index);
+                index
+            }
+            Err(index) => index,
+        };
+        checkpoints.bytes += fresh * TILE_BYTES;
+        checkpoints
+            .list
+            .insert(index, Checkpoint { generation, tiles });
+        checkpoints.base = Some(index);
+        checkpoints.dirty = FixedBitSet::with_capacity(tile_count);
+
+        let budget = self.limits.max_history_bytes;
+        while checkpoints.bytes > budget && checkpoints.list.len() > 1 {
+            checkpoints.remove(0);
+        }
+        if checkpoints.base.is_none() {
+            // the base itself was dropped, the next checkpoint is a full copy
+            checkpoints.dirty.insert_range(..);
+        }
+        generation
+    }
+
+    /// Save a checkpoint every `interval` generations while ticking
+    /// (0 disables automatic checkpoints).
+    pub fn set_checkpoint_interval(&mut self, interval: u32) {
+        self.checkpoints.interval = interval;
+    }
+
+    pub fn checkpoint_count(&self) -> usize {
+        self.checkpoints.list.len()
+    }
+
+    /// Bytes held by all checkpoints together.
+    pub fn checkpoint_bytes(&self) -> usize {
+        self.checkpoints.bytes
+    }
+
+    pub fn clear_checkpoints(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    /// Move to `generation`, restoring the closest checkpoint at or before
+    /// it when that saves ticks (always when going back), then ticking
+    /// forward the rest of the way.
+    pub fn goto_generation(&mut self, generation: u32) -> Result<(), Error> {
+        self.wake();
+        let list = &self.checkpoints.list;
+        let closest = list
+            .partition_point(|c| c.generation <= generation)
+            .checked_sub(1);
+        let from_checkpoint = closest.filter(|&index| {
+            generation < self.generation || list[index].generation > self.generation
+        });
+
+        if let Some(index) = from_checkpoint {
+            self.check_generations(generation - list[index].generation)?;
+            self.restore_checkpoint(index);
+        } else if generation < self.generation {
+            return Err(Error::NoCheckpoint(generation));
+        }
+
+        let steps = generation - self.generation;
+        self.check_generations(steps)?;
+        for done in 0..steps {
+            self.progress_step(done, steps)?;
+            self.tick();
+        }
+        self.report_progress(steps, steps);
+        Ok(())
+    }
+
+    /// One byte per cell, in storage order: 1 where the cell differs
+    /// from what it was at the earlier `generation`, 0 elsewhere.
+    ///
+    /// The reference state is rebuilt from the closest checkpoint at or
+    /// before `generation` without touching the universe itself. Fails
+    /// while noise is set, since replaying would draw differently than
+    /// the run did.
+    pub fn changes_since(&mut self, generation: u32) -> Result<Vec<u8>, Error> {
+        self.wake();
+        if generation == self.generation {
+            return Ok(vec![0; self.cells.len()]);
+        }
+        if self.noise.is_some() {
+            return Err(Error::InvalidConfig("noisy generations cannot be replayed"));
+        }
+        let list = &self.checkpoints.list;
+        let index = list
+            .partition_point(|c| c.generation <= generation)
+            .checked_sub(1)
+            .filter(|_| generation <= self.generation)
+            .ok_or(Error::NoCheckpoint(generation))?;
+        let checkpoint = &list[index];
+        self.check_generations(generation - checkpoint.generation)?;
+
+        let mut cells = FixedBitSet::with_capacity(self.cells.len());
+        let words = cells.as_mut_slice();
+        for (tile, chunk) in checkpoint.tiles.iter().zip(words.chunks_mut(TILE_WORDS)) {
+            chunk.copy_from_slice(tile);
+        }
+        let mut reference = Universe::from_cells(self.width, self.height, cells);
+        reference.order = self.order;
+        reference.engine = self.engine;
+        reference.rule = self.rule;
+        reference.topology = self.topology;
+        reference.neighborhood = self.neighborhood;
+        let steps = generation - checkpoint.generation;
+        for done in 0..steps {
+            self.progress_step(done, steps)?;
+            reference.tick();
+        }
+        self.report_progress(steps, steps);
+
+        Ok((0..self.cells.len())
+            .map(|idx| (self.cells[idx] != reference.cells[idx]) as u8)
+            .collect())
+    }
+}
+
+impl Universe {
+    fn restore_checkpoint(&mut self, index: usize) {
+        let checkpoint = &self.checkpoints.list[index];
+        let words = self.cells.as_mut_slice();
+        for (tile, chunk) in checkpoint.tiles.iter().zip(words.chunks_mut(TILE_WORDS)) {
+            chunk.copy_from_slice(tile);
+        }
+        self.generation = checkpoint.generation;
+        self.rehash();
+        self.rewind.clear();
+        self.forget_states();
+        self.restart_ages();
+        self.checkpoints.base = Some(index);
+        self.checkpoints.dirty.clear();
+    }
+
+    /// Called by `tick()` once the new generation is in place.
+    pub(crate) fn auto_checkpoint(&mut self) {
+        let interval = self.checkpoints.interval;
+        if interval != 0
+            && self.generation.is_multiple_of(interval)
+            && !self.checkpoints.contains(self.generation)
+        {
+            self.checkpoint();
+        }
+    }
+
+    /// Record an edit that did not come from `tick()`: checkpoints at or
+    /// after the current generation no longer describe this history.
+    pub(crate) fn invalidate_checkpoints(&mut self, idx: Option<usize>) {
+        let checkpoints = &mut self.checkpoints;
+        checkpoints.truncate_from(self.generation);
+        match idx {
+            Some(idx) => checkpoints.mark(idx),
+            None => checkpoints.dirty.insert_range(..),
+        }
+    }
+
+    /// Drop all checkpoints, e.g. when the dimensions or layout change.
+    pub(crate) fn reset_checkpoints(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    pub(crate) fn check_generations(&self, requested: u32) -> Result<(), Error> {
+        let limit = self.limits.max_generations_per_call;
+        if requested > limit {
+            return Err(Error::GenerationLimit { requested, limit });
+        }
+        Ok(())
+    }
+}
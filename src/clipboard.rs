@@ -0,0 +1,148 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Pattern, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// The live cells of the `rows` x `columns` rectangle whose top left
+    /// corner is `(row, column)`, as a pattern trimmed to their bounding
+    /// box like `to_rle()`; `Pattern::to_rle()` puts it on the clipboard.
+    ///
+    /// Fails when the rectangle does not fit inside the universe.
+    pub fn copy_region(
+        &self,
+        row: u32,
+        column: u32,
+        rows: u32,
+        columns: u32,
+    ) -> Result<Pattern, Error> {
+        self.check_region(row, column, rows, columns)?;
+        let cells = self.current_cells();
+        let mut live = Vec::new();
+        for r in row..row + rows {
+            for c in column..column + columns {
+                if cells[self.get_index(r, c)] {
+                    live.push((r, c));
+                }
+            }
+        }
+        Ok(Pattern::from_cells("", live))
+    }
+
+    /// `copy_region()`, then kill every cell of the rectangle.
+    pub fn cut_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        rows: u32,
+        columns: u32,
+    ) -> Result<Pattern, Error> {
+        let pattern = self.copy_region(row, column, rows, columns)?;
+        self.fill_region(row, column, rows, columns, |_, _| false);
+        Ok(pattern)
+    }
+
+    /// A new `rows` x `columns` universe holding that rectangle, as its
+    /// current and initial state, with this universe's rule, topology,
+    /// neighbourhood and engine; `to_rle()` then saves the structure.
+    ///
+    /// Fails when the rectangle does not fit inside the universe.
+    pub fn crop(&self, row: u32, column: u32, rows: u32, columns: u32) -> Result<Universe, Error> {
+        self.check_region(row, column, rows, columns)?;
+        let cells = self.current_cells();
+        let mut cropped = FixedBitSet::with_capacity(rows as usize * columns as usize);
+        for r in 0..rows {
+            for c in 0..columns {
+                if cells[self.get_index(row + r, column + c)] {
+                    cropped.insert((r * columns + c) as usize);
+                }
+            }
+        }
+        let mut universe = Universe::from_cells(columns, rows, cropped);
+        universe.rule = self.rule;
+        universe.topology = self.topology;
+        universe.neighborhood = self.neighborhood;
+        universe.engine = self.engine;
+        Ok(universe)
+    }
+
+    /// Replace the cells under `pattern`'s bounding box, its top left
+    /// corner at `(row, column)`, by the pattern's. Unlike
+    /// `place_pattern()`, which only adds live cells, the pattern's dead
+    /// cells kill those under them.
+    ///
+    /// Fails without changing anything unless the pattern fits.
+    pub fn paste(&mut self, pattern: &Pattern, row: u32, column: u32) -> Result<(), Error> {
+        let (rows, columns) = (pattern.height(), pattern.width());
+        self.check_region(row, column, rows, columns)?;
+        let mut live = pattern.cells().iter().peekable();
+        self.fill_region(row, column, rows, columns, |r, c| {
+            live.next_if(|&&cell| cell == (r, c)).is_some()
+        });
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// Fail unless the `rows` x `columns` rectangle at `(row, column)` is
+    /// inside the universe; empty rectangles always fit.
+    pub(crate) fn check_region(
+        &self,
+        row: u32,
+        column: u32,
+        rows: u32,
+        columns: u32,
+    ) -> Result<(), Error> {
+        if rows == 0 || columns == 0 {
+            return Ok(());
+        }
+        self.check_bounds(row as u64, column as u64)?;
+        self.check_bounds(
+            row as u64 + rows as u64 - 1,
+            column as u64 + columns as u64 - 1,
+        )
+    }
+
+    /// Set each cell of a rectangle checked by `check_region()` to
+    /// `alive(r, c)`, `(r, c)` its offset in the rectangle, visiting them
+    /// row by row. The initial state is left alone.
+    pub(crate) fn fill_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        rows: u32,
+        columns: u32,
+        mut alive: impl FnMut(u32, u32) -> bool,
+    ) {
+        if rows == 0 || columns == 0 {
+            return;
+        }
+        self.wake();
+        self.forget_states();
+        for r in 0..rows {
+            for c in 0..columns {
+                let idx = self.get_index(row + r, column + c);
+                self.write_cell(idx, alive(r, c));
+            }
+        }
+    }
+
+    /// Set one cell and keep the population, hash, ages and checkpoints
+    /// in step; callers wake the universe and forget its states first.
+    pub(crate) fn write_cell(&mut self, idx: usize, alive: bool) {
+        if self.cells[idx] == alive {
+            return;
+        }
+        self.cells.set(idx, alive);
+        self.flip_hash(idx);
+        self.edit_age(idx);
+        if alive {
+            self.population += 1;
+        } else {
+            self.population -= 1;
+        }
+        self.invalidate_checkpoints(Some(idx));
+        self.follow_edit(idx);
+    }
+}
@@ -0,0 +1,294 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::rng::{self, Xoshiro256};
+use crate::utils::now_ms;
+#[cfg(feature = "hashlife")]
+use crate::HashLifeUniverse;
+use crate::{check_dimensions, Engine, Error, Limits, Rule, Universe};
+
+/// Workload for `compare_engines()`: a seeded random soup evolved for a
+/// number of generations.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ComparisonConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Probability of each cell starting alive.
+    pub density: f64,
+    pub seed: u64,
+    pub generations: u32,
+}
+
+#[wasm_bindgen]
+impl ComparisonConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        width: u32,
+        height: u32,
+        density: f64,
+        seed: u64,
+        generations: u32,
+    ) -> ComparisonConfig {
+        ComparisonConfig {
+            width,
+            height,
+            density,
+            seed,
+            generations,
+        }
+    }
+}
+
+/// How one engine did on the workload.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EngineResult {
+    pub engine: Engine,
+    pub elapsed_ms: f64,
+    pub generations_per_second: f64,
+    /// Hash of the final state.
+    pub final_hash: u64,
+    /// Whether the final state equals the one of the first engine.
+    pub matches_reference: bool,
+}
+
+/// Results of `compare_engines()`, one entry per available engine, the
+/// first one being the reference.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonReport {
+    results: Vec<EngineResult>,
+}
+
+#[wasm_bindgen]
+impl ComparisonReport {
+    pub fn results(&self) -> Vec<EngineResult> {
+        self.results.clone()
+    }
+
+    /// Whether every engine produced the same final state.
+    pub fn consistent(&self) -> bool {
+        self.results.iter().all(|result| result.matches_reference)
+    }
+}
+
+/// Run the same seeded workload through every available engine, timing
+/// each and checking that they all end in the same state.
+///
+/// With the `hashlife` feature that includes `HashLifeUniverse`, whose
+/// run starts from the soup repeated around the board, so the board
+/// evolves as the torus of the other engines does.
+#[wasm_bindgen]
+pub fn compare_engines(config: &ComparisonConfig) -> Result<ComparisonReport, Error> {
+    let limit = Limits::default().max_generations_per_call;
+    if config.generations > limit {
+        return Err(Error::GenerationLimit {
+            requested: config.generations,
+            limit,
+        });
+    }
+
+    check_dimensions(config.width, config.height)?;
+    let size = (config.width * config.height) as usize;
+    let soup = rng::random_cells(size, config.density, &mut Xoshiro256::new(config.seed));
+
+    let mut reference = None;
+    let mut results = Vec::new();
+    for engine in Engine::available() {
+        let (universe, elapsed_ms) = run_engine(engine, config, &soup)?;
+
+        let reference_cells = reference.get_or_insert_with(|| universe.cells.clone());
+        results.push(EngineResult {
+            engine,
+            elapsed_ms,
+            generations_per_second: if elapsed_ms > 0.0 {
+                config.generations as f64 * 1000.0 / elapsed_ms
+            } else {
+                f64::INFINITY
+            },
+            final_hash: universe.state_hash(),
+            matches_reference: universe.cells == *reference_cells,
+        });
+    }
+    Ok(ComparisonReport { results })
+}
+
+/// `soup` evolved by `engine` for the generations of `config`, and the
+/// milliseconds that took.
+fn run_engine(
+    engine: Engine,
+    config: &ComparisonConfig,
+    soup: &FixedBitSet,
+) -> Result<(Universe, f64), Error> {
+    let mut universe = Universe::from_cells(config.width, config.height, soup.clone());
+    #[cfg(feature = "hashlife")]
+    if engine == Engine::HashLife {
+        let (cells, elapsed_ms) = run_hashlife(config, soup)?;
+        universe.cells = cells;
+        universe.rehash();
+        return Ok((universe, elapsed_ms));
+    }
+    universe.set_engine(engine);
+
+    let start = now_ms();
+    for _ in 0..config.generations {
+        universe.tick();
+    }
+    Ok((universe, now_ms() - start))
+}
+
+/// `run_engine()` for `Engine::HashLife`, whose plane has no edges: the soup
+/// is tiled across it as far around the board as light travels in the
+/// generations run, so the board evolves exactly as the torus does.
+#[cfg(feature = "hashlife")]
+fn run_hashlife(
+    config: &ComparisonConfig,
+    soup: &FixedBitSet,
+) -> Result<(FixedBitSet, f64), Error> {
+    let margin = config.generations.saturating_mul(2);
+    check_dimensions(
+        config.width.saturating_add(margin),
+        config.height.saturating_add(margin),
+    )?;
+    let (width, height) = (config.width as i64, config.height as i64);
+    let margin = config.generations as i64;
+    // the first copy of `at` at or past `-margin`, every `size` cells
+    let copies = |at: i64, size: i64| {
+        let first = at - (at + margin) / size * size;
+        (first..size + margin).step_by(size as usize)
+    };
+
+    let mut universe = HashLifeUniverse::new();
+    for idx in soup.ones() {
+        let (row, col) = (idx as i64 / width, idx as i64 % width);
+        for row in copies(row, height) {
+            for col in copies(col, width) {
+                universe.set_cell(row, col, true)?;
+            }
+        }
+    }
+
+    let start = now_ms();
+    universe.step_by(config.generations as u64)?;
+    let elapsed_ms = now_ms() - start;
+
+    let window = universe.window(0, 0, config.height, config.width);
+    let mut cells = FixedBitSet::with_capacity(window.len());
+    for (idx, &alive) in window.iter().enumerate() {
+        cells.set(idx, alive == 1);
+    }
+    Ok((cells, elapsed_ms))
+}
+
+/// The same seed evolved in lockstep under two rules, see
+/// `compare_rules()`.
+#[wasm_bindgen]
+pub struct RuleComparison {
+    a: Universe,
+    b: Universe,
+    /// Fraction of cells that differ, one entry per generation starting
+    /// with the seed
+    divergence: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl RuleComparison {
+    pub fn rule_a(&self) -> Rule {
+        self.a.rule
+    }
+
+    pub fn rule_b(&self) -> Rule {
+        self.b.rule
+    }
+
+    /// Generations evolved since the seed.
+    pub fn generation(&self) -> u32 {
+        self.a.generation
+    }
+
+    /// Advance both universes by one generation.
+    pub fn tick(&mut self) {
+        self.a.tick();
+        self.b.tick();
+        self.divergence.push(self.current_divergence());
+    }
+
+    /// One byte per cell, in the seed's storage order: 1 where the cell
+    /// is alive under rule A.
+    pub fn cells_a(&self) -> Vec<u8> {
+        cell_bytes(&self.a.cells)
+    }
+
+    /// Like `cells_a()`, under rule B.
+    pub fn cells_b(&self) -> Vec<u8> {
+        cell_bytes(&self.b.cells)
+    }
+
+    /// One byte per cell: 1 where the two rules disagree right now.
+    pub fn difference(&self) -> Vec<u8> {
+        cell_bytes(&(&self.a.cells ^ &self.b.cells))
+    }
+
+    /// Number of cells the two rules disagree on right now.
+    pub fn differing_cells(&self) -> u32 {
+        self.a.cells.symmetric_difference_count(&self.b.cells) as u32
+    }
+
+    /// For every generation since the seed, the fraction of cells that
+    /// differ between the two rules, from 0 (identical) to 1.
+    pub fn divergence(&self) -> Vec<f64> {
+        self.divergence.clone()
+    }
+}
+
+impl RuleComparison {
+    fn current_divergence(&self) -> f64 {
+        let size = self.a.cells.len().max(1);
+        self.differing_cells() as f64 / size as f64
+    }
+}
+
+/// Evolve the current state of `seed` for `generations` under both rules
+/// side by side, recording how far the two drift apart. Both sides keep
+/// the seed's topology, neighbourhood, engine and storage order. The
+/// returned comparison can be ticked further; `seed` is left unchanged.
+///
+/// Fails when a rulestring does not parse or `generations` exceeds the
+/// seed's `Limits::max_generations_per_call`.
+#[wasm_bindgen]
+pub fn compare_rules(
+    seed: &mut Universe,
+    rule_a: &str,
+    rule_b: &str,
+    generations: u32,
+) -> Result<RuleComparison, Error> {
+    let (rule_a, rule_b) = (Rule::parse(rule_a)?, Rule::parse(rule_b)?);
+    seed.check_generations(generations)?;
+    seed.wake();
+
+    let evolve = |rule| {
+        let mut universe = Universe::from_cells(seed.width, seed.height, seed.cells.clone());
+        universe.order = seed.order;
+        universe.engine = seed.engine;
+        universe.topology = seed.topology;
+        universe.neighborhood = seed.neighborhood;
+        universe.rule = rule;
+        universe.rehash();
+        universe
+    };
+    let mut comparison = RuleComparison {
+        a: evolve(rule_a),
+        b: evolve(rule_b),
+        divergence: Vec::with_capacity(generations as usize + 1),
+    };
+    comparison.divergence.push(0.0);
+    for _ in 0..generations {
+        comparison.tick();
+    }
+    Ok(comparison)
+}
+
+fn cell_bytes(cells: &FixedBitSet) -> Vec<u8> {
+    (0..cells.len()).map(|idx| cells[idx] as u8).collect()
+}
@@ -0,0 +1,150 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::topology::Edge;
+use crate::Universe;
+
+/// Connected components (8-connected, across the edges as the universe's
+/// topology joins them) of the live cells in a `width` x `height` grid.
+///
+/// Indices are taken as `row * width + col`; pass the storage shape and
+/// `Universe::edges()` for column-major universes, since connectivity is
+/// symmetric.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Components {
+    /// Component label per cell: 0 for dead cells, `1..=sizes.len()` otherwise.
+    pub(crate) labels: Vec<u32>,
+    /// Number of cells in each component; `sizes[label - 1]`.
+    pub(crate) sizes: Vec<u32>,
+    /// Lowest cell index of each component; `anchors[label - 1]`.
+    pub(crate) anchors: Vec<u32>,
+}
+
+impl Components {
+    pub(crate) fn label(
+        cells: &FixedBitSet,
+        width: u32,
+        height: u32,
+        edges: (Edge, Edge),
+    ) -> Components {
+        Components::label_within(cells, width, height, edges, 1)
+    }
+
+    /// Like `label()`, but joining live cells up to `reach` rows and
+    /// columns apart, e.g. 2 for the pieces of an object that share a dead
+    /// neighbour.
+    pub(crate) fn label_within(
+        cells: &FixedBitSet,
+        width: u32,
+        height: u32,
+        edges: (Edge, Edge),
+        reach: u32,
+    ) -> Components {
+        let size = (width * height) as usize;
+        let mut labels = vec![0u32; size];
+        let mut sizes = Vec::new();
+        let mut anchors = Vec::new();
+        let mut stack = Vec::new();
+
+        for start in cells.ones() {
+            if labels[start] != 0 {
+                continue;
+            }
+            // cells are visited in index order, so `start` is the lowest
+            // index of its component
+            sizes.push(0);
+            anchors.push(start as u32);
+            let label = sizes.len() as u32;
+            labels[start] = label;
+            stack.push(start);
+
+            while let Some(idx) = stack.pop() {
+                sizes[label as usize - 1] += 1;
+                let row = idx as u32 / width;
+                let col = idx as u32 % width;
+                let reach = reach as i64;
+                for dr in -reach..=reach {
+                    for dc in -reach..=reach {
+                        let (c, flip_row) = match cross(col, dc, width, edges.0) {
+                            Some(crossed) => crossed,
+                            None => continue,
+                        };
+                        let (r, flip_col) = match cross(row, dr, height, edges.1) {
+                            Some(crossed) => crossed,
+                            None => continue,
+                        };
+                        let r = if flip_row { height - 1 - r } else { r };
+                        let c = if flip_col { width - 1 - c } else { c };
+                        let n = (r * width + c) as usize;
+                        if cells[n] && labels[n] == 0 {
+                            labels[n] = label;
+                            stack.push(n);
+                        }
+                    }
+                }
+            }
+        }
+
+        Components {
+            labels,
+            sizes,
+            anchors,
+        }
+    }
+}
+
+/// `at + offset` on a line of `size` cells ending in `edge`, and whether
+/// it crossed a flipping edge; `None` past a dead one.
+fn cross(at: u32, offset: i64, size: u32, edge: Edge) -> Option<(u32, bool)> {
+    let (size, target) = (size as i64, at as i64 + offset);
+    if (0..size).contains(&target) {
+        return Some((target as u32, false));
+    }
+    match edge {
+        Edge::Dead => None,
+        Edge::Mirror => Some((target.clamp(0, size - 1) as u32, false)),
+        Edge::Wrap => Some((target.rem_euclid(size) as u32, false)),
+        Edge::Flip => Some((target.rem_euclid(size) as u32, true)),
+    }
+}
+
+#[wasm_bindgen]
+impl Components {
+    /// The label of each cell, indexed like `cells()`: 0 for dead cells,
+    /// `1..=count()` for live ones; from JavaScript a `Uint32Array`.
+    pub fn labels(&self) -> Vec<u32> {
+        self.labels.clone()
+    }
+
+    /// Number of components.
+    pub fn count(&self) -> u32 {
+        self.sizes.len() as u32
+    }
+
+    /// Cells in each component, the one labelled `label` at `label - 1`.
+    pub fn sizes(&self) -> Vec<u32> {
+        self.sizes.clone()
+    }
+
+    /// Lowest cell index of each component, the one labelled `label` at
+    /// `label - 1`.
+    pub fn anchors(&self) -> Vec<u32> {
+        self.anchors.clone()
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Label the structures on the board: live cells touching, also
+    /// diagonally and across the edges `topology()` joins, share a label.
+    /// On a Klein bottle that includes the cells the flip brings next to
+    /// each other; dead edges join nothing. E.g. to
+    /// highlight the structure under the mouse, whose label is at
+    /// `row * width + column` in row-major storage, or to measure the
+    /// debris of a collision.
+    pub fn components(&self) -> Components {
+        let (stride, lines) = self.storage_shape();
+        Components::label(&self.current_cells(), stride, lines, self.edges())
+    }
+}
@@ -0,0 +1,23 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Storage indices (as in `cells()`) of the cells the most recent
+    /// tick flipped, in increasing order, so a renderer can redraw only
+    /// those. Empty after edits that restart the history.
+    pub fn deltas(&self) -> Vec<u32> {
+        self.deltas.clone()
+    }
+
+    /// Pointer to the `deltas()` buffer, valid until the next tick: read
+    /// `deltas_len()` `u32`s from it to avoid the copy.
+    pub fn deltas_ptr(&self) -> *const u32 {
+        self.deltas.as_ptr()
+    }
+
+    pub fn deltas_len(&self) -> usize {
+        self.deltas.len()
+    }
+}
@@ -0,0 +1,209 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// The shape `Universe::paint_at()` paints.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BrushShape {
+    /// The `2r + 1` cells wide square around the cell.
+    #[default]
+    Square,
+    /// The disc `fill_circle()` fills.
+    Round,
+}
+
+/// What `Universe::paint_at()` paints with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Brush {
+    shape: BrushShape,
+    radius: u32,
+}
+
+/// Drawing tools, so a freehand stroke is one call per mouse event rather
+/// than one per cell. Each sets the cells it covers to `alive`.
+#[wasm_bindgen]
+impl Universe {
+    /// The Bresenham line from `(r0, c0)` to `(r1, c1)`, both ends
+    /// included.
+    ///
+    /// Fails without changing anything when an end is outside the
+    /// universe.
+    pub fn draw_line(
+        &mut self,
+        r0: u32,
+        c0: u32,
+        r1: u32,
+        c1: u32,
+        alive: bool,
+    ) -> Result<(), Error> {
+        self.check_bounds(r0 as u64, c0 as u64)?;
+        self.check_bounds(r1 as u64, c1 as u64)?;
+        self.wake();
+        self.forget_states();
+        let (r1, c1) = (r1 as i64, c1 as i64);
+        let (mut r, mut c) = (r0 as i64, c0 as i64);
+        let (dr, dc) = (-(r1 - r).abs(), (c1 - c).abs());
+        let (step_r, step_c) = ((r1 - r).signum(), (c1 - c).signum());
+        let mut err = dc + dr;
+        loop {
+            let idx = self.get_index(r as u32, c as u32);
+            self.write_cell(idx, alive);
+            if (r, c) == (r1, c1) {
+                return Ok(());
+            }
+            let e2 = 2 * err;
+            if e2 >= dr {
+                err += dr;
+                c += step_c;
+            }
+            if e2 <= dc {
+                err += dc;
+                r += step_r;
+            }
+        }
+    }
+
+    /// Every cell of the `rows` x `columns` rectangle whose top left
+    /// corner is `(row, column)`.
+    ///
+    /// Fails when the rectangle does not fit inside the universe.
+    pub fn fill_rect(
+        &mut self,
+        row: u32,
+        column: u32,
+        rows: u32,
+        columns: u32,
+        alive: bool,
+    ) -> Result<(), Error> {
+        self.check_region(row, column, rows, columns)?;
+        self.fill_region(row, column, rows, columns, |_, _| alive);
+        Ok(())
+    }
+
+    /// The border of the rectangle `fill_rect()` would fill.
+    pub fn outline_rect(
+        &mut self,
+        row: u32,
+        column: u32,
+        rows: u32,
+        columns: u32,
+        alive: bool,
+    ) -> Result<(), Error> {
+        self.check_region(row, column, rows, columns)?;
+        if rows == 0 || columns == 0 {
+            return Ok(());
+        }
+        let bottom = row + rows - 1;
+        let right = column + columns - 1;
+        self.fill_region(row, column, 1, columns, |_, _| alive);
+        self.fill_region(bottom, column, 1, columns, |_, _| alive);
+        self.fill_region(row, column, rows, 1, |_, _| alive);
+        self.fill_region(row, right, rows, 1, |_, _| alive);
+        Ok(())
+    }
+
+    /// The disc of cells within `radius` of `(row, column)`, traced with
+    /// the midpoint circle algorithm. Parts past the edges are left out.
+    ///
+    /// Fails when the centre is outside the universe.
+    pub fn fill_circle(
+        &mut self,
+        row: u32,
+        column: u32,
+        radius: u32,
+        alive: bool,
+    ) -> Result<(), Error> {
+        self.check_bounds(row as u64, column as u64)?;
+        self.wake();
+        self.forget_states();
+        let (row, column) = (row as i64, column as i64);
+        let (mut x, mut y) = (radius as i64, 0);
+        let mut err = 1 - x;
+        while x >= y {
+            for &(dr, half) in [(y, x), (-y, x), (x, y), (-x, y)].iter() {
+                self.fill_span(row + dr, column - half, column + half, alive);
+            }
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Set every listed cell, given as flat `[row, column, row, column,
+    /// ...]` pairs like `Pattern::cell_coords()`, in a single call; from
+    /// JavaScript a `Uint32Array`.
+    ///
+    /// Fails without changing anything when a cell is outside the
+    /// universe or a column is missing.
+    pub fn paint(&mut self, cells: &[u32], alive: bool) -> Result<(), Error> {
+        if cells.len() % 2 == 1 {
+            return Err(Error::InvalidConfig("cells come in row, column pairs"));
+        }
+        for pair in cells.chunks(2) {
+            self.check_bounds(pair[0] as u64, pair[1] as u64)?;
+        }
+        self.wake();
+        self.forget_states();
+        for pair in cells.chunks(2) {
+            let idx = self.get_index(pair[0], pair[1]);
+            self.write_cell(idx, alive);
+        }
+        Ok(())
+    }
+
+    /// Paint with `shape` and `radius` from now on; radius 0 is a single
+    /// cell whatever the shape.
+    pub fn set_brush(&mut self, shape: BrushShape, radius: u32) {
+        self.brush = Brush { shape, radius };
+    }
+
+    pub fn brush_shape(&self) -> BrushShape {
+        self.brush.shape
+    }
+
+    pub fn brush_radius(&self) -> u32 {
+        self.brush.radius
+    }
+
+    /// Dab the brush centred on `(row, column)`. Parts past the edges are
+    /// left out.
+    ///
+    /// Fails when the centre is outside the universe.
+    pub fn paint_at(&mut self, row: u32, column: u32, alive: bool) -> Result<(), Error> {
+        let Brush { shape, radius } = self.brush;
+        if shape == BrushShape::Round {
+            return self.fill_circle(row, column, radius, alive);
+        }
+        self.check_bounds(row as u64, column as u64)?;
+        self.wake();
+        self.forget_states();
+        let (row, column, radius) = (row as i64, column as i64, radius as i64);
+        for r in row - radius..=row + radius {
+            self.fill_span(r, column - radius, column + radius, alive);
+        }
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// Columns `from..=to` of `row`, clipped to the universe.
+    fn fill_span(&mut self, row: i64, from: i64, to: i64, alive: bool) {
+        if row < 0 || row >= self.height as i64 {
+            return;
+        }
+        let to = to.min(self.width as i64 - 1);
+        for column in from.max(0)..=to {
+            let idx = self.get_index(row as u32, column as u32);
+            self.write_cell(idx, alive);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{check_dimensions, packed, Entropy, Error};
+
+/// A one-dimensional elementary cellular automaton, such as Rule 30 or
+/// Rule 110, shown as a scrolling history: the bottom row is the current
+/// generation and every row above it one generation older.
+///
+/// `cells()` lays the history out like `Universe::cells()`, so a canvas
+/// drawing a universe draws it unchanged. The row wraps around at its
+/// ends.
+#[wasm_bindgen]
+pub struct Elementary {
+    width: u32,
+    height: u32,
+    /// The rows as a ring, `newest` the current one
+    rows: FixedBitSet,
+    newest: u32,
+    rule: u8,
+    generation: u32,
+    /// `cells()` buffer
+    packed: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Elementary {
+    /// `height` rows of `width` cells under Wolfram rule `rule`, starting
+    /// from a single live cell in the middle of the bottom row. Fails with
+    /// `Error::DimensionsTooLarge` when it would have more cells than it
+    /// can address.
+    pub fn new(height: u32, width: u32, rule: u8) -> Result<Elementary, Error> {
+        check_dimensions(width, height)?;
+        let mut elementary = Elementary {
+            width,
+            height,
+            rows: FixedBitSet::with_capacity(width as usize * height as usize),
+            newest: height.saturating_sub(1),
+            rule,
+            generation: 0,
+            packed: Vec::new(),
+        };
+        if height > 0 && width > 0 {
+            let _ = elementary.set_cell(width / 2, true);
+        }
+        Ok(elementary)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// The Wolfram rule number: bit `4l + 2c + r` is the next state of a
+    /// cell that is `c`, with neighbours `l` and `r`.
+    pub fn rule(&self) -> u8 {
+        self.rule
+    }
+
+    /// Apply `rule` from the next tick on; the history stays.
+    pub fn set_rule(&mut self, rule: u8) {
+        self.rule = rule;
+    }
+
+    /// The cell in `column` of `row`, counting from the oldest row at the
+    /// top.
+    pub fn get_cell(&self, row: u32, column: u32) -> Result<bool, Error> {
+        self.check_bounds(row, column)?;
+        let ring_row = (self.newest + 1 + row) % self.height;
+        Ok(self.rows[self.index(ring_row, column)])
+    }
+
+    /// Set a cell of the current generation, the bottom row.
+    pub fn set_cell(&mut self, column: u32, alive: bool) -> Result<(), Error> {
+        self.check_bounds(self.height.saturating_sub(1), column)?;
+        let idx = self.index(self.newest, column);
+        self.rows.set(idx, alive);
+        Ok(())
+    }
+
+    /// Replace the current generation by live cells with probability
+    /// `density`, see `Universe::randomize()`.
+    pub fn randomize(&mut self, density: f64, entropy: &mut Entropy) {
+        for column in 0..self.width {
+            let idx = self.index(self.newest, column);
+            self.rows.set(idx, entropy.next_f64() < density);
+        }
+    }
+
+    /// Kill every cell, history included.
+    pub fn reset_cells(&mut self) {
+        self.rows.clear();
+        self.generation = 0;
+    }
+
+    /// Compute the next row from the current one and scroll the history
+    /// up by a row, dropping the oldest.
+    pub fn tick(&mut self) {
+        if self.height == 0 || self.width == 0 {
+            self.generation += 1;
+            return;
+        }
+        let width = self.width;
+        let current = self.newest;
+        let next = (current + 1) % self.height;
+        let cell = |column: u32| self.rows[self.index(current, column % width)] as u8;
+        let row: Vec<bool> = (0..width)
+            .map(|column| {
+                let pattern = cell(column + width - 1) << 2 | cell(column) << 1 | cell(column + 1);
+                self.rule >> pattern & 1 == 1
+            })
+            .collect();
+        for (column, &alive) in (0..width).zip(&row) {
+            let idx = self.index(next, column);
+            self.rows.set(idx, alive);
+        }
+        self.newest = next;
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+
+    /// Pointer to the history, oldest row first, byte-packed like
+    /// `Universe::cells()`; read `cells_byte_len()` bytes from it.
+    pub fn cells(&mut self) -> *const u8 {
+        let mut ordered = FixedBitSet::with_capacity(self.rows.len());
+        for row in 0..self.height {
+            let ring_row = (self.newest + 1 + row) % self.height;
+            for column in 0..self.width {
+                let idx = self.index(ring_row, column);
+                ordered.set(self.index(row, column), self.rows[idx]);
+            }
+        }
+        self.packed = packed::pack(&ordered);
+        self.packed.as_ptr()
+    }
+
+    pub fn cells_byte_len(&self) -> usize {
+        packed::packed_len(self.rows.len())
+    }
+}
+
+impl Elementary {
+    fn index(&self, ring_row: u32, column: u32) -> usize {
+        ring_row as usize * self.width as usize + column as usize
+    }
+
+    fn check_bounds(&self, row: u32, column: u32) -> Result<(), Error> {
+        if row < self.height && column < self.width {
+            return Ok(());
+        }
+        Err(Error::OutOfBounds {
+            row: row as u64,
+            column: column as u64,
+            width: self.width,
+            height: self.height,
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, LogLevel, Universe};
+
+/// Algorithm `tick()` uses to compute the next generation. All engines
+/// produce identical generations.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Engine {
+    /// Per-cell neighbor counting; the reference implementation.
+    Naive,
+    /// Whole words of cells at a time with shifts and bit-sliced adders,
+    /// see `simulate`.
+    #[default]
+    BitParallel,
+    /// WebAssembly SIMD, 16 cells per instruction. Only in builds with the
+    /// `simd` feature and the `simd128` target feature; elsewhere it runs
+    /// as `BitParallel`.
+    Simd,
+    /// `BitParallel` evaluating only the tiles of 32 lines by one storage
+    /// word that changed in the last generation, and their neighbours, so
+    /// settled regions cost nothing. Runs as `BitParallel` on topologies
+    /// other than the torus and dead edges.
+    ActiveTiles,
+    /// The memoized quadtree of `HashLifeUniverse`, which has no edges.
+    /// Only `compare_engines()` runs it, and only with the `hashlife`
+    /// feature; a `Universe` set to it ticks as `BitParallel`.
+    HashLife,
+}
+
+/// Whether this build contains the SIMD engine. A module built with
+/// `simd128` does not load in browsers without SIMD support, so pages
+/// pick the build with `wasm-feature-detect` before loading it.
+pub(crate) const SIMD: bool = cfg!(all(
+    feature = "simd",
+    target_arch = "wasm32",
+    target_feature = "simd128"
+));
+
+impl Engine {
+    /// Engines usable in this build.
+    pub fn available() -> Vec<Engine> {
+        let mut engines = vec![Engine::Naive, Engine::BitParallel, Engine::ActiveTiles];
+        if SIMD {
+            engines.push(Engine::Simd);
+        }
+        if cfg!(feature = "hashlife") {
+            engines.push(Engine::HashLife);
+        }
+        engines
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Engine::Naive => "naive",
+            Engine::BitParallel => "bit-parallel",
+            Engine::Simd => "simd",
+            Engine::ActiveTiles => "active-tiles",
+            Engine::HashLife => "hashlife",
+        }
+    }
+}
+
+/// Whether this build contains `Engine::Simd`.
+#[wasm_bindgen]
+pub fn simd_enabled() -> bool {
+    SIMD
+}
+
+#[wasm_bindgen]
+impl Universe {
+    pub fn engine(&self) -> Engine {
+        self.engine
+    }
+
+    /// `Universe::try_new()` using the SIMD engine when `simd` is set (the
+    /// page detected browser support) and this build contains it, and the
+    /// scalar default otherwise.
+    pub fn new_with_simd(height: u32, width: u32, simd: bool) -> Result<Universe, Error> {
+        let mut universe = Universe::try_new(height, width)?;
+        if simd && SIMD {
+            universe.engine = Engine::Simd;
+        }
+        Ok(universe)
+    }
+
+    /// Engines missing from this build fall back to the default.
+    pub fn set_engine(&mut self, engine: Engine) {
+        let engine = if Engine::available().contains(&engine) {
+            engine
+        } else {
+            log_at!(
+                LogLevel::Info,
+                "engine",
+                "{} is not in this build",
+                engine.name()
+            );
+            Engine::default()
+        };
+        log_at!(
+            LogLevel::Info,
+            "engine",
+            "{} -> {}",
+            self.engine.name(),
+            engine.name()
+        );
+        self.engine = engine;
+    }
+}
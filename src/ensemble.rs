@@ -0,0 +1,247 @@
+use wasm_bindgen::prelude::*;
+
+use crate::rng::{self, Xoshiro256};
+use crate::{check_dimensions, Error, Limits, Universe};
+
+/// Replicates for `run_ensemble()`: random soups that differ only in
+/// their seed, each run until it settles.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnsembleConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Probability of each cell starting alive.
+    pub density: f64,
+    /// Seed of the first replicate; replicate `i` uses `seed + i`.
+    pub seed: u64,
+    /// Generations after which a replicate that has not settled is
+    /// stopped.
+    pub max_generations: u32,
+}
+
+#[wasm_bindgen]
+impl EnsembleConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        width: u32,
+        height: u32,
+        density: f64,
+        seed: u64,
+        max_generations: u32,
+    ) -> EnsembleConfig {
+        EnsembleConfig {
+            width,
+            height,
+            density,
+            seed,
+            max_generations,
+        }
+    }
+}
+
+/// How a replicate ended.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GrowthClass {
+    /// Every cell died.
+    Extinct,
+    /// Settled into a still life.
+    Stable,
+    /// Settled into a cycle longer than one generation.
+    Oscillating,
+    /// Still changing at `max_generations`, with more cells than it
+    /// started with.
+    Growing,
+    /// Still changing at `max_generations`, without having grown.
+    Unsettled,
+}
+
+const GROWTH_CLASSES: [GrowthClass; 5] = [
+    GrowthClass::Extinct,
+    GrowthClass::Stable,
+    GrowthClass::Oscillating,
+    GrowthClass::Growing,
+    GrowthClass::Unsettled,
+];
+
+/// Outcome of one replicate.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Replicate {
+    pub seed: u64,
+    /// Generations until the final still life or cycle was entered, or
+    /// `max_generations` for a replicate that did not settle.
+    pub lifespan: u32,
+    pub initial_population: u32,
+    pub final_population: u32,
+    pub class: GrowthClass,
+}
+
+/// Summary statistics of one quantity over the replicates.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Distribution {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+}
+
+impl Distribution {
+    fn of(mut values: Vec<f64>) -> Distribution {
+        if values.is_empty() {
+            return Distribution::default();
+        }
+        values.sort_by(f64::total_cmp);
+        let n = values.len();
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n as f64;
+        Distribution {
+            min: values[0],
+            max: values[n - 1],
+            mean,
+            median: if n % 2 == 1 {
+                values[n / 2]
+            } else {
+                (values[n / 2 - 1] + values[n / 2]) / 2.0
+            },
+            std_dev: variance.sqrt(),
+        }
+    }
+}
+
+/// Results of `run_ensemble()`.
+///
+/// Reports of disjoint shards, e.g. computed in different workers with
+/// `run_ensemble_shard()`, combine with `merge()` into the report of the
+/// whole ensemble.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnsembleReport {
+    replicates: Vec<Replicate>,
+}
+
+#[wasm_bindgen]
+impl EnsembleReport {
+    /// Number of replicates run.
+    pub fn len(&self) -> usize {
+        self.replicates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replicates.is_empty()
+    }
+
+    /// Every replicate, ordered by seed.
+    pub fn replicates(&self) -> Vec<Replicate> {
+        self.replicates.clone()
+    }
+
+    pub fn lifespan(&self) -> Distribution {
+        self.distribution(|replicate| replicate.lifespan)
+    }
+
+    pub fn final_population(&self) -> Distribution {
+        self.distribution(|replicate| replicate.final_population)
+    }
+
+    /// Number of replicates that ended as `class`.
+    pub fn class_count(&self, class: GrowthClass) -> u32 {
+        self.replicates
+            .iter()
+            .filter(|replicate| replicate.class == class)
+            .count() as u32
+    }
+
+    /// Fraction of the replicates in each `GrowthClass`, in declaration
+    /// order.
+    pub fn class_fractions(&self) -> Vec<f64> {
+        let n = self.replicates.len().max(1) as f64;
+        GROWTH_CLASSES
+            .iter()
+            .map(|&class| self.class_count(class) as f64 / n)
+            .collect()
+    }
+
+    /// Add the replicates of another shard of the same ensemble.
+    pub fn merge(&mut self, other: &EnsembleReport) {
+        self.replicates.extend_from_slice(&other.replicates);
+        self.replicates.sort_by_key(|replicate| replicate.seed);
+        self.replicates.dedup_by_key(|replicate| replicate.seed);
+    }
+}
+
+impl EnsembleReport {
+    fn distribution(&self, value: impl Fn(&Replicate) -> u32) -> Distribution {
+        Distribution::of(
+            self.replicates
+                .iter()
+                .map(|replicate| value(replicate) as f64)
+                .collect(),
+        )
+    }
+}
+
+/// Run `n` seeded replicates of `config` and aggregate their lifespans,
+/// final populations and growth classes.
+///
+/// Fails when `config.max_generations` exceeds
+/// `Limits::max_generations_per_call`.
+#[wasm_bindgen]
+pub fn run_ensemble(n: u32, config: &EnsembleConfig) -> Result<EnsembleReport, Error> {
+    run_ensemble_shard(config, 0, n)
+}
+
+/// Run replicates `first..first + count` of `config`, so an ensemble can
+/// be split across workers and the reports merged afterwards.
+#[wasm_bindgen]
+pub fn run_ensemble_shard(
+    config: &EnsembleConfig,
+    first: u32,
+    count: u32,
+) -> Result<EnsembleReport, Error> {
+    let limit = Limits::default().max_generations_per_call;
+    if config.max_generations > limit {
+        return Err(Error::GenerationLimit {
+            requested: config.max_generations,
+            limit,
+        });
+    }
+
+    check_dimensions(config.width, config.height)?;
+    let size = (config.width * config.height) as usize;
+    let replicates = (first..first.saturating_add(count))
+        .map(|i| {
+            let seed = config.seed.wrapping_add(i as u64);
+            let soup = rng::random_cells(size, config.density, &mut Xoshiro256::new(seed));
+            let initial_population = soup.count_ones(..) as u32;
+            let mut universe = Universe::from_cells(config.width, config.height, soup);
+            let report = universe.run_until_stable(config.max_generations)?;
+
+            let class = if report.population == 0 {
+                GrowthClass::Extinct
+            } else if report.stabilized && report.period == 1 {
+                GrowthClass::Stable
+            } else if report.stabilized {
+                GrowthClass::Oscillating
+            } else if report.population > initial_population {
+                GrowthClass::Growing
+            } else {
+                GrowthClass::Unsettled
+            };
+            Ok(Replicate {
+                seed,
+                lifespan: if report.stabilized {
+                    report.stabilized_at
+                } else {
+                    report.generations
+                },
+                initial_population,
+                final_population: report.population,
+                class,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok(EnsembleReport { replicates })
+}
@@ -0,0 +1,38 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{arena, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// One byte per cell, in storage order: 1 for every cell that has
+    /// been alive at some point since the last reset, 0 elsewhere. This is
+    /// the footprint a spaceship or puffer sweeps out.
+    pub fn envelope(&mut self) -> Vec<u8> {
+        let envelope = self.current_envelope();
+        (0..envelope.len()).map(|idx| envelope[idx] as u8).collect()
+    }
+
+    /// Number of cells in the envelope.
+    pub fn envelope_population(&mut self) -> u32 {
+        self.current_envelope().count_ones(..) as u32
+    }
+
+    /// Restart the envelope from the cells alive right now. Resetting or
+    /// randomizing the universe does this too.
+    pub fn reset_envelope(&mut self) {
+        self.wake();
+        arena::resize_in_place(&mut self.envelope, self.cells.len());
+        self.envelope.union_with(&self.cells);
+    }
+}
+
+impl Universe {
+    /// The envelope including edits made since the last tick.
+    fn current_envelope(&mut self) -> FixedBitSet {
+        self.wake();
+        let mut envelope = self.envelope.clone();
+        envelope.union_with(&self.cells);
+        envelope
+    }
+}
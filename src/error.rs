@@ -0,0 +1,117 @@
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+use crate::LogLevel;
+
+/// Errors returned by the fallible parts of the API.
+///
+/// Converted into a JavaScript `Error` when crossing the wasm boundary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A call asked for more generations than `Limits` allows.
+    GenerationLimit { requested: u32, limit: u32 },
+    /// Bookkeeping for `what` would grow past its memory budget.
+    MemoryLimit { what: &'static str, limit: usize },
+    /// A preallocated universe was asked to hold more cells than it has
+    /// room for.
+    CapacityExceeded { requested: usize, capacity: usize },
+    /// The requested dimensions hold more cells than a universe can
+    /// address, or than `Limits` allows for decoded input.
+    DimensionsTooLarge { width: u64, height: u64 },
+    /// A cell at `(row, column)` was addressed in a universe of only
+    /// `width` x `height` cells.
+    OutOfBounds {
+        row: u64,
+        column: u64,
+        width: u32,
+        height: u32,
+    },
+    /// No checkpoint at or before the requested generation.
+    NoCheckpoint(u32),
+    /// No save-state is stored under this name.
+    NoSaveState(String),
+    /// Snapshot data could not be decoded.
+    InvalidSnapshot(&'static str),
+    /// Pattern text could not be parsed; `line` is 1-based.
+    InvalidPattern { line: u32, reason: &'static str },
+    /// A pattern search query could not be parsed.
+    InvalidQuery(&'static str),
+    /// A rulestring could not be parsed.
+    InvalidRule(&'static str),
+    /// The parameters of a search or simulation contradict each other.
+    InvalidConfig(&'static str),
+    /// A cancel token stopped the call after `generations` ticks.
+    Cancelled { generations: u32 },
+    /// No `kind` (universe, pattern, ...) is registered under `id`.
+    UnknownHandle { kind: &'static str, id: u32 },
+    /// Reading a file or stream failed.
+    Io(String),
+    /// Downloading a resource failed.
+    Fetch(String),
+    /// WebGL refused a resource or shader.
+    Gpu(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::GenerationLimit { requested, limit } => write!(
+                f,
+                "requested {} generations but at most {} are allowed per call",
+                requested, limit
+            ),
+            Error::MemoryLimit { what, limit } => {
+                write!(f, "{} exceeded its memory limit of {} bytes", what, limit)
+            }
+            Error::CapacityExceeded {
+                requested,
+                capacity,
+            } => write!(
+                f,
+                "{} cells requested but the universe was preallocated for {}",
+                requested, capacity
+            ),
+            Error::DimensionsTooLarge { width, height } => {
+                write!(f, "a {}x{} universe is too large", width, height)
+            }
+            Error::OutOfBounds {
+                row,
+                column,
+                width,
+                height,
+            } => write!(
+                f,
+                "cell ({}, {}) is outside the {}x{} universe",
+                row, column, width, height
+            ),
+            Error::NoCheckpoint(generation) => {
+                write!(f, "no checkpoint at or before generation {}", generation)
+            }
+            Error::NoSaveState(name) => write!(f, "no save-state named {:?}", name),
+            Error::InvalidSnapshot(reason) => write!(f, "invalid snapshot: {}", reason),
+            Error::InvalidPattern { line, reason } => {
+                write!(f, "invalid pattern on line {}: {}", line, reason)
+            }
+            Error::InvalidQuery(reason) => write!(f, "invalid search query: {}", reason),
+            Error::InvalidRule(reason) => write!(f, "invalid rule: {}", reason),
+            Error::InvalidConfig(reason) => write!(f, "invalid configuration: {}", reason),
+            Error::Cancelled { generations } => {
+                write!(f, "cancelled after {} generations", generations)
+            }
+            Error::UnknownHandle { kind, id } => write!(f, "no {} with handle {}", kind, id),
+            Error::Io(reason) => write!(f, "I/O error: {}", reason),
+            Error::Fetch(reason) => write!(f, "fetch failed: {}", reason),
+            Error::Gpu(reason) => write!(f, "GPU error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for JsValue {
+    fn from(error: Error) -> Self {
+        log_at!(LogLevel::Error, "api", "{}", error);
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
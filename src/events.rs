@@ -0,0 +1,211 @@
+use std::mem;
+
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::components::Components;
+use crate::Universe;
+
+/// Kinds of event recorded in the event stream.
+///
+/// Recording is opt-in per kind through `Universe::set_event_mask()`, using
+/// `1 << kind` as the bit for each kind.
+#[wasm_bindgen]
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// A cell came alive; payload is the cell index.
+    Birth = 0,
+    /// A cell died; payload is the cell index.
+    Death = 1,
+    /// A connected object appeared (born from nothing or split off a
+    /// larger one); payload is its lowest cell index.
+    ObjectAppeared = 2,
+    /// A connected object disappeared (died out or merged into a larger
+    /// one); payload is its lowest cell index in the previous generation.
+    ObjectDisappeared = 3,
+    /// The universe stopped changing; payload is the population.
+    Stabilized = 4,
+}
+
+/// Number of `u32` words per record: kind, generation, payload.
+pub const EVENT_RECORD_WORDS: usize = 3;
+
+const DEFAULT_EVENT_CAPACITY: usize = 1 << 16;
+const OBJECT_MASK: u32 =
+    1 << EventKind::ObjectAppeared as u32 | 1 << EventKind::ObjectDisappeared as u32;
+
+/// Flat buffer of event records waiting to be drained by the host.
+#[derive(Clone, Debug)]
+pub(crate) struct EventLog {
+    mask: u32,
+    /// Maximum number of buffered records before new ones are dropped.
+    capacity: usize,
+    records: Vec<u32>,
+    dropped: u32,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        EventLog {
+            mask: 0,
+            capacity: DEFAULT_EVENT_CAPACITY,
+            records: Vec::new(),
+            dropped: 0,
+        }
+    }
+}
+
+impl EventLog {
+    /// Allocate room for `capacity` records up front.
+    pub(crate) fn reserve(&mut self) {
+        let words = self.capacity * EVENT_RECORD_WORDS;
+        self.records
+            .reserve_exact(words.saturating_sub(self.records.len()));
+    }
+
+    /// Keep at most `capacity` records, dropping the newest over it as if
+    /// they had arrived while the buffer was full.
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        let records = self.records.len() / EVENT_RECORD_WORDS;
+        if records > capacity {
+            self.dropped += (records - capacity) as u32;
+            self.records.truncate(capacity * EVENT_RECORD_WORDS);
+        }
+    }
+
+    pub(crate) fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    pub(crate) fn wants(&self, kind: EventKind) -> bool {
+        self.mask & (1 << kind as u32) != 0
+    }
+
+    pub(crate) fn wants_cells(&self) -> bool {
+        self.wants(EventKind::Birth) || self.wants(EventKind::Death)
+    }
+
+    pub(crate) fn push(&mut self, kind: EventKind, generation: u32, payload: u32) {
+        if self.records.len() / EVENT_RECORD_WORDS >= self.capacity {
+            self.dropped += 1;
+            return;
+        }
+        self.records
+            .extend_from_slice(&[kind as u32, generation, payload]);
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Choose which events are recorded, as a bit set of `1 << EventKind`.
+    /// Recording is off (mask 0) by default.
+    pub fn set_event_mask(&mut self, mask: u32) {
+        self.events.mask = mask;
+    }
+
+    pub fn event_mask(&self) -> u32 {
+        self.events.mask
+    }
+
+    /// Limit the number of buffered records. Records arriving while the
+    /// buffer is full, or buffered beyond a reduced limit, are dropped and
+    /// counted by `events_dropped()`.
+    pub fn set_event_capacity(&mut self, records: usize) {
+        self.events.set_capacity(records);
+        if self.capacity.is_some() {
+            self.events.reserve();
+        }
+    }
+
+    /// Take all buffered events as flat records of `[kind, generation,
+    /// payload]` and reset the dropped counter.
+    ///
+    /// Preallocated universes hand out a copy and keep their buffer.
+    pub fn drain_events(&mut self) -> Vec<u32> {
+        self.events.dropped = 0;
+        if self.capacity.is_some() {
+            let records = self.events.records.clone();
+            self.events.records.clear();
+            return records;
+        }
+        mem::take(&mut self.events.records)
+    }
+
+    /// Number of records dropped since the last `drain_events()`.
+    pub fn events_dropped(&self) -> u32 {
+        self.events.dropped
+    }
+}
+
+impl Universe {
+    /// Record the events that compare whole generations; called by `tick()`
+    /// after the new cells are in place.
+    pub(crate) fn record_generation_events(&mut self, previous: &FixedBitSet, was_changing: bool) {
+        if self.events.mask & OBJECT_MASK != 0 {
+            self.record_object_events(previous);
+        }
+
+        let stabilized = was_changing && !self.last_tick.changed;
+        if stabilized && self.events.wants(EventKind::Stabilized) {
+            let (generation, population) = (self.generation, self.last_tick.population);
+            self.events
+                .push(EventKind::Stabilized, generation, population);
+        }
+    }
+
+    /// Match the objects of consecutive generations by shared cells. Each
+    /// old object continues as the new object it overlaps most, and each
+    /// new object descends from the old object it overlaps most; new
+    /// objects that continue nothing appeared, old objects that nothing
+    /// descends from disappeared.
+    fn record_object_events(&mut self, previous: &FixedBitSet) {
+        let (stride, lines) = self.storage_shape();
+        let old = Components::label(previous, stride, lines, self.edges());
+        let new = Components::label(&self.cells, stride, lines, self.edges());
+
+        // best (overlap, label) per component, 0 meaning no overlap
+        let mut successor = vec![(0u32, 0u32); old.count() as usize];
+        let mut predecessor = vec![(0u32, 0u32); new.count() as usize];
+        let mut overlaps = std::collections::HashMap::new();
+        for idx in previous.intersection(&self.cells) {
+            *overlaps
+                .entry((old.labels[idx], new.labels[idx]))
+                .or_insert(0u32) += 1;
+        }
+        for (&(o, n), &overlap) in overlaps.iter() {
+            let best = &mut successor[o as usize - 1];
+            if (overlap, n) > *best {
+                *best = (overlap, n);
+            }
+            let best = &mut predecessor[n as usize - 1];
+            if (overlap, o) > *best {
+                *best = (overlap, o);
+            }
+        }
+
+        let mut continued = vec![false; new.count() as usize];
+        for &(_, n) in successor.iter().filter(|(overlap, _)| *overlap > 0) {
+            continued[n as usize - 1] = true;
+        }
+        let mut descended = vec![false; old.count() as usize];
+        for &(_, o) in predecessor.iter().filter(|(overlap, _)| *overlap > 0) {
+            descended[o as usize - 1] = true;
+        }
+
+        let generation = self.generation;
+        if self.events.wants(EventKind::ObjectDisappeared) {
+            for (i, _) in descended.iter().enumerate().filter(|(_, d)| !**d) {
+                self.events
+                    .push(EventKind::ObjectDisappeared, generation, old.anchors[i]);
+            }
+        }
+        if self.events.wants(EventKind::ObjectAppeared) {
+            for (i, _) in continued.iter().enumerate().filter(|(_, c)| !**c) {
+                self.events
+                    .push(EventKind::ObjectAppeared, generation, new.anchors[i]);
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{Error, Pattern};
+
+/// Download and parse a pattern file, e.g. straight from a LifeWiki link.
+///
+/// The format follows the file extension (`.rle`, `.cells`, `.lif` or
+/// `.life`) and is detected from the contents otherwise. Patterns without
+/// a name of their own are named after the file.
+#[wasm_bindgen]
+pub async fn load_pattern_from_url(url: String) -> Result<Pattern, Error> {
+    let text = fetch_text(&url).await?;
+    let file = file_name(&url);
+    let extension = file.rsplit_once('.').map_or("", |(_, ext)| ext);
+    let mut pattern = match extension.to_ascii_lowercase().as_str() {
+        "rle" => Pattern::from_rle(&text),
+        "cells" => Pattern::from_plaintext(&text),
+        "lif" | "life" => Pattern::from_life106(&text),
+        _ => Pattern::parse(&text),
+    }?;
+    if pattern.name().is_empty() {
+        let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+        pattern.set_name(stem);
+    }
+    Ok(pattern)
+}
+
+/// Last path segment of `url`, without query or fragment.
+fn file_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+pub(crate) async fn fetch_text(url: &str) -> Result<String, Error> {
+    let global = js_sys::global();
+    let promise = if let Some(window) = global.dyn_ref::<web_sys::Window>() {
+        window.fetch_with_str(url)
+    } else if let Some(worker) = global.dyn_ref::<web_sys::WorkerGlobalScope>() {
+        worker.fetch_with_str(url)
+    } else {
+        return Err(Error::Fetch("fetch is not available here".to_owned()));
+    };
+
+    let response: web_sys::Response = JsFuture::from(promise)
+        .await
+        .and_then(|response| response.dyn_into())
+        .map_err(fetch_error)?;
+    if !response.ok() {
+        return Err(Error::Fetch(format!(
+            "HTTP {} fetching {}",
+            response.status(),
+            url
+        )));
+    }
+    let text = JsFuture::from(response.text().map_err(fetch_error)?)
+        .await
+        .map_err(fetch_error)?;
+    text.as_string()
+        .ok_or_else(|| Error::Fetch("response body is not text".to_owned()))
+}
+
+fn fetch_error(error: JsValue) -> Error {
+    let message = match error.dyn_ref::<js_sys::Error>() {
+        Some(error) => error.message().into(),
+        None => error
+            .as_string()
+            .unwrap_or_else(|| "fetch failed".to_owned()),
+    };
+    Error::Fetch(message)
+}
@@ -0,0 +1,222 @@
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::byte_grid::ByteGrid;
+use crate::{Entropy, Error, Rule};
+
+/// A Generations rule: a Life-like rule whose live cells, instead of
+/// dying, fade through `states - 2` dying states first. Those still count
+/// as occupied (they cannot be born into) but not as live neighbours.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GenerationsRule {
+    rule: Rule,
+    states: u8,
+}
+
+#[wasm_bindgen]
+impl GenerationsRule {
+    /// Parse `"S/B/C"` notation, e.g. `"/2/3"` for Brian's Brain or
+    /// `"345/2/4"` for Star Wars, or `"B2/S/C3"` notation.
+    ///
+    /// Fails for fewer than 2 or more than 255 states.
+    pub fn parse(rulestring: &str) -> Result<GenerationsRule, Error> {
+        let text = rulestring.trim().to_ascii_lowercase();
+        let (rule, states) = text
+            .rsplit_once('/')
+            .ok_or(Error::InvalidRule("expected S/B/C or B/S/C notation"))?;
+        let states = states
+            .trim_start_matches(['c', 'g'])
+            .parse::<u8>()
+            .map_err(|_| Error::InvalidRule("the number of states is 2 to 255"))?;
+        if states < 2 {
+            return Err(Error::InvalidRule("the number of states is 2 to 255"));
+        }
+        let rule = Rule::parse(rule)?;
+        if !rule.is_totalistic() {
+            return Err(Error::InvalidRule(
+                "non-totalistic Generations rules are not supported",
+            ));
+        }
+        Ok(GenerationsRule { rule, states })
+    }
+
+    /// Brian's Brain, `B2/S/C3`: every live cell dies after one
+    /// generation and rests for another.
+    pub fn brians_brain() -> GenerationsRule {
+        GenerationsRule::parse("/2/3").expect("valid rulestring")
+    }
+
+    /// The Life-like part: which counts give births and survivals.
+    pub fn life_like(&self) -> Rule {
+        self.rule
+    }
+
+    /// Number of states including dead and alive.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// The rulestring in canonical B/S/C notation, e.g. `"B2/S/C3"`.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn rulestring(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl GenerationsRule {
+    /// State of a cell in the next generation.
+    fn next(&self, state: u8, neighbours: u8) -> u8 {
+        match state {
+            0 => self.rule.births(neighbours) as u8,
+            1 if self.rule.survives(neighbours) => 1,
+            _ if state + 1 < self.states => state + 1,
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/C{}", self.rule, self.states)
+    }
+}
+
+/// A torus under a Generations rule, with a byte per cell: 0 for dead, 1
+/// for alive and 2 up to `states - 1` for the dying stages.
+#[wasm_bindgen]
+pub struct GenerationsUniverse {
+    grid: ByteGrid,
+    rule: GenerationsRule,
+    generation: u32,
+}
+
+#[wasm_bindgen]
+impl GenerationsUniverse {
+    /// A dead universe evolving under `rule`. Fails with
+    /// `Error::DimensionsTooLarge` when it would have more cells than it
+    /// can address.
+    pub fn new(
+        height: u32,
+        width: u32,
+        rule: &GenerationsRule,
+    ) -> Result<GenerationsUniverse, Error> {
+        Ok(GenerationsUniverse {
+            grid: ByteGrid::new(height, width)?,
+            rule: *rule,
+            generation: 0,
+        })
+    }
+
+    /// `new()` with a rulestring, see `GenerationsRule::parse()`.
+    pub fn with_rule(
+        height: u32,
+        width: u32,
+        rulestring: &str,
+    ) -> Result<GenerationsUniverse, Error> {
+        GenerationsUniverse::new(height, width, &GenerationsRule::parse(rulestring)?)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.grid.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn rule(&self) -> GenerationsRule {
+        self.rule
+    }
+
+    /// Apply `rule` from the next tick on. Dying cells past its last
+    /// state die.
+    pub fn set_rule(&mut self, rule: &GenerationsRule) {
+        self.rule = *rule;
+        for state in self.grid.cells.iter_mut() {
+            if *state >= rule.states {
+                *state = 0;
+            }
+        }
+    }
+
+    /// Pointer to the state of every cell, a byte each in row-major
+    /// order, for renderers that colour each stage of decay. Read
+    /// `cell_states_len()` bytes from it.
+    pub fn cell_states(&self) -> *const u8 {
+        self.grid.cells.as_ptr()
+    }
+
+    pub fn cell_states_len(&self) -> usize {
+        self.grid.cells.len()
+    }
+
+    /// Number of live cells, not counting dying ones.
+    pub fn population(&self) -> u32 {
+        self.grid.cells.iter().filter(|&&state| state == 1).count() as u32
+    }
+
+    pub fn get_state(&self, row: u32, column: u32) -> Result<u8, Error> {
+        Ok(self.grid.cells[self.grid.index(row, column)?])
+    }
+
+    /// Fails when the cell is outside the universe or `state` is not one
+    /// of the rule's states.
+    pub fn set_state(&mut self, row: u32, column: u32, state: u8) -> Result<(), Error> {
+        let idx = self.grid.index(row, column)?;
+        if state >= self.rule.states {
+            return Err(Error::InvalidConfig("state exceeds the rule's states"));
+        }
+        self.grid.cells[idx] = state;
+        Ok(())
+    }
+
+    /// Bring a cell to life, or kill it when it is alive or dying.
+    pub fn toggle_cell(&mut self, row: u32, column: u32) -> Result<(), Error> {
+        let idx = self.grid.index(row, column)?;
+        self.grid.cells[idx] = (self.grid.cells[idx] == 0) as u8;
+        Ok(())
+    }
+
+    pub fn reset_cells(&mut self) {
+        self.grid.cells.iter_mut().for_each(|state| *state = 0);
+        self.generation = 0;
+    }
+
+    /// Replace the cells by a soup of live cells with probability
+    /// `density`, see `Universe::randomize()`.
+    pub fn randomize(&mut self, density: f64, entropy: &mut Entropy) {
+        for state in self.grid.cells.iter_mut() {
+            *state = (entropy.next_f64() < density) as u8;
+        }
+        self.generation = 0;
+    }
+
+    pub fn tick(&mut self) {
+        let rule = self.rule;
+        self.grid
+            .step(true, |state| state == 1, |state, n| rule.next(state, n));
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+}
+
+impl GenerationsUniverse {
+    /// Bring each listed `(row, column)` cell to life.
+    ///
+    /// Fails without changing anything when a cell is outside the
+    /// universe.
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) -> Result<(), Error> {
+        self.grid.set_cells(cells, 1)
+    }
+}
@@ -0,0 +1,320 @@
+use std::cmp::Ordering;
+
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::rng::{self, EntropySource, Xoshiro256};
+use crate::{check_dimensions, Error, Limits, Pattern, StopCondition, Universe};
+
+/// Number of genomes drawn for each tournament selecting a parent.
+const TOURNAMENT_SIZE: usize = 3;
+
+/// What `GeneticSearch` breeds patterns for.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fitness {
+    /// Generations until the pattern settles into a still life or cycle.
+    Lifespan,
+    /// Live cells once the pattern has settled, or at `max_generations`.
+    FinalPopulation,
+    /// How far the centre of the live cells moved after
+    /// `max_generations`, at most half the universe across.
+    Displacement,
+}
+
+/// Parameters of a `GeneticSearch`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchConfig {
+    /// Size of the universe each genome is evaluated in.
+    pub width: u32,
+    pub height: u32,
+    /// Size of the grid a genome describes, placed in the centre of the
+    /// universe.
+    pub genome_width: u32,
+    pub genome_height: u32,
+    /// Genomes per generation.
+    pub population: u32,
+    /// Probability of each cell of a child being flipped.
+    pub mutation_rate: f64,
+    /// Probability of a child mixing two parents rather than copying one.
+    pub crossover_rate: f64,
+    /// Generations each genome is evolved for when evaluated.
+    pub max_generations: u32,
+    pub seed: u64,
+}
+
+#[wasm_bindgen]
+impl SearchConfig {
+    /// A search with a 1% mutation rate and 70% crossover rate; both can
+    /// be changed afterwards.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        width: u32,
+        height: u32,
+        genome_width: u32,
+        genome_height: u32,
+        population: u32,
+        max_generations: u32,
+        seed: u64,
+    ) -> SearchConfig {
+        SearchConfig {
+            width,
+            height,
+            genome_width,
+            genome_height,
+            population,
+            mutation_rate: 0.01,
+            crossover_rate: 0.7,
+            max_generations,
+            seed,
+        }
+    }
+}
+
+/// A candidate initial pattern and how well it scored.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Genome {
+    /// `genome_width` x `genome_height` cells, row by row
+    cells: FixedBitSet,
+    width: u32,
+    pub fitness: f64,
+}
+
+#[wasm_bindgen]
+impl Genome {
+    /// The genome's live cells as a pattern, e.g. for `place_pattern()`.
+    pub fn pattern(&self) -> Pattern {
+        let width = self.width as usize;
+        Pattern::from_cells(
+            "",
+            self.cells
+                .ones()
+                .map(|idx| ((idx / width) as u32, (idx % width) as u32))
+                .collect(),
+        )
+    }
+
+    /// Live cells in the genome.
+    pub fn population(&self) -> u32 {
+        self.cells.count_ones(..) as u32
+    }
+}
+
+/// Evolves initial patterns toward a `Fitness` by mutation, crossover
+/// and tournament selection, keeping the best genome of every generation.
+#[wasm_bindgen]
+pub struct GeneticSearch {
+    config: SearchConfig,
+    fitness: Fitness,
+    rng: Xoshiro256,
+    /// Current generation of genomes, fittest first
+    population: Vec<Genome>,
+    best: Genome,
+    /// Fitness of the best genome of every generation
+    history: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl GeneticSearch {
+    /// Start a search from random genomes.
+    ///
+    /// Fails when the universe is too large, the genome does not fit in
+    /// it, the population has fewer than two genomes, or
+    /// `max_generations` exceeds `Limits::max_generations_per_call`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: &SearchConfig, fitness: Fitness) -> Result<GeneticSearch, Error> {
+        check_dimensions(config.width, config.height)?;
+        if config.genome_width == 0
+            || config.genome_height == 0
+            || config.genome_width > config.width
+            || config.genome_height > config.height
+        {
+            return Err(Error::InvalidConfig("the genome must fit in the universe"));
+        }
+        if config.population < 2 {
+            return Err(Error::InvalidConfig("the population needs two genomes"));
+        }
+        let limit = Limits::default().max_generations_per_call;
+        if config.max_generations > limit {
+            return Err(Error::GenerationLimit {
+                requested: config.max_generations,
+                limit,
+            });
+        }
+
+        let mut rng = Xoshiro256::new(config.seed);
+        let size = (config.genome_width * config.genome_height) as usize;
+        let population = (0..config.population)
+            .map(|_| rng::random_cells(size, 0.5, &mut rng))
+            .collect();
+        let mut search = GeneticSearch {
+            config: *config,
+            fitness,
+            rng,
+            population: Vec::new(),
+            best: Genome {
+                cells: FixedBitSet::with_capacity(size),
+                width: config.genome_width,
+                fitness: f64::NEG_INFINITY,
+            },
+            history: Vec::new(),
+        };
+        search.install(population)?;
+        Ok(search)
+    }
+
+    /// Breed the next generation: the fittest genome carries over, the
+    /// others are children of tournament-selected parents. Fails, keeping
+    /// the current generation, when evolving a child does.
+    pub fn step(&mut self) -> Result<(), Error> {
+        let mut next = vec![self.population[0].cells.clone()];
+        while next.len() < self.population.len() {
+            let first = self.select();
+            let mut child = self.population[first].cells.clone();
+            if self.rng.next_f64() < self.config.crossover_rate {
+                let second = self.select();
+                for idx in 0..child.len() {
+                    if self.rng.next_f64() < 0.5 {
+                        child.set(idx, self.population[second].cells[idx]);
+                    }
+                }
+            }
+            for idx in 0..child.len() {
+                if self.rng.next_f64() < self.config.mutation_rate {
+                    child.toggle(idx);
+                }
+            }
+            next.push(child);
+        }
+        self.install(next)
+    }
+
+    /// Run `generations` steps.
+    pub fn evolve(&mut self, generations: u32) -> Result<(), Error> {
+        for _ in 0..generations {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Generations bred since the random start.
+    pub fn generation(&self) -> u32 {
+        self.history.len() as u32 - 1
+    }
+
+    /// The fittest genome found so far, in any generation.
+    pub fn best(&self) -> Genome {
+        self.best.clone()
+    }
+
+    /// The `count` fittest genomes of the current generation, fittest
+    /// first.
+    pub fn top(&self, count: usize) -> Vec<Genome> {
+        self.population.iter().take(count).cloned().collect()
+    }
+
+    /// Fitness of the best genome of every generation, starting with the
+    /// random one.
+    pub fn best_fitness_history(&self) -> Vec<f64> {
+        self.history.clone()
+    }
+}
+
+impl GeneticSearch {
+    /// Evaluate and rank a new generation.
+    fn install(&mut self, cells: Vec<FixedBitSet>) -> Result<(), Error> {
+        self.population = cells
+            .into_iter()
+            .map(|cells| {
+                Ok(Genome {
+                    fitness: self.evaluate(&cells)?,
+                    cells,
+                    width: self.config.genome_width,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        self.population
+            .sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
+        if self.population[0].fitness > self.best.fitness {
+            self.best = self.population[0].clone();
+        }
+        self.history.push(self.population[0].fitness);
+        Ok(())
+    }
+
+    /// Index of the fittest of a few random genomes.
+    fn select(&mut self) -> usize {
+        (0..TOURNAMENT_SIZE)
+            .map(|_| (self.rng.next_u64() % self.population.len() as u64) as usize)
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn evaluate(&self, genome: &FixedBitSet) -> Result<f64, Error> {
+        let config = &self.config;
+        let top = (config.height - config.genome_height) / 2;
+        let left = (config.width - config.genome_width) / 2;
+        let mut cells = FixedBitSet::with_capacity((config.width * config.height) as usize);
+        for idx in genome.ones() {
+            let row = top + idx as u32 / config.genome_width;
+            let col = left + idx as u32 % config.genome_width;
+            cells.insert((row * config.width + col) as usize);
+        }
+        let start = centroid(&cells, config.width);
+        let mut universe = Universe::from_cells(config.width, config.height, cells);
+
+        let fitness = match self.fitness {
+            Fitness::Lifespan => {
+                let report = universe.run_until_stable(config.max_generations)?;
+                if report.stabilized {
+                    report.stabilized_at as f64
+                } else {
+                    report.generations as f64
+                }
+            }
+            Fitness::FinalPopulation => {
+                let report = universe.run_until_stable(config.max_generations)?;
+                report.population as f64
+            }
+            Fitness::Displacement => {
+                universe.run_until(&StopCondition::extinct(), config.max_generations)?;
+                match (start, centroid(&universe.cells, config.width)) {
+                    (Some(start), Some(end)) => {
+                        let dy = wrapped(end.0 - start.0, config.height as f64);
+                        let dx = wrapped(end.1 - start.1, config.width as f64);
+                        (dx * dx + dy * dy).sqrt()
+                    }
+                    _ => 0.0,
+                }
+            }
+        };
+        Ok(fitness)
+    }
+}
+
+/// Mean row and column of the live cells of a row-major grid.
+fn centroid(cells: &FixedBitSet, width: u32) -> Option<(f64, f64)> {
+    let count = cells.count_ones(..);
+    if count == 0 {
+        return None;
+    }
+    let (mut rows, mut cols) = (0.0, 0.0);
+    for idx in cells.ones() {
+        rows += (idx / width as usize) as f64;
+        cols += (idx % width as usize) as f64;
+    }
+    Some((rows / count as f64, cols / count as f64))
+}
+
+/// The shortest of the offsets equivalent to `delta` on a torus `size`
+/// cells around.
+fn wrapped(delta: f64, size: f64) -> f64 {
+    let delta = delta.rem_euclid(size);
+    if delta > size / 2.0 {
+        delta - size
+    } else {
+        delta
+    }
+}
@@ -0,0 +1,293 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{
+    WebGl2RenderingContext as Gl, WebGlFramebuffer, WebGlProgram, WebGlShader, WebGlTexture,
+};
+
+use crate::{packed, Error, Universe};
+
+/// A triangle covering the whole viewport, from `gl_VertexID` alone so no
+/// vertex buffer is needed.
+const VERTEX_SHADER: &str = r#"#version 300 es
+void main() {
+    vec2 corner = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+    gl_Position = vec4(corner * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+
+/// One generation per fragment: the Moore neighbourhood on a torus, with
+/// the rule as bit masks over the neighbour counts.
+const FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+precision highp int;
+uniform sampler2D cells;
+uniform uint births;
+uniform uint survivals;
+out vec4 color;
+void main() {
+    ivec2 size = textureSize(cells, 0);
+    ivec2 here = ivec2(gl_FragCoord.xy);
+    uint count = 0u;
+    for (int dy = -1; dy <= 1; dy++) {
+        for (int dx = -1; dx <= 1; dx++) {
+            if (dx != 0 || dy != 0) {
+                ivec2 at = (here + ivec2(dx, dy) + size) % size;
+                count += uint(texelFetch(cells, at, 0).r > 0.5);
+            }
+        }
+    }
+    uint rule = texelFetch(cells, here, 0).r > 0.5 ? survivals : births;
+    color = vec4(float((rule >> count) & 1u), 0.0, 0.0, 1.0);
+}
+"#;
+
+/// A copy of a universe ticked by a WebGL2 fragment pass, for grids too
+/// large to tick on the CPU every frame.
+///
+/// The cells are uploaded once into a texture with a byte per cell; each
+/// tick renders the next generation into a second texture and the two
+/// swap roles. `cells()` reads them back in the layout of
+/// `Universe::cells()`, so a frontend drawing from that buffer works
+/// unchanged.
+///
+/// Only the rule carries over: the grid is always a torus, and storage
+/// order, history and the other bookkeeping of `Universe` stay behind.
+///
+/// Experimental: the shaders have not been run against a real WebGL2
+/// context yet.
+#[wasm_bindgen]
+pub struct GpuUniverse {
+    gl: Gl,
+    program: WebGlProgram,
+    textures: [WebGlTexture; 2],
+    framebuffers: [WebGlFramebuffer; 2],
+    /// Which texture holds the current generation
+    current: usize,
+    width: u32,
+    height: u32,
+    generation: u32,
+    /// `cells()` buffer, and the RGBA pixels it is packed from
+    packed: Vec<u8>,
+    rgba: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl GpuUniverse {
+    /// Upload `universe` to the GPU behind `gl`.
+    ///
+    /// Fails for non-totalistic rules, which the shader does not
+    /// implement, when the shaders do not compile or when the textures
+    /// cannot be rendered to.
+    #[wasm_bindgen(constructor)]
+    pub fn new(gl: Gl, universe: &Universe) -> Result<GpuUniverse, Error> {
+        if !universe.rule.is_totalistic() {
+            return Err(Error::InvalidRule("non-totalistic rules need a Universe"));
+        }
+        let program = link_program(&gl)?;
+        let (width, height) = (universe.width, universe.height);
+        let cells = universe.row_major_cells();
+        let bytes: Vec<u8> = (0..cells.len())
+            .map(|idx| if cells[idx] { 255 } else { 0 })
+            .collect();
+
+        let texture = |pixels: Option<&[u8]>| -> Result<WebGlTexture, Error> {
+            let texture = gl.create_texture().ok_or_else(|| gpu_error("no texture"))?;
+            gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+            for &parameter in [Gl::TEXTURE_MIN_FILTER, Gl::TEXTURE_MAG_FILTER].iter() {
+                gl.tex_parameteri(Gl::TEXTURE_2D, parameter, Gl::NEAREST as i32);
+            }
+            gl.pixel_storei(Gl::UNPACK_ALIGNMENT, 1);
+            gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                Gl::TEXTURE_2D,
+                0,
+                Gl::R8 as i32,
+                width as i32,
+                height as i32,
+                0,
+                Gl::RED,
+                Gl::UNSIGNED_BYTE,
+                pixels,
+            )
+            .map_err(js_error)?;
+            Ok(texture)
+        };
+        let textures = [texture(Some(&bytes))?, texture(None)?];
+
+        let framebuffer = |texture: &WebGlTexture| -> Result<WebGlFramebuffer, Error> {
+            let framebuffer = gl
+                .create_framebuffer()
+                .ok_or_else(|| gpu_error("no framebuffer"))?;
+            gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+            gl.framebuffer_texture_2d(
+                Gl::FRAMEBUFFER,
+                Gl::COLOR_ATTACHMENT0,
+                Gl::TEXTURE_2D,
+                Some(texture),
+                0,
+            );
+            if gl.check_framebuffer_status(Gl::FRAMEBUFFER) != Gl::FRAMEBUFFER_COMPLETE {
+                return Err(gpu_error("cannot render to a single channel texture"));
+            }
+            Ok(framebuffer)
+        };
+        let framebuffers = [framebuffer(&textures[0])?, framebuffer(&textures[1])?];
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+
+        let mask =
+            |set: &dyn Fn(u8) -> bool| (0..=8).filter(|&n| set(n)).fold(0, |m, n| m | 1 << n);
+        gl.use_program(Some(&program));
+        gl.uniform1i(gl.get_uniform_location(&program, "cells").as_ref(), 0);
+        gl.uniform1ui(
+            gl.get_uniform_location(&program, "births").as_ref(),
+            mask(&|n| universe.rule.births(n)),
+        );
+        gl.uniform1ui(
+            gl.get_uniform_location(&program, "survivals").as_ref(),
+            mask(&|n| universe.rule.survives(n)),
+        );
+
+        Ok(GpuUniverse {
+            gl,
+            program,
+            textures,
+            framebuffers,
+            current: 0,
+            width,
+            height,
+            generation: universe.generation,
+            packed: Vec::new(),
+            rgba: Vec::new(),
+        })
+    }
+
+    pub fn tick(&mut self) {
+        let gl = &self.gl;
+        let next = 1 - self.current;
+        gl.use_program(Some(&self.program));
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&self.framebuffers[next]));
+        gl.viewport(0, 0, self.width as i32, self.height as i32);
+        gl.active_texture(Gl::TEXTURE0);
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&self.textures[self.current]));
+        gl.draw_arrays(Gl::TRIANGLES, 0, 3);
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+        self.current = next;
+        self.generation += 1;
+    }
+
+    /// Queue `generations` ticks; the GPU runs them without a round trip
+    /// until the next `cells()`.
+    pub fn tick_many(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+
+    /// Read the current generation back from the GPU and return a pointer
+    /// to it, byte-packed in row-major order like `Universe::cells()`.
+    /// Read `cells_byte_len()` bytes from it.
+    ///
+    /// This waits for the queued ticks to finish, so call it once per frame
+    /// rather than per tick.
+    pub fn cells(&mut self) -> Result<*const u8, Error> {
+        let len = self.width as usize * self.height as usize;
+        // RGBA is the only read back format every implementation supports
+        self.rgba.resize(len * 4, 0);
+        let gl = &self.gl;
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&self.framebuffers[self.current]));
+        let read = gl.read_pixels_with_opt_u8_array(
+            0,
+            0,
+            self.width as i32,
+            self.height as i32,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            Some(&mut self.rgba),
+        );
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+        read.map_err(js_error)?;
+
+        let mut cells = fixedbitset::FixedBitSet::with_capacity(len);
+        for (idx, pixel) in self.rgba.chunks_exact(4).enumerate() {
+            cells.set(idx, pixel[0] > 127);
+        }
+        self.packed = packed::pack(&cells);
+        Ok(self.packed.as_ptr())
+    }
+
+    pub fn cells_byte_len(&self) -> usize {
+        (self.width as usize * self.height as usize).div_ceil(8)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl Drop for GpuUniverse {
+    fn drop(&mut self) {
+        let gl = &self.gl;
+        for (texture, framebuffer) in self.textures.iter().zip(&self.framebuffers) {
+            gl.delete_framebuffer(Some(framebuffer));
+            gl.delete_texture(Some(texture));
+        }
+        gl.delete_program(Some(&self.program));
+    }
+}
+
+fn link_program(gl: &Gl) -> Result<WebGlProgram, Error> {
+    let vertex = compile_shader(gl, Gl::VERTEX_SHADER, VERTEX_SHADER)?;
+    let fragment = compile_shader(gl, Gl::FRAGMENT_SHADER, FRAGMENT_SHADER)?;
+    let program = gl.create_program().ok_or_else(|| gpu_error("no program"))?;
+    gl.attach_shader(&program, &vertex);
+    gl.attach_shader(&program, &fragment);
+    gl.link_program(&program);
+    // the linked program keeps what it needs
+    gl.delete_shader(Some(&vertex));
+    gl.delete_shader(Some(&fragment));
+    if gl
+        .get_program_parameter(&program, Gl::LINK_STATUS)
+        .as_bool()
+        != Some(true)
+    {
+        let log = gl.get_program_info_log(&program).unwrap_or_default();
+        return Err(Error::Gpu(format!("linking failed: {}", log)));
+    }
+    Ok(program)
+}
+
+fn compile_shader(gl: &Gl, kind: u32, source: &str) -> Result<WebGlShader, Error> {
+    let shader = gl
+        .create_shader(kind)
+        .ok_or_else(|| gpu_error("no shader"))?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+    if gl
+        .get_shader_parameter(&shader, Gl::COMPILE_STATUS)
+        .as_bool()
+        != Some(true)
+    {
+        let log = gl.get_shader_info_log(&shader).unwrap_or_default();
+        return Err(Error::Gpu(format!("shader compilation failed: {}", log)));
+    }
+    Ok(shader)
+}
+
+fn gpu_error(what: &str) -> Error {
+    // WebGL hands out null objects once the context is lost
+    Error::Gpu(format!("{} (context lost?)", what))
+}
+
+fn js_error(error: JsValue) -> Error {
+    Error::Gpu(
+        error
+            .as_string()
+            .unwrap_or_else(|| "WebGL call failed".to_owned()),
+    )
+}
@@ -0,0 +1,598 @@
+use std::collections::HashMap;
+use std::mem;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Limits, Pattern, Rule};
+
+/// Level of the root of an empty universe, 8x8 cells.
+const MIN_LEVEL: u8 = 3;
+/// Highest level of the root, so coordinates and sides stay within `i64`.
+const MAX_LEVEL: u8 = 62;
+/// Bytes a node costs with its index entry and a memoized result, for
+/// budgeting against `Limits::max_cache_bytes`.
+const NODE_BYTES: usize =
+    mem::size_of::<Node>() + mem::size_of::<([u32; 4], u32)>() + mem::size_of::<((u32, u8), u32)>();
+
+/// A quadtree node: four children of the level below, or a single cell at
+/// level 0 (nodes 0 and 1, dead and alive).
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    /// nw, ne, sw, se
+    children: [u32; 4],
+    level: u8,
+    population: u64,
+}
+
+const NW: usize = 0;
+const NE: usize = 1;
+const SW: usize = 2;
+const SE: usize = 3;
+
+/// Canonicalized nodes with their memoized results.
+#[derive(Clone, Debug)]
+struct Store {
+    nodes: Vec<Node>,
+    index: HashMap<[u32; 4], u32>,
+    /// (node, j) -> centre of the node advanced 2^j generations
+    results: HashMap<(u32, u8), u32>,
+    /// Empty node of each level
+    empty: Vec<u32>,
+    rule: Rule,
+    /// Nodes allowed before results stop being computed, see
+    /// `over_budget()`
+    max_nodes: usize,
+}
+
+impl Store {
+    fn new(rule: Rule, max_nodes: usize) -> Store {
+        let cell = |population| Node {
+            children: [0; 4],
+            level: 0,
+            population,
+        };
+        Store {
+            nodes: vec![cell(0), cell(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![0],
+            rule,
+            max_nodes,
+        }
+    }
+
+    /// Whether the store has outgrown its budget. Results computed from
+    /// then on are placeholders, to be thrown away.
+    fn over_budget(&self) -> bool {
+        self.nodes.len() > self.max_nodes
+    }
+
+    fn node(&self, id: u32) -> Node {
+        self.nodes[id as usize]
+    }
+
+    fn join(&mut self, children: [u32; 4]) -> u32 {
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+        let level = self.node(children[0]).level + 1;
+        let population = children.iter().map(|&c| self.node(c).population).sum();
+        let id = self.nodes.len() as u32;
+        self.nodes.push(Node {
+            children,
+            level,
+            population,
+        });
+        self.index.insert(children, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> u32 {
+        while self.empty.len() <= level as usize {
+            let below = *self.empty.last().unwrap();
+            let id = self.join([below; 4]);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    fn child(&self, id: u32, quadrant: usize) -> u32 {
+        self.node(id).children[quadrant]
+    }
+
+    /// The middle half of a node, one level down.
+    fn centre(&mut self, id: u32) -> u32 {
+        let [nw, ne, sw, se] = self.node(id).children;
+        self.join([
+            self.child(nw, SE),
+            self.child(ne, SW),
+            self.child(sw, NE),
+            self.child(se, NW),
+        ])
+    }
+
+    /// The centre of node `id` (level `k` >= 2) advanced `2^j` generations,
+    /// `j <= k - 2`.
+    fn result(&mut self, id: u32, j: u8) -> u32 {
+        let node = self.node(id);
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+        if self.over_budget() {
+            return self.empty(node.level - 1);
+        }
+        let result = if node.population == 0 {
+            self.empty(node.level - 1)
+        } else if node.level == 2 {
+            self.base_result(id)
+        } else {
+            self.recursive_result(id, j)
+        };
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// One generation of the middle 2x2 of a 4x4 node.
+    fn base_result(&mut self, id: u32) -> u32 {
+        let cell = |store: &Store, row: usize, col: usize| {
+            let quadrant = store.child(id, (row / 2) * 2 + col / 2);
+            store.child(quadrant, (row % 2) * 2 + col % 2) == 1
+        };
+        let mut next = [0; 4];
+        for (i, &(row, col)) in [(1, 1), (1, 2), (2, 1), (2, 2)].iter().enumerate() {
+            // the 3 x 3 block in row-major order, see `Rule::apply()`
+            let mut pattern = 0;
+            for bit in 0..9 {
+                pattern |= (cell(self, row + bit / 3 - 1, col + bit % 3 - 1) as u16) << bit;
+            }
+            next[i] = self.rule.apply(pattern) as u32;
+        }
+        self.join(next)
+    }
+
+    fn recursive_result(&mut self, id: u32, j: u8) -> u32 {
+        let level = self.node(id).level;
+        let [a, b, c, d] = self.node(id).children;
+        let ch = |store: &Store, id, quadrant| store.child(id, quadrant);
+        // nine overlapping nodes one level down, row by row
+        let grid = [
+            a,
+            self.join([
+                ch(self, a, NE),
+                ch(self, b, NW),
+                ch(self, a, SE),
+                ch(self, b, SW),
+            ]),
+            b,
+            self.join([
+                ch(self, a, SW),
+                ch(self, a, SE),
+                ch(self, c, NW),
+                ch(self, c, NE),
+            ]),
+            self.join([
+                ch(self, a, SE),
+                ch(self, b, SW),
+                ch(self, c, NE),
+                ch(self, d, NW),
+            ]),
+            self.join([
+                ch(self, b, SW),
+                ch(self, b, SE),
+                ch(self, d, NW),
+                ch(self, d, NE),
+            ]),
+            c,
+            self.join([
+                ch(self, c, NE),
+                ch(self, d, NW),
+                ch(self, c, SE),
+                ch(self, d, SW),
+            ]),
+            d,
+        ];
+        // at full speed both halves advance 2^(k-3) generations; slower
+        // steps only advance in the second half
+        let full_speed = j == level - 2;
+        let mut halfway = [0; 9];
+        for (i, &node) in grid.iter().enumerate() {
+            halfway[i] = if full_speed {
+                self.result(node, level - 3)
+            } else {
+                self.centre(node)
+            };
+        }
+        let second = if full_speed { level - 3 } else { j };
+        let mut quadrants = [0; 4];
+        for (i, &(row, col)) in [(0, 0), (0, 1), (1, 0), (1, 1)].iter().enumerate() {
+            let at = |r: usize, c: usize| halfway[(row + r) * 3 + col + c];
+            let joined = self.join([at(0, 0), at(0, 1), at(1, 0), at(1, 1)]);
+            quadrants[i] = self.result(joined, second);
+        }
+        self.join(quadrants)
+    }
+
+    /// Copy of `root` and everything below it into a fresh store, without
+    /// memoized results.
+    fn compact(&self, root: u32) -> (Store, u32) {
+        let mut store = Store::new(self.rule, self.max_nodes);
+        let mut mapping = HashMap::new();
+        mapping.insert(0, 0);
+        mapping.insert(1, 1);
+        let root = self.copy_into(root, &mut store, &mut mapping);
+        (store, root)
+    }
+
+    fn copy_into(&self, id: u32, store: &mut Store, mapping: &mut HashMap<u32, u32>) -> u32 {
+        if let Some(&copy) = mapping.get(&id) {
+            return copy;
+        }
+        let children = self.node(id).children;
+        let mut copied = [0; 4];
+        for (i, &child) in children.iter().enumerate() {
+            copied[i] = self.copy_into(child, store, mapping);
+        }
+        let copy = store.join(copied);
+        mapping.insert(id, copy);
+        copy
+    }
+}
+
+/// An unbounded Life-like universe run with HashLife: a quadtree with
+/// every distinct block stored once and its future memoized, so patterns
+/// with repetitive structure (breeders, guns, metapixels) can be advanced
+/// by huge numbers of generations at once.
+///
+/// Unlike `Universe` it has no edges: cells are addressed by signed
+/// `(row, column)` within `2^61` of the origin. Rules with B0 are not
+/// supported.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct HashLifeUniverse {
+    store: Store,
+    root: u32,
+    /// The root covers rows and columns `-2^(level-1)..2^(level-1)`
+    level: u8,
+    generation: u64,
+    /// `max_cache_bytes` bounds the node store
+    limits: Limits,
+}
+
+impl Default for HashLifeUniverse {
+    fn default() -> HashLifeUniverse {
+        HashLifeUniverse::new()
+    }
+}
+
+#[wasm_bindgen]
+impl HashLifeUniverse {
+    /// An empty universe evolving under B3/S23.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> HashLifeUniverse {
+        let limits = Limits::default();
+        let mut store = Store::new(Rule::default(), max_nodes(&limits));
+        let root = store.empty(MIN_LEVEL);
+        HashLifeUniverse {
+            store,
+            root,
+            level: MIN_LEVEL,
+            generation: 0,
+            limits,
+        }
+    }
+
+    /// `pattern` with its top left corner at the origin.
+    pub fn from_pattern(pattern: &Pattern) -> HashLifeUniverse {
+        let mut universe = HashLifeUniverse::new();
+        for &(row, col) in pattern.cells() {
+            // u32 coordinates are always in range
+            universe.put(row as i64, col as i64, true);
+        }
+        universe
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.store.rule
+    }
+
+    /// Fails for rules with B0, whose background would fill the whole
+    /// unbounded plane.
+    pub fn set_rule(&mut self, rule: &Rule) -> Result<(), Error> {
+        if rule.births(0) {
+            return Err(Error::InvalidRule("B0 rules need a bounded universe"));
+        }
+        self.store.rule = *rule;
+        self.store.results.clear();
+        Ok(())
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u64 {
+        self.store.node(self.root).population
+    }
+
+    pub fn get_cell(&self, row: i64, column: i64) -> bool {
+        if !self.contains(row, column) {
+            return false;
+        }
+        let half = 1i64 << (self.level - 1);
+        let (mut id, mut level) = (self.root, self.level);
+        let (mut row, mut col) = ((row + half) as u64, (column + half) as u64);
+        while level > 0 {
+            let half = 1u64 << (level - 1);
+            let quadrant = (row >= half) as usize * 2 + (col >= half) as usize;
+            id = self.store.child(id, quadrant);
+            row %= half;
+            col %= half;
+            level -= 1;
+        }
+        id == 1
+    }
+
+    /// Fails when the cell lies `2^61` or more from the origin.
+    pub fn set_cell(&mut self, row: i64, column: i64, alive: bool) -> Result<(), Error> {
+        let edge = 1i64 << (MAX_LEVEL - 1);
+        if !(-edge..edge).contains(&row) || !(-edge..edge).contains(&column) {
+            return Err(Error::InvalidConfig(
+                "HashLife cells must lie within 2^61 of the origin",
+            ));
+        }
+        self.put(row, column, alive);
+        Ok(())
+    }
+
+    /// Advance `2^log2_generations` generations in one super-step. When
+    /// the step would outgrow `Limits::max_cache_bytes`, the memoized
+    /// results are dropped and it is retried as two half steps, and so on
+    /// down to single generations.
+    ///
+    /// Fails for steps of more than `2^59` generations, when the
+    /// generation count would overflow, when the pattern would grow past
+    /// `2^61` cells from the origin, and when a single generation does
+    /// not fit the memory budget; steps already done are kept.
+    pub fn step(&mut self, log2_generations: u8) -> Result<(), Error> {
+        if log2_generations > MAX_LEVEL - 3 {
+            return Err(Error::InvalidConfig(
+                "HashLife super-steps are limited to 2^59 generations",
+            ));
+        }
+        if self.generation.checked_add(1 << log2_generations).is_none() {
+            return Err(Error::InvalidConfig("the generation count would overflow"));
+        }
+        self.store.max_nodes = max_nodes(&self.limits);
+        self.super_step(log2_generations, false)
+    }
+
+    /// Advance any number of generations, as super-steps of powers of two.
+    /// Fails like `step()`.
+    pub fn step_by(&mut self, generations: u64) -> Result<(), Error> {
+        for bit in 0..64 {
+            if generations & 1 << bit != 0 {
+                self.step(bit)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Advance one generation, like `Universe::tick()`. Fails like
+    /// `step()`.
+    pub fn tick(&mut self) -> Result<(), Error> {
+        self.step(0)
+    }
+
+    /// `[top, left, bottom, right]` of the live cells, bottom and right
+    /// exclusive; empty when no cell is alive.
+    pub fn bounding_box(&self) -> Vec<i64> {
+        if self.population() == 0 {
+            return Vec::new();
+        }
+        let half = 1i64 << (self.level - 1);
+        let mut bounds = [i64::MAX, i64::MAX, i64::MIN, i64::MIN];
+        self.extend_bounds(self.root, self.level, -half, -half, &mut bounds);
+        bounds.to_vec()
+    }
+
+    /// One byte per cell of the `height` x `width` window whose top left
+    /// corner is `(top, left)`, row by row: 1 where the cell is alive.
+    pub fn window(&self, top: i64, left: i64, height: u32, width: u32) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(height as usize * width as usize);
+        for row in 0..height as i64 {
+            for col in 0..width as i64 {
+                // past i64 is outside the plane, hence dead
+                let (row, col) = (top.saturating_add(row), left.saturating_add(col));
+                bytes.push(self.get_cell(row, col) as u8);
+            }
+        }
+        bytes
+    }
+
+    /// The live cells as a pattern, shifted to start at `(0, 0)`. Fails
+    /// when they span more rows or columns than a pattern can hold.
+    pub fn to_pattern(&self) -> Result<Pattern, Error> {
+        let bounds = self.bounding_box();
+        if bounds.is_empty() {
+            return Ok(Pattern::from_cells("", Vec::new()));
+        }
+        let (height, width) = (bounds[2] - bounds[0], bounds[3] - bounds[1]);
+        if height > u32::MAX as i64 || width > u32::MAX as i64 {
+            return Err(Error::DimensionsTooLarge {
+                width: width as u64,
+                height: height as u64,
+            });
+        }
+        let half = 1i64 << (self.level - 1);
+        let mut cells = Vec::new();
+        self.collect_cells(self.root, self.level, -half, -half, &mut cells);
+        Ok(Pattern::from_cells(
+            "",
+            cells
+                .into_iter()
+                .map(|(row, col)| ((row - bounds[0]) as u32, (col - bounds[1]) as u32))
+                .collect(),
+        ))
+    }
+
+    /// Distinct quadtree nodes stored.
+    pub fn node_count(&self) -> usize {
+        self.store.nodes.len()
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Bound the node store, and with it the memory of a step, by
+    /// `limits.max_cache_bytes`.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+        self.store.max_nodes = max_nodes(&limits);
+    }
+
+    /// Drop the memoized results and every node the current state does not
+    /// use.
+    pub fn collect_garbage(&mut self) {
+        let (store, root) = self.store.compact(self.root);
+        self.store = store;
+        self.root = root;
+    }
+}
+
+impl HashLifeUniverse {
+    /// `set_cell()` for a cell known to be within range.
+    fn put(&mut self, row: i64, column: i64, alive: bool) {
+        while !self.contains(row, column) {
+            self.expand();
+        }
+        let half = 1i64 << (self.level - 1);
+        let (row, col) = ((row + half) as u64, (column + half) as u64);
+        self.root = self.set_below(self.root, self.level, row, col, alive);
+    }
+
+    /// `step()` after its checks: make room, then advance. Running out of
+    /// memory, retry on a `fresh` store, i.e. one just collected, and
+    /// halve the step when even that is not enough.
+    fn super_step(&mut self, j: u8, fresh: bool) -> Result<(), Error> {
+        // room for the pattern to grow by the step on every side
+        while self.level < j + 3 || !self.has_margin() {
+            if self.level == MAX_LEVEL {
+                return Err(Error::DimensionsTooLarge {
+                    width: 1 << MAX_LEVEL,
+                    height: 1 << MAX_LEVEL,
+                });
+            }
+            self.expand();
+        }
+        let result = self.store.result(self.root, j);
+        if !self.store.over_budget() {
+            self.root = result;
+            self.level -= 1;
+            self.generation += 1 << j;
+            return Ok(());
+        }
+        // the result is a placeholder, and the memo full of them
+        self.collect_garbage();
+        if self.store.over_budget() || (fresh && j == 0) {
+            return Err(Error::MemoryLimit {
+                what: "HashLife",
+                limit: self.limits.max_cache_bytes,
+            });
+        }
+        if !fresh {
+            return self.super_step(j, true);
+        }
+        self.super_step(j - 1, true)?;
+        self.super_step(j - 1, false)
+    }
+
+    fn contains(&self, row: i64, column: i64) -> bool {
+        let half = 1i64 << (self.level - 1);
+        (-half..half).contains(&row) && (-half..half).contains(&column)
+    }
+
+    /// Double the side of the root, keeping it centred on the origin.
+    fn expand(&mut self) {
+        let store = &mut self.store;
+        let empty = store.empty(self.level - 1);
+        let [nw, ne, sw, se] = store.node(self.root).children;
+        self.root = {
+            let nw = store.join([empty, empty, empty, nw]);
+            let ne = store.join([empty, empty, ne, empty]);
+            let sw = store.join([empty, sw, empty, empty]);
+            let se = store.join([se, empty, empty, empty]);
+            store.join([nw, ne, sw, se])
+        };
+        self.level += 1;
+    }
+
+    /// Whether every live cell lies in the middle quarter of the root, so
+    /// a super-step cannot move any out of the result.
+    fn has_margin(&mut self) -> bool {
+        let centre = self.store.centre(self.root);
+        let middle = self.store.centre(centre);
+        self.store.node(middle).population == self.population()
+    }
+
+    fn set_below(&mut self, id: u32, level: u8, row: u64, col: u64, alive: bool) -> u32 {
+        if level == 0 {
+            return alive as u32;
+        }
+        let half = 1u64 << (level - 1);
+        let quadrant = (row >= half) as usize * 2 + (col >= half) as usize;
+        let mut children = self.store.node(id).children;
+        children[quadrant] =
+            self.set_below(children[quadrant], level - 1, row % half, col % half, alive);
+        self.store.join(children)
+    }
+
+    fn extend_bounds(&self, id: u32, level: u8, top: i64, left: i64, bounds: &mut [i64; 4]) {
+        let node = self.store.node(id);
+        if node.population == 0 {
+            return;
+        }
+        let side = 1i64 << level;
+        // a node entirely inside the current bounds cannot extend them
+        if top >= bounds[0]
+            && left >= bounds[1]
+            && top + side <= bounds[2]
+            && left + side <= bounds[3]
+        {
+            return;
+        }
+        if level == 0 {
+            bounds[0] = bounds[0].min(top);
+            bounds[1] = bounds[1].min(left);
+            bounds[2] = bounds[2].max(top + 1);
+            bounds[3] = bounds[3].max(left + 1);
+            return;
+        }
+        let half = side / 2;
+        for (quadrant, &child) in node.children.iter().enumerate() {
+            let (dr, dc) = ((quadrant / 2) as i64 * half, (quadrant % 2) as i64 * half);
+            self.extend_bounds(child, level - 1, top + dr, left + dc, bounds);
+        }
+    }
+
+    fn collect_cells(&self, id: u32, level: u8, top: i64, left: i64, cells: &mut Vec<(i64, i64)>) {
+        let node = self.store.node(id);
+        if node.population == 0 {
+            return;
+        }
+        if level == 0 {
+            cells.push((top, left));
+            return;
+        }
+        let half = 1i64 << (level - 1);
+        for (quadrant, &child) in node.children.iter().enumerate() {
+            let (dr, dc) = ((quadrant / 2) as i64 * half, (quadrant % 2) as i64 * half);
+            self.collect_cells(child, level - 1, top + dr, left + dc, cells);
+        }
+    }
+}
+
+/// Nodes `limits` leaves room for.
+fn max_nodes(limits: &Limits) -> usize {
+    limits.max_cache_bytes / NODE_BYTES
+}
@@ -0,0 +1,303 @@
+use std::borrow::Cow;
+
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::ages::{Ages, CompressedAges};
+use crate::occupancy::{CompressedOccupancy, Occupancy};
+use crate::{arena::Arena, packed, Universe};
+
+/// Cells squeezed into a compact byte form while a universe is idle, or
+/// kept for `step_back()`.
+#[derive(Clone, Debug)]
+pub(crate) enum Compressed {
+    /// Alternating dead/alive run lengths, starting with a dead run, as
+    /// LEB128 varints.
+    Runs(Vec<u8>),
+    /// Byte-packed cells, used when runs would not be smaller (dense soups).
+    Packed(Vec<u8>),
+}
+
+impl Compressed {
+    pub(crate) fn new(cells: &FixedBitSet) -> Compressed {
+        let runs = encode_runs(cells);
+        let packed = packed::pack(cells);
+        if runs.len() < packed.len() {
+            Compressed::Runs(runs)
+        } else {
+            Compressed::Packed(packed)
+        }
+    }
+
+    pub(crate) fn restore(&self, len: usize) -> FixedBitSet {
+        self.try_restore(len)
+            .expect("compressed cells match their length")
+    }
+
+    /// `restore()` for data from outside, `None` unless it describes
+    /// exactly `len` cells.
+    pub(crate) fn try_restore(&self, len: usize) -> Option<FixedBitSet> {
+        match self {
+            Compressed::Runs(runs) => decode_runs(runs, len),
+            Compressed::Packed(bytes) => packed::unpack(bytes, len),
+        }
+    }
+
+    /// Append a tag byte and the data to `bytes`, for `read()`.
+    #[cfg_attr(not(feature = "formats"), allow(dead_code))]
+    pub(crate) fn write(&self, bytes: &mut Vec<u8>) {
+        let (tag, data) = match self {
+            Compressed::Runs(data) => (0, data),
+            Compressed::Packed(data) => (1, data),
+        };
+        bytes.push(tag);
+        bytes.extend_from_slice(data);
+    }
+
+    #[cfg_attr(not(feature = "formats"), allow(dead_code))]
+    pub(crate) fn read(bytes: &[u8]) -> Option<Compressed> {
+        match bytes.split_first()? {
+            (0, data) => Some(Compressed::Runs(data.to_vec())),
+            (1, data) => Some(Compressed::Packed(data.to_vec())),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn byte_len(&self) -> usize {
+        match self {
+            Compressed::Runs(bytes) | Compressed::Packed(bytes) => bytes.len(),
+        }
+    }
+}
+
+/// Per-cell counters (occupancy counts, ages) squeezed like `Compressed`
+/// squeezes cells: each non-zero value as the number of zeros before it
+/// and the value itself, as LEB128 varints.
+#[derive(Clone, Debug)]
+pub(crate) struct Counters {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl Counters {
+    pub(crate) fn new(values: impl ExactSizeIterator<Item = u32>) -> Counters {
+        let len = values.len();
+        let mut bytes = Vec::new();
+        let mut zeros = 0;
+        for value in values {
+            if value == 0 {
+                zeros += 1;
+                continue;
+            }
+            push_varint(&mut bytes, zeros);
+            push_varint(&mut bytes, value);
+            zeros = 0;
+        }
+        Counters { bytes, len }
+    }
+
+    pub(crate) fn restore(&self) -> Vec<u32> {
+        let mut values = Vec::with_capacity(self.len);
+        let mut bytes = &self.bytes[..];
+        while let (Some(zeros), Some(value)) = (read_varint(&mut bytes), read_varint(&mut bytes)) {
+            values.resize(values.len() + zeros as usize, 0);
+            values.push(value);
+        }
+        values.resize(self.len, 0);
+        values
+    }
+
+    pub(crate) fn byte_len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Hibernated {
+    cells: Compressed,
+    init_states: Compressed,
+    envelope: Compressed,
+    occupancy: Option<CompressedOccupancy>,
+    ages: Option<CompressedAges>,
+    len: usize,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Compress the current and initial states, the envelope and any
+    /// tracked occupancy and ages in memory, and release every per-cell
+    /// buffer, for universes that are not being simulated right now.
+    ///
+    /// While hibernating the exported buffers (`cells()`, `ages()`) are
+    /// empty; `tick()` and the editing methods wake the universe up
+    /// automatically. The deltas of the last tick are dropped, and the
+    /// scratch buffers come back with the next tick. Preallocated
+    /// universes keep their buffers, so this does nothing for them.
+    pub fn hibernate(&mut self) {
+        if self.hibernated.is_some() || self.capacity.is_some() {
+            return;
+        }
+        self.hibernated = Some(Hibernated {
+            cells: Compressed::new(&self.cells),
+            init_states: Compressed::new(&self.init_states),
+            envelope: Compressed::new(&self.envelope),
+            occupancy: self.occupancy.take().map(|occupancy| occupancy.compress()),
+            ages: self.ages.take().map(|ages| ages.compress()),
+            len: self.cells.len(),
+        });
+        self.cells = FixedBitSet::new();
+        self.init_states = FixedBitSet::new();
+        self.envelope = FixedBitSet::new();
+        self.scratch = FixedBitSet::new();
+        self.deltas = Vec::new();
+        self.arena = Arena::default();
+        self.active_tiles = None;
+        #[cfg(feature = "render")]
+        self.pixels.release();
+    }
+
+    /// Restore the bitsets and trackers of a hibernating universe.
+    pub fn wake(&mut self) {
+        if let Some(hibernated) = self.hibernated.take() {
+            self.cells = hibernated.cells.restore(hibernated.len);
+            self.init_states = hibernated.init_states.restore(hibernated.len);
+            self.envelope = hibernated.envelope.restore(hibernated.len);
+            self.occupancy = hibernated.occupancy.map(|occupancy| occupancy.restore());
+            self.ages = hibernated.ages.map(|ages| ages.restore());
+        }
+    }
+
+    pub fn is_hibernating(&self) -> bool {
+        self.hibernated.is_some()
+    }
+
+    /// Bytes held by the compressed state, or 0 when awake.
+    pub fn hibernated_byte_len(&self) -> usize {
+        self.hibernated.as_ref().map_or(0, |h| {
+            h.cells.byte_len()
+                + h.init_states.byte_len()
+                + h.envelope.byte_len()
+                + h.occupancy.as_ref().map_or(0, |o| o.byte_len())
+                + h.ages.as_ref().map_or(0, |a| a.byte_len())
+        })
+    }
+
+    /// Bytes held by the per-cell state: bitsets, scratch and spare
+    /// buffers, deltas, trackers and, while hibernating, the compressed
+    /// state. History, checkpoints and save states are counted by
+    /// `history_bytes()`, `checkpoint_bytes()` and `snapshots_byte_len()`.
+    pub fn cell_memory_byte_len(&self) -> usize {
+        let bitsets = [
+            &self.cells,
+            &self.init_states,
+            &self.envelope,
+            &self.scratch,
+        ];
+        let mut bytes: usize = bitsets.iter().map(|bits| bitset_byte_len(bits)).sum();
+        bytes += self.arena.byte_len();
+        bytes += self.deltas.capacity() * std::mem::size_of::<u32>();
+        bytes += self.occupancy.as_ref().map_or(0, Occupancy::byte_len);
+        bytes += self.ages.as_ref().map_or(0, Ages::byte_len);
+        bytes += self
+            .active_tiles
+            .as_ref()
+            .map_or(0, |tiles| tiles.byte_len());
+        #[cfg(feature = "render")]
+        {
+            bytes += self.pixels.byte_len();
+        }
+        bytes + self.hibernated_byte_len()
+    }
+}
+
+impl Universe {
+    /// The current cells, decompressed on the fly while hibernating.
+    #[cfg_attr(not(feature = "formats"), allow(dead_code))]
+    pub(crate) fn current_cells(&self) -> Cow<'_, FixedBitSet> {
+        match &self.hibernated {
+            Some(hibernated) => Cow::Owned(hibernated.cells.restore(hibernated.len)),
+            None => Cow::Borrowed(&self.cells),
+        }
+    }
+
+    /// The tracked occupancy, decompressed on the fly while hibernating.
+    pub(crate) fn current_occupancy(&self) -> Option<Cow<'_, Occupancy>> {
+        match &self.hibernated {
+            Some(hibernated) => hibernated
+                .occupancy
+                .as_ref()
+                .map(|occupancy| Cow::Owned(occupancy.restore())),
+            None => self.occupancy.as_ref().map(Cow::Borrowed),
+        }
+    }
+
+    /// The tracked ages, decompressed on the fly while hibernating.
+    pub(crate) fn current_ages(&self) -> Option<Cow<'_, Ages>> {
+        match &self.hibernated {
+            Some(hibernated) => hibernated
+                .ages
+                .as_ref()
+                .map(|ages| Cow::Owned(ages.restore())),
+            None => self.ages.as_ref().map(Cow::Borrowed),
+        }
+    }
+}
+
+pub(crate) fn bitset_byte_len(bits: &FixedBitSet) -> usize {
+    std::mem::size_of_val(bits.as_slice())
+}
+
+fn encode_runs(cells: &FixedBitSet) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut current = false;
+    let mut run = 0u32;
+    for idx in 0..cells.len() {
+        if cells[idx] != current {
+            push_varint(&mut bytes, run);
+            current = !current;
+            run = 0;
+        }
+        run += 1;
+    }
+    push_varint(&mut bytes, run);
+    bytes
+}
+
+fn decode_runs(mut bytes: &[u8], len: usize) -> Option<FixedBitSet> {
+    let mut cells = FixedBitSet::with_capacity(len);
+    let mut idx = 0usize;
+    let mut alive = false;
+    while !bytes.is_empty() {
+        let end = idx
+            .checked_add(read_varint(&mut bytes)? as usize)
+            .filter(|&end| end <= len)?;
+        if alive {
+            cells.insert_range(idx..end);
+        }
+        idx = end;
+        alive = !alive;
+    }
+    (idx == len).then_some(cells)
+}
+
+pub(crate) fn push_varint(bytes: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Read a `push_varint()` value off the front of `bytes`; `None` when it
+/// is cut short or does not fit a `u32`.
+pub(crate) fn read_varint(bytes: &mut &[u8]) -> Option<u32> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate().take(5) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return (value <= u64::from(u32::MAX)).then_some(value as u32);
+        }
+    }
+    None
+}
@@ -0,0 +1,26 @@
+use crate::Universe;
+
+/// Callback run after every generation, see `Universe::set_tick_hook()`.
+pub type TickHook = Box<dyn FnMut(&Universe)>;
+
+impl Universe {
+    /// Run `hook` after each generation computed by `tick()` (and
+    /// everything built on it), once the new state, `last_tick()` and the
+    /// series are up to date. For native embedders that log, record or
+    /// decide when to stop their own loop; replaces any previous hook.
+    pub fn set_tick_hook(&mut self, hook: TickHook) {
+        self.tick_hook = Some(hook);
+    }
+
+    pub fn clear_tick_hook(&mut self) {
+        self.tick_hook = None;
+    }
+
+    /// Called at the end of every tick.
+    pub(crate) fn run_tick_hook(&mut self) {
+        if let Some(mut hook) = self.tick_hook.take() {
+            hook(self);
+            self.tick_hook = Some(hook);
+        }
+    }
+}
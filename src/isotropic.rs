@@ -0,0 +1,228 @@
+//! Isotropic non-totalistic rules in Hensel notation, e.g. `B2-a/S12`.
+//!
+//! Within each neighbour count, the letters name the arrangements of the
+//! live neighbours up to rotation and reflection: `2a` two adjacent
+//! neighbours at a corner and an edge, `2i` two opposite edges and so on.
+//!
+//! Neighbourhoods are 9 bit patterns of the 3 x 3 block in row-major
+//! order, the cell itself in bit 4.
+//!
+//! Only the `Transitions` tables are compiled without the `rules`
+//! feature, for the neighbourhoods of the table engine.
+
+#[cfg(feature = "rules")]
+use crate::Error;
+
+/// The cell itself in a neighbourhood pattern.
+pub(crate) const CENTRE: u16 = 1 << 4;
+/// The eight neighbours in a neighbourhood pattern.
+#[cfg(feature = "rules")]
+const NEIGHBOURS: u16 = 0x1ff & !CENTRE;
+
+/// Letters for 0 to 4 live neighbours, in Hensel's order; `8 - n` live
+/// neighbours use those of `n`, for the complementary arrangements.
+#[cfg(feature = "rules")]
+const LETTERS: [&str; 5] = ["", "ce", "ceaikn", "ceaiknjqry", "ceaiknjqrytwz"];
+
+/// One arrangement for each letter of `LETTERS`, as patterns.
+#[cfg(feature = "rules")]
+const REPRESENTATIVES: [&[u16]; 5] = [
+    &[0],
+    &[1, 2],
+    &[5, 10, 3, 40, 33, 68],
+    &[69, 42, 11, 7, 98, 13, 14, 70, 41, 97],
+    &[325, 170, 15, 45, 99, 71, 106, 102, 43, 101, 105, 78, 108],
+];
+
+/// The next state of every neighbourhood pattern, one bit each.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Transitions([u64; 8]);
+
+impl Transitions {
+    /// The table giving `next(pattern)` for every pattern.
+    pub(crate) fn from_fn(next: impl Fn(u16) -> bool) -> Transitions {
+        let mut table = [0; 8];
+        for pattern in 0..512u16 {
+            if next(pattern) {
+                table[pattern as usize / 64] |= 1 << (pattern % 64);
+            }
+        }
+        Transitions(table)
+    }
+
+    pub(crate) fn get(&self, pattern: u16) -> bool {
+        self.0[pattern as usize / 64] >> (pattern % 64) & 1 == 1
+    }
+
+    /// A table from the arrangements giving births and survivals, each a
+    /// mask over the letters of the count (see `parse_counts()`).
+    #[cfg(feature = "rules")]
+    pub(crate) fn from_counts(birth: &[u16; 9], survival: &[u16; 9]) -> Transitions {
+        Transitions::from_fn(|pattern| {
+            let (count, letter) = classify(pattern & NEIGHBOURS);
+            let counts = if pattern & CENTRE != 0 {
+                survival
+            } else {
+                birth
+            };
+            counts[count] >> letter & 1 == 1
+        })
+    }
+
+    /// The letter masks giving births (`alive` false) or survivals, the
+    /// inverse of `from_counts()` for isotropic tables.
+    #[cfg(feature = "rules")]
+    pub(crate) fn counts(&self, alive: bool) -> [u16; 9] {
+        let centre = if alive { CENTRE } else { 0 };
+        let mut counts = [0; 9];
+        for (count, mask) in counts.iter_mut().enumerate() {
+            for letter in 0..classes(count) {
+                if self.get(representative(count, letter) | centre) {
+                    *mask |= 1 << letter;
+                }
+            }
+        }
+        counts
+    }
+
+    /// The table of the black/white complement, see `Rule::complement()`.
+    #[cfg(feature = "rules")]
+    pub(crate) fn complement(&self) -> Transitions {
+        Transitions::from_fn(|pattern| !self.get(!pattern & 0x1ff))
+    }
+}
+
+/// All arrangements of `count` live neighbours, as a letter mask.
+#[cfg(feature = "rules")]
+pub(crate) fn full(count: usize) -> u16 {
+    (1 << classes(count)) - 1
+}
+
+/// Which letters of each count a list like `"2-a3"` or `"12ce"` names:
+/// a digit alone stands for every arrangement, a digit followed by
+/// letters for those, and by `-` and letters for all others.
+#[cfg(feature = "rules")]
+pub(crate) fn parse_counts(text: &str) -> Result<[u16; 9], Error> {
+    let mut counts = [0; 9];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = c
+            .to_digit(9)
+            .ok_or(Error::InvalidRule("neighbour counts are 0 to 8"))? as usize;
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut letters = 0;
+        while let Some(letter) = chars.next_if(|c| !c.is_ascii_digit()) {
+            let index = letters_of(count)
+                .find(letter)
+                .ok_or(Error::InvalidRule("no such arrangement for that count"))?;
+            letters |= 1 << index;
+        }
+        counts[count] |= match (letters, negated) {
+            (0, true) => return Err(Error::InvalidRule("expected letters after '-'")),
+            (0, false) => full(count),
+            (letters, false) => letters,
+            (letters, true) => full(count) & !letters,
+        };
+    }
+    Ok(counts)
+}
+
+/// The shorter of listing and excluding letters for each count, e.g.
+/// `"2-a3"`.
+#[cfg(feature = "rules")]
+pub(crate) fn format_counts(counts: &[u16; 9]) -> String {
+    let mut text = String::new();
+    for (count, &mask) in counts.iter().enumerate() {
+        if mask == 0 {
+            continue;
+        }
+        text.push(char::from(b'0' + count as u8));
+        if mask == full(count) {
+            continue;
+        }
+        let letters = letters_of(count);
+        let listed = mask.count_ones() as usize;
+        let (negated, mask) = match listed * 2 > letters.len() {
+            true => (true, full(count) & !mask),
+            false => (false, mask),
+        };
+        if negated {
+            text.push('-');
+        }
+        text.extend(
+            letters
+                .chars()
+                .enumerate()
+                .filter(|&(index, _)| mask >> index & 1 == 1)
+                .map(|(_, letter)| letter),
+        );
+    }
+    text
+}
+
+/// Characters that may follow a neighbour count in a rulestring.
+#[cfg(feature = "rules")]
+pub(crate) fn is_hensel_letter(c: char) -> bool {
+    c == '-' || LETTERS[4].contains(c)
+}
+
+#[cfg(feature = "rules")]
+fn letters_of(count: usize) -> &'static str {
+    LETTERS[count.min(8 - count)]
+}
+
+/// Number of arrangements of `count` live neighbours, at least 1.
+#[cfg(feature = "rules")]
+fn classes(count: usize) -> usize {
+    letters_of(count).len().max(1)
+}
+
+#[cfg(feature = "rules")]
+fn representative(count: usize, letter: usize) -> u16 {
+    match count {
+        0..=4 => REPRESENTATIVES[count][letter],
+        _ => !REPRESENTATIVES[8 - count][letter] & NEIGHBOURS,
+    }
+}
+
+/// The count and letter index of the arrangement of the neighbours in
+/// `pattern`.
+#[cfg(feature = "rules")]
+fn classify(pattern: u16) -> (usize, usize) {
+    let count = pattern.count_ones() as usize;
+    let letter = (0..classes(count))
+        .find(|&letter| {
+            let mut image = representative(count, letter);
+            (0..8).any(|turn| {
+                image = if turn == 4 {
+                    reflect(image)
+                } else {
+                    rotate(image)
+                };
+                image == pattern
+            })
+        })
+        .expect("every arrangement has a letter");
+    (count, letter)
+}
+
+/// `pattern` turned a quarter clockwise.
+#[cfg(feature = "rules")]
+fn rotate(pattern: u16) -> u16 {
+    (0..9)
+        .filter(|&bit| pattern >> bit & 1 == 1)
+        .map(|bit| {
+            let (row, col) = (bit / 3, bit % 3);
+            1 << (col * 3 + 2 - row)
+        })
+        .sum()
+}
+
+/// `pattern` mirrored left to right.
+#[cfg(feature = "rules")]
+fn reflect(pattern: u16) -> u16 {
+    (0..9)
+        .filter(|&bit| pattern >> bit & 1 == 1)
+        .map(|bit| 1 << (bit / 3 * 3 + 2 - bit % 3))
+        .sum()
+}
@@ -0,0 +1,199 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{check_dimensions, packed, Error};
+
+/// Where an ant is heading; north is towards row 0.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    fn turn_right(self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    fn turn_left(self) -> Direction {
+        self.turn_right().turn_right().turn_right()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Ant {
+    row: u32,
+    column: u32,
+    direction: Direction,
+}
+
+/// Langton's Ant on a torus of black and white cells: each tick every ant
+/// turns right on a white cell and left on a black one, flips the colour
+/// of its cell and steps forward. A single ant builds its highway after
+/// about 10,000 ticks.
+///
+/// Ants move in the order they were added, so two on the same cell flip
+/// it twice.
+#[wasm_bindgen]
+pub struct Langton {
+    width: u32,
+    height: u32,
+    /// Black cells
+    cells: FixedBitSet,
+    ants: Vec<Ant>,
+    generation: u32,
+    /// `cells()` buffer
+    packed: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Langton {
+    /// An all white grid without ants. Fails with
+    /// `Error::DimensionsTooLarge` when it would have more cells than it
+    /// can address.
+    pub fn new(height: u32, width: u32) -> Result<Langton, Error> {
+        check_dimensions(width, height)?;
+        Ok(Langton {
+            width,
+            height,
+            cells: FixedBitSet::with_capacity(width as usize * height as usize),
+            ants: Vec::new(),
+            generation: 0,
+            packed: Vec::new(),
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Put an ant on `(row, column)`, heading `direction`, and return its
+    /// index for the getters below.
+    pub fn add_ant(&mut self, row: u32, column: u32, direction: Direction) -> Result<u32, Error> {
+        self.index(row, column)?;
+        self.ants.push(Ant {
+            row,
+            column,
+            direction,
+        });
+        Ok(self.ants.len() as u32 - 1)
+    }
+
+    pub fn ant_count(&self) -> u32 {
+        self.ants.len() as u32
+    }
+
+    pub fn ant_row(&self, ant: u32) -> Result<u32, Error> {
+        Ok(self.ant(ant)?.row)
+    }
+
+    pub fn ant_column(&self, ant: u32) -> Result<u32, Error> {
+        Ok(self.ant(ant)?.column)
+    }
+
+    pub fn ant_direction(&self, ant: u32) -> Result<Direction, Error> {
+        Ok(self.ant(ant)?.direction)
+    }
+
+    /// Every ant as `[row, column, row, column, ...]`, for drawing them on
+    /// top of the cells.
+    pub fn ant_positions(&self) -> Vec<u32> {
+        self.ants
+            .iter()
+            .flat_map(|ant| [ant.row, ant.column])
+            .collect()
+    }
+
+    /// Whether the cell is black, i.e. flipped an odd number of times.
+    pub fn get_cell(&self, row: u32, column: u32) -> Result<bool, Error> {
+        Ok(self.cells[self.index(row, column)?])
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, column: u32) -> Result<(), Error> {
+        let idx = self.index(row, column)?;
+        self.cells.toggle(idx);
+        Ok(())
+    }
+
+    /// Whiten every cell and remove the ants.
+    pub fn reset(&mut self) {
+        self.cells.clear();
+        self.ants.clear();
+        self.generation = 0;
+    }
+
+    pub fn tick(&mut self) {
+        let (width, height) = (self.width, self.height);
+        for ant in self.ants.iter_mut() {
+            let idx = ant.row as usize * width as usize + ant.column as usize;
+            ant.direction = if self.cells[idx] {
+                ant.direction.turn_left()
+            } else {
+                ant.direction.turn_right()
+            };
+            self.cells.toggle(idx);
+            match ant.direction {
+                Direction::North => ant.row = (ant.row + height - 1) % height,
+                Direction::East => ant.column = (ant.column + 1) % width,
+                Direction::South => ant.row = (ant.row + 1) % height,
+                Direction::West => ant.column = (ant.column + width - 1) % width,
+            }
+        }
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+
+    /// Pointer to the black cells, byte-packed in row-major order like
+    /// `Universe::cells()` so the same drawing code works; read
+    /// `cells_byte_len()` bytes from it.
+    pub fn cells(&mut self) -> *const u8 {
+        self.packed = packed::pack(&self.cells);
+        self.packed.as_ptr()
+    }
+
+    pub fn cells_byte_len(&self) -> usize {
+        packed::packed_len(self.cells.len())
+    }
+}
+
+impl Langton {
+    fn index(&self, row: u32, column: u32) -> Result<usize, Error> {
+        if row >= self.height || column >= self.width {
+            return Err(Error::OutOfBounds {
+                row: row as u64,
+                column: column as u64,
+                width: self.width,
+                height: self.height,
+            });
+        }
+        Ok(row as usize * self.width as usize + column as usize)
+    }
+
+    fn ant(&self, ant: u32) -> Result<&Ant, Error> {
+        self.ants.get(ant as usize).ok_or(Error::UnknownHandle {
+            kind: "ant",
+            id: ant,
+        })
+    }
+}
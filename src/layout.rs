@@ -0,0 +1,100 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{arena, LogLevel, Universe};
+
+/// Order in which cells are laid out in the exported buffer.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StorageOrder {
+    /// Cell `(row, col)` is at index `row * width + col`.
+    #[default]
+    RowMajor,
+    /// Cell `(row, col)` is at index `col * height + row`.
+    ColumnMajor,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    pub fn storage_order(&self) -> StorageOrder {
+        self.order
+    }
+
+    /// Switch the storage order, transposing the current and initial
+    /// states in place so no cell changes.
+    pub fn set_storage_order(&mut self, order: StorageOrder) {
+        if order == self.order {
+            return;
+        }
+        self.wake();
+        let (width, height) = self.storage_shape();
+        transpose_into(&self.cells, &mut self.scratch, width, height);
+        std::mem::swap(&mut self.cells, &mut self.scratch);
+        transpose_into(&self.init_states, &mut self.scratch, width, height);
+        std::mem::swap(&mut self.init_states, &mut self.scratch);
+        transpose_into(&self.envelope, &mut self.scratch, width, height);
+        std::mem::swap(&mut self.envelope, &mut self.scratch);
+        if let Some(occupancy) = &mut self.occupancy {
+            occupancy.transpose(width, height);
+        }
+        if let Some(ages) = &mut self.ages {
+            ages.transpose(width, height);
+        }
+        self.order = order;
+        self.reset_checkpoints();
+        self.rewind.clear();
+        log_at!(LogLevel::Info, "layout", "storage order {:?}", order);
+    }
+}
+
+impl Universe {
+    /// Dimensions of the grid as it is stored: `(stride, lines)`, so that a
+    /// storage index is `line * stride + offset` whatever the order.
+    pub(crate) fn storage_shape(&self) -> (u32, u32) {
+        match self.order {
+            StorageOrder::RowMajor => (self.width, self.height),
+            StorageOrder::ColumnMajor => (self.height, self.width),
+        }
+    }
+
+    /// The cells in row-major order, as used by the exchange formats.
+    #[cfg_attr(not(feature = "formats"), allow(dead_code))]
+    pub(crate) fn row_major_cells(&self) -> FixedBitSet {
+        let cells = self.current_cells();
+        match self.order {
+            StorageOrder::RowMajor => cells.into_owned(),
+            StorageOrder::ColumnMajor => {
+                let mut transposed = FixedBitSet::with_capacity(cells.len());
+                transpose_into(&cells, &mut transposed, self.height, self.width);
+                transposed
+            }
+        }
+    }
+}
+
+impl Universe {
+    /// `cells` of this universe's dimensions stored in `order`, converted
+    /// to the current storage order.
+    pub(crate) fn in_storage_order(&self, cells: FixedBitSet, order: StorageOrder) -> FixedBitSet {
+        if order == self.order {
+            return cells;
+        }
+        let (stride, lines) = match order {
+            StorageOrder::RowMajor => (self.width, self.height),
+            StorageOrder::ColumnMajor => (self.height, self.width),
+        };
+        let mut converted = FixedBitSet::with_capacity(cells.len());
+        transpose_into(&cells, &mut converted, stride, lines);
+        converted
+    }
+}
+
+/// Transpose a grid stored as `lines` lines of `stride` cells into `dst`,
+/// reusing its buffer.
+fn transpose_into(src: &FixedBitSet, dst: &mut FixedBitSet, stride: u32, lines: u32) {
+    arena::resize_in_place(dst, src.len());
+    for idx in src.ones() {
+        let (line, offset) = (idx as u32 / stride, idx as u32 % stride);
+        dst.insert((offset * lines + line) as usize);
+    }
+}
@@ -0,0 +1,159 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Pattern;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    term: String,
+    definition: String,
+    diagrams: Vec<Pattern>,
+}
+
+/// A pattern dictionary read from the Life Lexicon's plain text edition.
+///
+/// Entries start with `:term:` at the beginning of a line, followed by the
+/// definition, which may continue on the next lines. Diagrams are
+/// tab-indented plaintext rows of `.` and `O`; each becomes a `Pattern`
+/// named after its term. Everything before the first entry is skipped.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lexicon {
+    entries: Vec<Entry>,
+}
+
+#[wasm_bindgen]
+impl Lexicon {
+    pub fn parse(text: &str) -> Lexicon {
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut diagram = String::new();
+
+        for line in text.lines() {
+            let line = line.trim_end();
+            if let Some((term, rest)) = entry_start(line) {
+                flush_diagram(entries.last_mut(), &mut diagram);
+                entries.push(Entry {
+                    term: term.to_owned(),
+                    definition: String::new(),
+                    diagrams: Vec::new(),
+                });
+                append_text(&mut entries.last_mut().unwrap().definition, rest);
+                continue;
+            }
+            let entry = match entries.last_mut() {
+                Some(entry) => entry,
+                None => continue,
+            };
+            match line.strip_prefix('\t') {
+                Some(row) if !row.trim().is_empty() => {
+                    diagram.push_str(row.trim());
+                    diagram.push('\n');
+                }
+                _ => {
+                    flush_diagram(Some(&mut *entry), &mut diagram);
+                    append_text(&mut entry.definition, line);
+                }
+            }
+        }
+        flush_diagram(entries.last_mut(), &mut diagram);
+        Lexicon { entries }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.entries.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn term(&self, index: u32) -> Option<String> {
+        self.entry(index).map(|entry| entry.term.clone())
+    }
+
+    /// The definition with line breaks folded into spaces. References to
+    /// other terms keep the lexicon's `{term}` markup.
+    pub fn definition(&self, index: u32) -> Option<String> {
+        self.entry(index).map(|entry| entry.definition.clone())
+    }
+
+    pub fn diagram_count(&self, index: u32) -> u32 {
+        self.entry(index)
+            .map_or(0, |entry| entry.diagrams.len() as u32)
+    }
+
+    pub fn diagram(&self, index: u32, diagram: u32) -> Option<Pattern> {
+        self.entry(index)?.diagrams.get(diagram as usize).cloned()
+    }
+
+    /// Index of the entry for `term`, ignoring case.
+    pub fn lookup(&self, term: &str) -> Option<u32> {
+        self.entries
+            .iter()
+            .position(|entry| entry.term.eq_ignore_ascii_case(term))
+            .map(|index| index as u32)
+    }
+
+    /// Indices of the entries mentioning `query`, ignoring case: those
+    /// whose term contains it first, then those whose definition does.
+    pub fn search(&self, query: &str) -> Vec<u32> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(bool, u32)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                if entry.term.to_lowercase().contains(&query) {
+                    Some((false, index as u32))
+                } else if entry.definition.to_lowercase().contains(&query) {
+                    Some((true, index as u32))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        matches.sort_unstable();
+        matches.into_iter().map(|(_, index)| index).collect()
+    }
+}
+
+impl Lexicon {
+    fn entry(&self, index: u32) -> Option<&Entry> {
+        self.entries.get(index as usize)
+    }
+}
+
+/// `(term, rest of line)` when `line` starts a new `:term:` entry.
+fn entry_start(line: &str) -> Option<(&str, &str)> {
+    let (term, rest) = line.strip_prefix(':')?.split_once(':')?;
+    if term.is_empty() {
+        return None;
+    }
+    Some((term, rest))
+}
+
+fn append_text(definition: &mut String, text: &str) {
+    for word in text.split_whitespace() {
+        if !definition.is_empty() {
+            definition.push(' ');
+        }
+        definition.push_str(word);
+    }
+}
+
+/// Turn the collected diagram rows into a pattern of `entry`; rows that
+/// are not a diagram after all (tab-indented tables) become text.
+fn flush_diagram(entry: Option<&mut Entry>, diagram: &mut String) {
+    if diagram.is_empty() {
+        return;
+    }
+    if let Some(entry) = entry {
+        match Pattern::from_plaintext(diagram) {
+            Ok(mut pattern) => {
+                pattern.set_name(&entry.term);
+                entry.diagrams.push(pattern);
+            }
+            Err(_) => append_text(&mut entry.definition, diagram),
+        }
+    }
+    diagram.clear();
+}
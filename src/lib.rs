@@ -1,13 +1,210 @@
 #[macro_use]
 mod utils;
-
-use std::u32;
-
-use fixedbitset::FixedBitSet;
-use js_sys::Math;
+#[macro_use]
+mod logging;
+mod ages;
+#[cfg(feature = "formats")]
+mod apgcode;
+mod arena;
+mod boundary;
+#[cfg(any(feature = "generations", feature = "wireworld"))]
+mod byte_grid;
+mod cancel;
+#[cfg(feature = "fetch")]
+mod catagolue;
+#[cfg(feature = "patterns")]
+mod catalog;
+#[cfg(all(feature = "analysis", feature = "formats"))]
+mod census;
+mod checkpoint;
+mod clipboard;
+#[cfg(feature = "analysis")]
+mod compare;
+mod components;
+mod delta;
+mod draw;
+#[cfg(feature = "elementary")]
+mod elementary;
+mod engine;
+#[cfg(feature = "analysis")]
+mod ensemble;
+mod envelope;
+mod error;
+mod events;
+#[cfg(feature = "fetch")]
+mod fetch;
+#[cfg(feature = "generations")]
+mod generations;
+#[cfg(feature = "analysis")]
+mod genetic;
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "hashlife")]
+mod hashlife;
+mod hibernate;
+mod hook;
+mod isotropic;
+#[cfg(feature = "langton")]
+mod langton;
+mod layout;
+#[cfg(feature = "patterns")]
+mod lexicon;
+#[cfg(feature = "formats")]
+mod life106;
+mod limits;
+#[cfg(feature = "ltl")]
+mod ltl;
+mod manager;
+mod merge;
+mod metapixel;
+#[cfg(feature = "native")]
+mod native;
+mod neighborhood;
+mod noise;
+mod occupancy;
+mod pacing;
+mod packed;
+mod pattern;
+#[cfg(feature = "analysis")]
+mod periodicity;
+#[cfg(feature = "render")]
+mod pixels;
+mod placement;
+mod prealloc;
+mod progress;
+#[cfg(feature = "render")]
+mod render;
+mod reorient;
+mod resize;
+mod rewind;
+#[cfg(feature = "formats")]
+mod rle;
+mod rng;
+mod rule;
+#[cfg(feature = "analysis")]
+mod ruleinfo;
+#[cfg(feature = "analysis")]
+mod run;
+mod savestate;
+#[cfg(feature = "serde")]
+mod serialize;
+mod series;
+#[cfg(feature = "formats")]
+mod share;
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+mod simd;
+mod simulate;
+#[cfg(feature = "formats")]
+mod snapshot;
+#[cfg(feature = "sparse")]
+mod sparse;
+mod stats;
+mod status;
+mod table;
+mod tiles;
+#[cfg(feature = "timing")]
+mod timing;
+mod topology;
+mod view;
+#[cfg(feature = "wireworld")]
+mod wireworld;
+mod zobrist;
+
+use std::collections::{HashMap, VecDeque};
+
+use ages::Ages;
+use arena::Arena;
+use boundary::BoundaryTracker;
+use checkpoint::Checkpoints;
+use events::EventLog;
+use fixedbitset::{Block, FixedBitSet};
+use hibernate::Hibernated;
+use occupancy::Occupancy;
+use pacing::Pacer;
+use progress::ProgressReporter;
+use rewind::Rewind;
+use series::Series;
 use utils::Timer;
 use wasm_bindgen::prelude::*;
 
+pub use boundary::BoundaryTraffic;
+pub use cancel::CancelToken;
+#[cfg(feature = "fetch")]
+pub use catagolue::{fetch_catagolue_object, fetch_catagolue_soup, CatagolueObject};
+#[cfg(feature = "patterns")]
+pub use catalog::{PatternCatalog, PatternCategory, Thumbnail};
+#[cfg(all(feature = "analysis", feature = "formats"))]
+pub use census::Census;
+#[cfg(feature = "analysis")]
+pub use compare::{
+    compare_engines, compare_rules, ComparisonConfig, ComparisonReport, EngineResult,
+    RuleComparison,
+};
+pub use components::Components;
+pub use draw::BrushShape;
+#[cfg(feature = "elementary")]
+pub use elementary::Elementary;
+pub use engine::{simd_enabled, Engine};
+#[cfg(feature = "analysis")]
+pub use ensemble::{
+    run_ensemble, run_ensemble_shard, Distribution, EnsembleConfig, EnsembleReport, GrowthClass,
+    Replicate,
+};
+pub use error::Error;
+pub use events::{EventKind, EVENT_RECORD_WORDS};
+#[cfg(feature = "fetch")]
+pub use fetch::load_pattern_from_url;
+#[cfg(feature = "generations")]
+pub use generations::{GenerationsRule, GenerationsUniverse};
+#[cfg(feature = "analysis")]
+pub use genetic::{Fitness, GeneticSearch, Genome, SearchConfig};
+#[cfg(feature = "gpu")]
+pub use gpu::GpuUniverse;
+#[cfg(feature = "hashlife")]
+pub use hashlife::HashLifeUniverse;
+pub use hook::TickHook;
+#[cfg(feature = "langton")]
+pub use langton::{Direction, Langton};
+pub use layout::StorageOrder;
+#[cfg(feature = "patterns")]
+pub use lexicon::Lexicon;
+pub use limits::Limits;
+pub use logging::LogLevel;
+#[cfg(feature = "logging")]
+pub use logging::{log_level, set_log_level};
+#[cfg(feature = "ltl")]
+pub use ltl::{LargerThanLifeUniverse, LtlRule};
+pub use manager::UniverseManager;
+pub use merge::MergeOp;
+pub use metapixel::{expand_to_metapixels, OTCA_PITCH};
+pub use neighborhood::Neighborhood;
+pub use pattern::{Pattern, PatternMeta, PlacementPolicy};
+#[cfg(feature = "analysis")]
+pub use periodicity::{population_periods, PeriodCandidate};
+#[cfg(feature = "render")]
+pub use pixels::Palette;
+pub use placement::{Placement, Rotation, Transform};
+pub use progress::{Progress, ProgressHook};
+pub use resize::Anchor;
+pub use rng::{Entropy, EntropySource, JsCallback, MathRandom, Xoshiro256};
+#[cfg(feature = "rules")]
+pub use rule::complement_rule;
+pub use rule::Rule;
+#[cfg(feature = "analysis")]
+pub use ruleinfo::{analyze_rule, RuleReport};
+#[cfg(feature = "analysis")]
+pub use run::{RunReport, StabilizationReport, StopCondition, StopReason};
+#[cfg(feature = "sparse")]
+pub use sparse::SparseUniverse;
+pub use stats::TickStats;
+pub use status::{Status, StatusKind};
+#[cfg(feature = "timing")]
+pub use timing::{reset_timings, timing_names, timing_summary, PhaseTimer, TimingSummary};
+pub use topology::Topology;
+pub use view::{memory_generation, CellsView};
+#[cfg(feature = "wireworld")]
+pub use wireworld::{WireState, Wireworld};
+
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
 #[cfg(feature = "wee_alloc")]
@@ -22,24 +219,116 @@ pub struct Universe {
     cells: FixedBitSet,
     /// Initial state of cells
     init_states: FixedBitSet,
+    /// Cells alive at any point since the last reset, see `envelope()`
+    envelope: FixedBitSet,
+    /// Zobrist hash of `cells`, see `state_hash()`
+    zobrist: u64,
+    /// Live cells, kept up to date by ticks and edits
+    population: u32,
+    /// Per-cell alive counts, when tracked
+    occupancy: Option<Occupancy>,
+    /// Generations each cell has been alive for, when tracked
+    ages: Option<Ages>,
+    /// Births and deaths caused by the wrap, when tracked
+    boundary: Option<BoundaryTracker>,
+    /// Population, births and deaths of the recent generations
+    series: Series,
+    limits: Limits,
+    /// Number of ticks since the universe was created or reset
+    generation: u32,
+    /// Summary of the most recent tick
+    last_tick: TickStats,
+    /// Storage indices of the cells the most recent tick flipped
+    deltas: Vec<u32>,
+    /// Generation and state hash of the states before the current one,
+    /// oldest first, see `status()`
+    recent_states: VecDeque<(u32, u64)>,
+    events: EventLog,
+    order: StorageOrder,
+    /// Compressed state while hibernating, see `hibernate()`
+    hibernated: Option<Hibernated>,
+    checkpoints: Checkpoints,
+    /// Recent generations kept for `step_back()`
+    rewind: Rewind,
+    /// Buffer the next generation is computed into, reused every tick
+    scratch: FixedBitSet,
+    /// Spare buffers reused when the universe is resized
+    arena: Arena,
+    /// Maximum number of cells in preallocated mode
+    capacity: Option<usize>,
+    /// Algorithm used by `tick()`
+    engine: Engine,
+    /// What lies beyond the edges
+    topology: Topology,
+    /// Which cells count as neighbours
+    neighborhood: Neighborhood,
+    /// Lookup tables of `Engine::Naive`, for the current rule
+    transitions: Option<table::TransitionTables>,
+    /// Whether edits also apply to `init_states`
+    edits_update_init_state: bool,
+    /// Bookmarks of `save_snapshot()`, by name
+    save_states: HashMap<String, savestate::SaveState>,
+    /// What `paint_at()` paints with
+    brush: draw::Brush,
+    /// Probabilities of births and survivals, see `set_noise()`
+    noise: Option<noise::Noise>,
+    /// What `Engine::ActiveTiles` remembers between ticks
+    active_tiles: Option<tiles::ActiveTiles>,
+    /// Birth and survival conditions applied by `tick()`
+    rule: Rule,
+    /// Native callback run after every tick
+    tick_hook: Option<TickHook>,
+    /// Token that aborts long-running calls
+    cancel: Option<CancelToken>,
+    /// Progress of long-running calls
+    progress: ProgressReporter,
+    /// Wall-clock pacing of `advance()`
+    pacer: Pacer,
+    /// Buffer and colours of `render_rgba()`
+    #[cfg(feature = "render")]
+    pixels: pixels::Pixels,
+    /// Byte-packed copy handed out by `cells()` on big-endian targets
+    #[cfg(target_endian = "big")]
+    export: std::cell::RefCell<Vec<u8>>,
+}
+
+/// Fail unless a `width` x `height` universe has few enough cells to
+/// index them with `u32`s.
+pub(crate) fn check_dimensions(width: u32, height: u32) -> Result<(), Error> {
+    match width.checked_mul(height) {
+        Some(_) => Ok(()),
+        None => Err(Error::DimensionsTooLarge {
+            width: width as u64,
+            height: height as u64,
+        }),
+    }
 }
 
-#[wasm_bindgen]
 impl Universe {
     /// Constructor for a new Universe with the given height and width.
     /// All cells are uninitialized. Set cell values through `set_cells()`
+    ///
+    /// Panics when `width * height` does not fit in a `u32`; `try_new()`
+    /// reports that as an error instead, and is what JavaScript gets as
+    /// `Universe.new()`.
     pub fn new(height: u32, width: u32) -> Self {
+        Universe::try_new(height, width).expect("width * height does not fit in a u32")
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// `new()`, failing with `Error::DimensionsTooLarge` instead of
+    /// panicking when the universe would have more cells than it can
+    /// address.
+    #[wasm_bindgen(js_name = new)]
+    pub fn try_new(height: u32, width: u32) -> Result<Universe, Error> {
         // make a error panic message more informative
         utils::set_panic_hook();
 
+        check_dimensions(width, height)?;
         let cells = FixedBitSet::with_capacity((width * height) as usize);
-        let init_states = cells.clone();
-        Universe {
-            width,
-            height,
-            cells,
-            init_states,
-        }
+        Ok(Universe::from_cells(width, height, cells))
     }
 
     pub fn new_fixed() -> Self {
@@ -51,14 +340,7 @@ impl Universe {
         for i in 0..size {
             cells.set(i, i % 2 == 0 || i % 7 == 0);
         }
-        let init_states = cells.clone();
-
-        Universe {
-            width,
-            height,
-            cells,
-            init_states,
-        }
+        Universe::from_cells(width, height, cells)
     }
 
     pub fn new_randomized() -> Self {
@@ -66,135 +348,152 @@ impl Universe {
         let height = 64u32;
         let size = (width * height) as usize;
 
-        let mut cells = FixedBitSet::with_capacity(size);
-
-        for i in 0..size {
-            cells.set(i, Math::random() >= 0.5);
-        }
-        let init_states = cells.clone();
-
-        Universe {
-            width,
-            height,
-            cells,
-            init_states,
-        }
+        let cells = rng::random_cells(size, 0.5, &mut MathRandom);
+        Universe::from_cells(width, height, cells)
     }
 
     /// Set the width of the universe.
     ///
-    /// Resets all cells to the dead state.
-    pub fn set_width(&mut self, width: u32) {
+    /// Resets all cells (and the initial state) to the dead state, see
+    /// `resize()` to keep them. Fails when the universe would be too large
+    /// or a preallocated one would outgrow its capacity.
+    pub fn set_width(&mut self, width: u32) -> Result<(), Error> {
+        self.check_capacity(width, self.height)?;
         self.width = width;
-        self.reset_cells();
+        self.resize_storage();
+        Ok(())
     }
 
     /// Set the height of the universes.
     ///
-    /// Resets all cells to the dead state.
-    pub fn set_height(&mut self, height: u32) {
+    /// Resets all cells (and the initial state) to the dead state, see
+    /// `resize()` to keep them. Fails when the universe would be too large
+    /// or a preallocated one would outgrow its capacity.
+    pub fn set_height(&mut self, height: u32) -> Result<(), Error> {
+        self.check_capacity(self.width, height)?;
         self.height = height;
-        self.reset_cells();
+        self.resize_storage();
+        Ok(())
     }
 
     /// Reset all cells to DEAD
     pub fn reset_cells(&mut self) {
+        self.wake();
         let size = (self.width * self.height) as usize;
         self.cells.set_range(0..size, false);
+        self.restart_history();
     }
 
     /// Reset to the initial state
     pub fn reset_init_state(&mut self) {
-        self.cells = self.init_states.clone();
+        self.wake();
+        self.cells.clone_from(&self.init_states);
+        self.restart_history();
     }
 
-    fn get_index(&self, row: u32, column: u32) -> usize {
-        return (row * self.width + column) as usize;
+    /// Make the current cells the initial state, so `reset_init_state()`
+    /// comes back to them rather than to the original soup or pattern.
+    /// The generation count and history are kept.
+    pub fn commit_init_state(&mut self) {
+        self.wake();
+        self.init_states.clone_from(&self.cells);
     }
 
-    /// The live_neighbor_count method uses deltas and modulo to avoid special casing the edges
-    /// of the universe with ifs. When applying a delta of -1, we add self.height - 1 and let
-    /// the modulo do its thing, rather than attempting to subtract 1. row and column can be 0,
-    /// and if we attempted to subtract 1 from them, there would be an unsigned integer underflow.
-    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let mut count = 0;
-
-        let north = if row == 0 { self.height - 1 } else { row - 1 };
-
-        let south = if row == self.height - 1 { 0 } else { row + 1 };
-
-        let west = if column == 0 {
-            self.width - 1
-        } else {
-            column - 1
-        };
-
-        let east = if column == self.width - 1 {
-            0
-        } else {
-            column + 1
-        };
-
-        let nw = self.get_index(north, west);
-        count += self.cells[nw] as u8;
-
-        let n = self.get_index(north, column);
-        count += self.cells[n] as u8;
-
-        let ne = self.get_index(north, east);
-        count += self.cells[ne] as u8;
-
-        let w = self.get_index(row, west);
-        count += self.cells[w] as u8;
+    /// Whether edits (`toggle_cell()`, `set_cells()`, the drawing tools,
+    /// `paste()`, ...) also apply to the initial state, re-baselining it
+    /// as the user curates a setup. Off by default.
+    pub fn set_edits_update_init_state(&mut self, enabled: bool) {
+        self.edits_update_init_state = enabled;
+    }
 
-        let e = self.get_index(row, east);
-        count += self.cells[e] as u8;
+    pub fn edits_update_init_state(&self) -> bool {
+        self.edits_update_init_state
+    }
 
-        let sw = self.get_index(south, west);
-        count += self.cells[sw] as u8;
+    fn check_bounds(&self, row: u64, column: u64) -> Result<(), Error> {
+        if row < self.height as u64 && column < self.width as u64 {
+            return Ok(());
+        }
+        Err(Error::OutOfBounds {
+            row,
+            column,
+            width: self.width,
+            height: self.height,
+        })
+    }
 
-        let s = self.get_index(south, column);
-        count += self.cells[s] as u8;
+    /// Called after every edit of the cell at `idx`.
+    fn follow_edit(&mut self, idx: usize) {
+        if self.edits_update_init_state {
+            self.init_states.set(idx, self.cells[idx]);
+        }
+    }
 
-        let se = self.get_index(south, east);
-        count += self.cells[se] as u8;
+    /// Start over at generation 0 from the current cells: everything
+    /// recorded about earlier generations is dropped.
+    fn restart_history(&mut self) {
+        self.rehash();
+        self.reset_envelope();
+        self.restart_occupancy();
+        self.restart_ages();
+        self.restart_boundary_traffic();
+        self.reset_checkpoints();
+        self.rewind.clear();
+        self.forget_states();
+        self.generation = 0;
+        self.last_tick = TickStats::default();
+        self.deltas.clear();
+        self.series.restart(self.generation, self.population);
+    }
 
-        count
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        match self.order {
+            StorageOrder::RowMajor => (row * self.width + column) as usize,
+            StorageOrder::ColumnMajor => (column * self.height + row) as usize,
+        }
     }
 
     pub fn tick(&mut self) {
+        self.wake();
         let _timer = Timer::new("Universe::tick");
-        let mut next = self.cells.clone();
-
-        for row in 0..self.height {
-            for col in 0..self.width {
-                let live_neighbors = self.live_neighbor_count(row, col);
-                let idx = self.get_index(row, col);
-                let cell = self.cells[idx];
-
-                next.set(
-                    idx,
-                    match (cell, live_neighbors) {
-                        (true, n) if n < 2 => false,
-                        (true, n) if n > 3 => false,
-                        (false, 3) => true,
-                        (otherwise, _) => otherwise,
-                    },
-                );
-
-                // if next[idx] != cell {
-                //     log!(
-                //         "cell at ({},{}) changes from {} to {}",
-                //         row,
-                //         col,
-                //         cell,
-                //         next[idx],
-                //     );
-                // }
-            }
+        let mut next = std::mem::take(&mut self.scratch);
+        // only the transition tables know other neighbourhoods and which
+        // arrangements of the neighbours count
+        let engine = if self.neighborhood == Neighborhood::Moore && self.rule.is_totalistic() {
+            self.engine
+        } else {
+            self.active_tiles = None;
+            Engine::Naive
+        };
+        match engine {
+            Engine::Naive => self.step_table(&mut next),
+            Engine::BitParallel => self.step_bit_parallel(&mut next),
+            #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+            Engine::Simd => self.step_simd(&mut next),
+            #[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+            Engine::Simd => self.step_bit_parallel(&mut next),
+            Engine::ActiveTiles => self.step_active_tiles(&mut next),
+            Engine::HashLife => self.step_bit_parallel(&mut next),
+        }
+        if let Some(noise) = &mut self.noise {
+            noise.apply(&self.cells, &mut next);
+            // the tiles the engine saw settle may not have
+            self.active_tiles = None;
         }
+        self.finish_tick(next);
+    }
 
-        self.cells = next;
+    /// Tick `generations` times, stopping early when cancelled.
+    ///
+    /// Fails when `generations` exceeds `Limits::max_generations_per_call`.
+    pub fn tick_many(&mut self, generations: u32) -> Result<(), Error> {
+        self.check_generations(generations)?;
+        for done in 0..generations {
+            self.progress_step(done, generations)?;
+            self.tick();
+        }
+        self.report_progress(generations, generations);
+        Ok(())
     }
 
     pub fn width(&self) -> u32 {
@@ -205,32 +504,341 @@ impl Universe {
         self.height
     }
 
-    pub fn cells(&self) -> *const usize {
-        self.cells.as_slice().as_ptr()
+    /// Pointer to the cells in byte-packed layout: byte `i` holds cells
+    /// `8 * i .. 8 * i + 8`, lowest cell index in the least significant bit.
+    /// Read `cells_byte_len()` bytes from it.
+    ///
+    /// The layout does not depend on the target's word size, so the same
+    /// JavaScript works on wasm32 and wasm64.
+    pub fn cells(&self) -> *const u8 {
+        self.export_ptr(&self.cells)
+    }
+
+    /// Pointer to the initial state, in the same layout as `cells()`.
+    pub fn init_states(&self) -> *const u8 {
+        self.export_ptr(&self.init_states)
+    }
+
+    /// Number of storage words backing the cells.
+    pub fn cells_len_words(&self) -> usize {
+        self.cells.as_slice().len()
     }
 
-    pub fn init_states(&self) -> *const usize {
-        self.init_states.as_slice().as_ptr()
+    /// Bits per storage word (32 on wasm32).
+    pub fn word_bits(&self) -> u32 {
+        usize::BITS
     }
 
-    pub fn toggle_cell(&mut self, row: u32, column: u32) {
+    /// Number of bytes to read from the `cells()` pointer.
+    pub fn cells_byte_len(&self) -> usize {
+        packed::packed_len(self.cells.len())
+    }
+
+    /// The live cells as flat `[row, column, row, column, ...]` pairs, a
+    /// `Uint32Array` in JavaScript, e.g. to draw a sparse board as
+    /// sprites. The scan skips whole empty words; cells come in storage
+    /// order.
+    pub fn live_cells(&self) -> Vec<u32> {
+        let cells = self.current_cells();
+        let (stride, _) = self.storage_shape();
+        let mut coordinates = Vec::with_capacity(2 * self.population as usize);
+        for idx in cells.ones() {
+            let (line, offset) = (idx as u32 / stride, idx as u32 % stride);
+            coordinates.extend_from_slice(&match self.order {
+                StorageOrder::RowMajor => [line, offset],
+                StorageOrder::ColumnMajor => [offset, line],
+            });
+        }
+        coordinates
+    }
+
+    /// `[top, left, bottom, right]` of the live cells, bottom and right
+    /// exclusive like `HashLifeUniverse::bounding_box()`; empty when no
+    /// cell is alive. Found in one scan that skips whole empty words.
+    pub fn bounding_box(&self) -> Vec<u32> {
+        let cells = self.current_cells();
+        let (stride, _) = self.storage_shape();
+        let mut bounds = [u32::MAX, u32::MAX, 0, 0];
+        for idx in cells.ones() {
+            let (line, offset) = (idx as u32 / stride, idx as u32 % stride);
+            let (row, column) = match self.order {
+                StorageOrder::RowMajor => (line, offset),
+                StorageOrder::ColumnMajor => (offset, line),
+            };
+            bounds = [
+                bounds[0].min(row),
+                bounds[1].min(column),
+                bounds[2].max(row + 1),
+                bounds[3].max(column + 1),
+            ];
+        }
+        if bounds[2] == 0 {
+            return Vec::new();
+        }
+        bounds.to_vec()
+    }
+
+    /// Whether the cell at `(row, column)` is alive. Fails when it is
+    /// outside the universe.
+    pub fn cell(&self, row: u32, column: u32) -> Result<bool, Error> {
+        self.check_bounds(row as u64, column as u64)?;
+        Ok(self.current_cells()[self.get_index(row, column)])
+    }
+
+    /// `set_cells()` for JavaScript, with the cells as flat `[row, column,
+    /// row, column, ...]` pairs, e.g. a `Uint32Array`.
+    ///
+    /// Fails without changing anything when a cell is outside the
+    /// universe or a column is missing.
+    pub fn set_cells_js(&mut self, flat: &[u32]) -> Result<(), Error> {
+        self.paint(flat, true)
+    }
+
+    /// Flip the cell at `(row, column)`. Fails when it is outside the
+    /// universe.
+    pub fn toggle_cell(&mut self, row: u32, column: u32) -> Result<(), Error> {
+        self.check_bounds(row as u64, column as u64)?;
+        self.wake();
         let idx = self.get_index(row, column);
         self.cells.toggle(idx);
+        self.flip_hash(idx);
+        self.forget_states();
+        self.edit_age(idx);
+        if self.cells[idx] {
+            self.population += 1;
+        } else {
+            self.population -= 1;
+        }
+        self.invalidate_checkpoints(Some(idx));
+        self.follow_edit(idx);
+        Ok(())
     }
 }
 
 /// No WASM bindgen. No expose to JavaScript
 impl Universe {
+    /// Install the generation computed by an engine and do the per-tick
+    /// bookkeeping (statistics, events, checkpoints) from the word-level
+    /// difference, independent of the engine used.
+    fn finish_tick(&mut self, next: FixedBitSet) {
+        let was_changing = self.generation == 0 || self.last_tick.changed;
+        self.record_state();
+        let previous = std::mem::replace(&mut self.cells, next);
+        self.envelope.union_with(&self.cells);
+        if let Some(occupancy) = &mut self.occupancy {
+            occupancy.record(&self.cells);
+        }
+        if let Some(ages) = &mut self.ages {
+            ages.record(&previous, &self.cells);
+        }
+        self.generation += 1;
+
+        let mut births = 0;
+        let mut deaths = 0;
+        let mut zobrist = self.zobrist;
+        self.deltas.clear();
+        let trace = logging::enabled(LogLevel::Trace);
+        let (stride, _) = self.storage_shape();
+        let words = previous.as_slice().iter().zip(self.cells.as_slice());
+        for (i, (&old, &new)) in words.enumerate() {
+            let changed = old ^ new;
+            if changed == 0 {
+                continue;
+            }
+            births += (changed & new).count_ones();
+            deaths += (changed & old).count_ones();
+
+            let first = i * Block::BITS as usize;
+            self.checkpoints.mark(first);
+            let wants_cells = self.events.wants_cells();
+            let mut bits = changed;
+            while bits != 0 {
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                zobrist ^= self.cell_key(first + bit);
+                self.deltas.push((first + bit) as u32);
+                if trace {
+                    let idx = (first + bit) as u32;
+                    let (line, offset) = (idx / stride, idx % stride);
+                    let (row, col) = match self.order {
+                        StorageOrder::RowMajor => (line, offset),
+                        StorageOrder::ColumnMajor => (offset, line),
+                    };
+                    let alive = new & (1 << bit) != 0;
+                    log_at!(
+                        LogLevel::Trace,
+                        "tick",
+                        "cell at ({},{}) changes from {} to {}",
+                        row,
+                        col,
+                        !alive,
+                        alive,
+                    );
+                }
+                if !wants_cells {
+                    continue;
+                }
+                let kind = if new & (1 << bit) != 0 {
+                    EventKind::Birth
+                } else {
+                    EventKind::Death
+                };
+                if self.events.wants(kind) {
+                    self.events
+                        .push(kind, self.generation, (first + bit) as u32);
+                }
+            }
+        }
+
+        self.zobrist = zobrist;
+        self.population = self.population + births - deaths;
+        self.last_tick = TickStats {
+            generation: self.generation,
+            births,
+            deaths,
+            population: self.population,
+            changed: births + deaths > 0,
+        };
+        self.series
+            .record(&self.last_tick, self.limits.max_history_bytes);
+        if self.events.mask() != 0 {
+            self.record_generation_events(&previous, was_changing);
+        }
+        self.record_boundary_traffic(&previous);
+        self.record_rewind(&previous);
+        self.auto_checkpoint();
+        self.scratch = previous;
+        self.run_tick_hook();
+    }
+
+    /// Swap in dead buffers matching the current dimensions, recycling
+    /// the old ones through the arena.
+    ///
+    /// Preallocated universes resize their own buffers in place instead.
+    fn resize_storage(&mut self) {
+        self.wake();
+        let size = (self.width * self.height) as usize;
+        log_at!(
+            LogLevel::Info,
+            "resize",
+            "{}x{} ({} cells)",
+            self.width,
+            self.height,
+            size
+        );
+        if self.capacity.is_some() {
+            for bits in [&mut self.cells, &mut self.init_states, &mut self.scratch] {
+                arena::resize_in_place(bits, size);
+            }
+            self.reset_cells();
+            return;
+        }
+
+        let cells = self.arena.take(size);
+        let init_states = self.arena.take(size);
+        let old_cells = std::mem::replace(&mut self.cells, cells);
+        let old_init_states = std::mem::replace(&mut self.init_states, init_states);
+        let old_scratch = std::mem::take(&mut self.scratch);
+        for bits in [old_cells, old_init_states, old_scratch] {
+            self.arena.recycle(bits);
+        }
+        self.reset_cells();
+    }
+
+    /// On little-endian targets (all of wasm) the storage words already
+    /// are the byte-packed layout; elsewhere a packed copy is exported.
+    fn export_ptr(&self, cells: &FixedBitSet) -> *const u8 {
+        #[cfg(target_endian = "little")]
+        {
+            cells.as_slice().as_ptr() as *const u8
+        }
+        #[cfg(target_endian = "big")]
+        {
+            let mut export = self.export.borrow_mut();
+            *export = packed::pack(cells);
+            export.as_ptr()
+        }
+    }
+
+    fn from_cells(width: u32, height: u32, cells: FixedBitSet) -> Universe {
+        let init_states = cells.clone();
+        let envelope = cells.clone();
+        let population = cells.count_ones(..) as u32;
+        let mut universe = Universe {
+            width,
+            height,
+            cells,
+            init_states,
+            envelope,
+            zobrist: 0,
+            population,
+            occupancy: None,
+            ages: None,
+            boundary: None,
+            series: Series::starting_at(0, population),
+            limits: Limits::default(),
+            generation: 0,
+            last_tick: TickStats::default(),
+            deltas: Vec::new(),
+            recent_states: VecDeque::new(),
+            events: EventLog::default(),
+            order: StorageOrder::RowMajor,
+            hibernated: None,
+            checkpoints: Checkpoints::default(),
+            rewind: Rewind::default(),
+            scratch: FixedBitSet::new(),
+            arena: Arena::default(),
+            capacity: None,
+            engine: Engine::default(),
+            topology: Topology::default(),
+            neighborhood: Neighborhood::default(),
+            transitions: None,
+            edits_update_init_state: false,
+            save_states: HashMap::new(),
+            brush: draw::Brush::default(),
+            noise: None,
+            active_tiles: None,
+            rule: Rule::default(),
+            tick_hook: None,
+            cancel: None,
+            progress: ProgressReporter::default(),
+            pacer: Pacer::default(),
+            #[cfg(feature = "render")]
+            pixels: Default::default(),
+            #[cfg(target_endian = "big")]
+            export: Default::default(),
+        };
+        universe.rehash();
+        universe
+    }
+
+    /// The current cells; empty while hibernating, like the `cells()`
+    /// buffer, so `wake()` first.
     pub fn get_cells(&self) -> &FixedBitSet {
         &self.cells
     }
 
     /// Set cells to be alive in a universe by passing the row and column
     /// of each cell as an array.
-    pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
+    ///
+    /// Fails without changing anything when a cell is outside the
+    /// universe.
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) -> Result<(), Error> {
+        for &(row, col) in cells {
+            self.check_bounds(row as u64, col as u64)?;
+        }
+        self.wake();
+        self.forget_states();
         for (row, col) in cells.iter().cloned() {
             let idx = self.get_index(row, col);
-            self.cells.set(idx, true);
+            if !self.cells.put(idx) {
+                self.flip_hash(idx);
+                self.population += 1;
+                self.edit_age(idx);
+            }
+            self.invalidate_checkpoints(Some(idx));
+            self.follow_edit(idx);
         }
+        Ok(())
     }
 }
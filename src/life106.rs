@@ -0,0 +1,72 @@
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Pattern};
+
+const HEADER: &str = "#Life 1.06";
+
+#[wasm_bindgen]
+impl Pattern {
+    /// Parse the Life 1.06 format: a `#Life 1.06` header, then one
+    /// `x y` coordinate pair (column, row; possibly negative) per live
+    /// cell.
+    pub fn from_life106(text: &str) -> Result<Pattern, Error> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header.trim() == HEADER => {}
+            _ => {
+                return Err(Error::InvalidPattern {
+                    line: 1,
+                    reason: "expected a '#Life 1.06' header",
+                })
+            }
+        }
+
+        let mut coords = Vec::new();
+        for (number, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace().map(str::parse::<i64>);
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(Ok(x)), Some(Ok(y)), None) => coords.push((y, x)),
+                _ => {
+                    return Err(Error::InvalidPattern {
+                        line: number as u32 + 1,
+                        reason: "expected an 'x y' coordinate pair",
+                    })
+                }
+            }
+        }
+
+        let top = coords.iter().map(|&(row, _)| row).min().unwrap_or(0);
+        let left = coords.iter().map(|&(_, col)| col).min().unwrap_or(0);
+        let mut cells = Vec::with_capacity(coords.len());
+        for (row, col) in coords {
+            match (u32::try_from(row - top), u32::try_from(col - left)) {
+                (Ok(row), Ok(col)) => cells.push((row, col)),
+                _ => {
+                    return Err(Error::InvalidPattern {
+                        line: 0,
+                        reason: "pattern is too large",
+                    })
+                }
+            }
+        }
+        Ok(Pattern::from_cells("", cells))
+    }
+
+    /// The pattern in Life 1.06 format, with the top-left corner of the
+    /// bounding box at `0 0`.
+    pub fn to_life106(&self) -> String {
+        let mut text = String::from(HEADER);
+        text.push('\n');
+        for &(row, col) in self.cells() {
+            let _ = writeln!(text, "{} {}", col, row);
+        }
+        text
+    }
+}
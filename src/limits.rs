@@ -0,0 +1,52 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// Upper bounds that keep absurd requests from freezing the tab or
+/// aborting the wasm instance with an out-of-memory error.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// Most generations a single multi-step call may run.
+    pub max_generations_per_call: u32,
+    /// Budget in bytes for state history (e.g. cycle detection tables).
+    pub max_history_bytes: usize,
+    /// Budget in bytes for memoization caches.
+    pub max_cache_bytes: usize,
+    /// Budget in bytes for the live cells of a parsed pattern.
+    pub max_pattern_bytes: usize,
+    /// Most cells of a universe decoded from untrusted input, such as a
+    /// share string, checked before anything is allocated.
+    pub max_decoded_cells: u32,
+}
+
+#[wasm_bindgen]
+impl Limits {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Limits {
+        Limits::default()
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_generations_per_call: 1_000_000,
+            max_history_bytes: 64 << 20,
+            max_cache_bytes: 256 << 20,
+            max_pattern_bytes: 256 << 20,
+            max_decoded_cells: 1 << 26,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(feature = "logging")]
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[cfg(feature = "logging")]
+use wasm_bindgen::prelude::*;
+
+/// How much the crate logs to the console. Each level includes the ones
+/// before it.
+///
+/// Without the `logging` feature nothing is ever logged and the level
+/// cannot be changed.
+#[cfg_attr(feature = "logging", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum LogLevel {
+    Off = 0,
+    /// Failures surfaced to the caller.
+    Error = 1,
+    /// State changes that are rare per frame: resizes, engine switches.
+    Info = 2,
+    /// Per-cell detail from `tick()`. Only usable on small universes.
+    Trace = 3,
+}
+
+#[cfg(feature = "logging")]
+static LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Error as u8);
+
+#[cfg(feature = "logging")]
+#[wasm_bindgen]
+pub fn set_log_level(level: LogLevel) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+#[cfg(feature = "logging")]
+#[wasm_bindgen]
+pub fn log_level() -> LogLevel {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => LogLevel::Off,
+        1 => LogLevel::Error,
+        2 => LogLevel::Info,
+        _ => LogLevel::Trace,
+    }
+}
+
+/// Whether messages at `level` are currently emitted. Check this before
+/// building expensive messages in hot loops.
+#[cfg(feature = "logging")]
+pub(crate) fn enabled(level: LogLevel) -> bool {
+    level != LogLevel::Off && level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+#[cfg(not(feature = "logging"))]
+pub(crate) fn enabled(_level: LogLevel) -> bool {
+    false
+}
+
+/// Emit `message` as `[gol:<level>:<target>] message`, through
+/// `console.error`, `console.info` or `console.debug` on wasm32 and stderr
+/// elsewhere.
+#[cfg(feature = "logging")]
+pub(crate) fn emit(level: LogLevel, target: &str, message: &str) {
+    let label = match level {
+        LogLevel::Off => return,
+        LogLevel::Error => "error",
+        LogLevel::Info => "info",
+        LogLevel::Trace => "trace",
+    };
+    let line = format!("[gol:{}:{}] {}", label, target, message);
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let line = JsValue::from_str(&line);
+        match level {
+            LogLevel::Error => web_sys::console::error_1(&line),
+            LogLevel::Info => web_sys::console::info_1(&line),
+            _ => web_sys::console::debug_1(&line),
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    eprintln!("{}", line);
+}
+
+/// `log_at!(LogLevel::Info, "target", "format {}", args)`: formats and
+/// emits the message only when `level` is enabled.
+#[cfg(feature = "logging")]
+macro_rules! log_at {
+    ( $level:expr, $target:expr, $( $t:tt )* ) => {
+        if crate::logging::enabled($level) {
+            crate::logging::emit($level, $target, &format!( $( $t )* ));
+        }
+    };
+}
+
+/// Without the `logging` feature the arguments are only type checked.
+#[cfg(not(feature = "logging"))]
+macro_rules! log_at {
+    ( $level:expr, $target:expr, $( $t:tt )* ) => {
+        if false {
+            let _ = ($level, $target, format_args!( $( $t )* ));
+        }
+    };
+}
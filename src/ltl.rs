@@ -0,0 +1,351 @@
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{check_dimensions, packed, Entropy, Error};
+
+/// Largest radius accepted, as in Golly.
+const MAX_RADIUS: u32 = 500;
+
+/// A Larger than Life rule: neighbours are counted over the square of
+/// cells within `radius` in both directions (the Moore neighbourhood of
+/// range `radius`), and births and survivals are ranges of counts.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LtlRule {
+    radius: u32,
+    birth: (u32, u32),
+    survival: (u32, u32),
+    /// Whether a cell counts among its own neighbours
+    middle: bool,
+}
+
+#[wasm_bindgen]
+impl LtlRule {
+    /// A rule of radius `radius` in which dead cells with `birth_min` to
+    /// `birth_max` live neighbours are born and live cells with
+    /// `survival_min` to `survival_max` survive, both inclusive.
+    ///
+    /// Fails for a radius of 0 or above 500 and for empty ranges.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        radius: u32,
+        birth_min: u32,
+        birth_max: u32,
+        survival_min: u32,
+        survival_max: u32,
+        middle: bool,
+    ) -> Result<LtlRule, Error> {
+        if radius == 0 || radius > MAX_RADIUS {
+            return Err(Error::InvalidRule("the radius is 1 to 500"));
+        }
+        if birth_min > birth_max || survival_min > survival_max {
+            return Err(Error::InvalidRule("empty range of neighbour counts"));
+        }
+        Ok(LtlRule {
+            radius,
+            birth: (birth_min, birth_max),
+            survival: (survival_min, survival_max),
+            middle,
+        })
+    }
+
+    /// Parse Golly's notation, e.g. `"R5,C0,M1,S34..58,B34..45,NM"` for
+    /// Bugs. R, B and S are required; C defaults to 0 and M to 0.
+    ///
+    /// Only two states (C0 or C2) and the Moore neighbourhood (NM) are
+    /// supported.
+    pub fn parse(rulestring: &str) -> Result<LtlRule, Error> {
+        let text = rulestring.trim().to_ascii_lowercase();
+        let (mut radius, mut birth, mut survival, mut middle) = (None, None, None, false);
+        for field in text.split(',').map(str::trim) {
+            let mut chars = field.chars();
+            let letter = chars.next();
+            let value = chars.as_str();
+            match letter {
+                Some('r') => radius = Some(number(value)?),
+                Some('c') if number(value)? <= 2 => {}
+                Some('c') => return Err(Error::InvalidRule("only two states are supported")),
+                Some('m') => middle = number(value)? == 1,
+                Some('b') => birth = Some(range(value)?),
+                Some('s') => survival = Some(range(value)?),
+                Some('n') if value == "m" => {}
+                Some('n') => {
+                    return Err(Error::InvalidRule(
+                        "only the Moore neighbourhood is supported",
+                    ))
+                }
+                _ => return Err(Error::InvalidRule("expected R, C, M, S, B or N fields")),
+            }
+        }
+        match (radius, birth, survival) {
+            (Some(radius), Some(birth), Some(survival)) => {
+                LtlRule::new(radius, birth.0, birth.1, survival.0, survival.1, middle)
+            }
+            _ => Err(Error::InvalidRule("R, B and S are required")),
+        }
+    }
+
+    /// Bugs, `R5,C0,M1,S34..58,B34..45,NM`: gliders that crawl through
+    /// blobs, from Kellie Evans' thesis.
+    pub fn bugs() -> LtlRule {
+        LtlRule::new(5, 34, 45, 34, 58, true).expect("valid rule")
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    pub fn birth_min(&self) -> u32 {
+        self.birth.0
+    }
+
+    pub fn birth_max(&self) -> u32 {
+        self.birth.1
+    }
+
+    pub fn survival_min(&self) -> u32 {
+        self.survival.0
+    }
+
+    pub fn survival_max(&self) -> u32 {
+        self.survival.1
+    }
+
+    /// Whether a live cell counts itself as a neighbour (M1).
+    pub fn middle(&self) -> bool {
+        self.middle
+    }
+
+    /// The rulestring in Golly's notation.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn rulestring(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl LtlRule {
+    fn next(&self, alive: bool, neighbours: u32) -> bool {
+        let (min, max) = if alive { self.survival } else { self.birth };
+        (min..=max).contains(&neighbours)
+    }
+}
+
+impl fmt::Display for LtlRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R{},C0,M{},S{}..{},B{}..{},NM",
+            self.radius,
+            self.middle as u8,
+            self.survival.0,
+            self.survival.1,
+            self.birth.0,
+            self.birth.1
+        )
+    }
+}
+
+fn number(text: &str) -> Result<u32, Error> {
+    text.parse()
+        .map_err(|_| Error::InvalidRule("expected a number after each letter"))
+}
+
+fn range(text: &str) -> Result<(u32, u32), Error> {
+    match text.split_once("..") {
+        Some((min, max)) => Ok((number(min)?, number(max)?)),
+        None => number(text).map(|count| (count, count)),
+    }
+}
+
+/// A torus under a Larger than Life rule.
+///
+/// Each tick builds a summed-area table of the cells, padded by the
+/// radius on every side, so a neighbour count costs four lookups whatever
+/// the radius.
+#[wasm_bindgen]
+pub struct LargerThanLifeUniverse {
+    width: u32,
+    height: u32,
+    cells: FixedBitSet,
+    rule: LtlRule,
+    generation: u32,
+    /// Summed-area table, reused between ticks
+    sums: Vec<u32>,
+    /// `cells()` buffer
+    packed: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl LargerThanLifeUniverse {
+    /// A dead universe evolving under `rule`. Fails with
+    /// `Error::DimensionsTooLarge` when it would have more cells than it
+    /// can address.
+    pub fn new(height: u32, width: u32, rule: &LtlRule) -> Result<LargerThanLifeUniverse, Error> {
+        check_dimensions(width, height)?;
+        Ok(LargerThanLifeUniverse {
+            width,
+            height,
+            cells: FixedBitSet::with_capacity(width as usize * height as usize),
+            rule: *rule,
+            generation: 0,
+            sums: Vec::new(),
+            packed: Vec::new(),
+        })
+    }
+
+    /// `new()` with a rulestring, see `LtlRule::parse()`.
+    pub fn with_rule(
+        height: u32,
+        width: u32,
+        rulestring: &str,
+    ) -> Result<LargerThanLifeUniverse, Error> {
+        LargerThanLifeUniverse::new(height, width, &LtlRule::parse(rulestring)?)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.cells.count_ones(..) as u32
+    }
+
+    pub fn rule(&self) -> LtlRule {
+        self.rule
+    }
+
+    /// Apply `rule` from the next tick on.
+    pub fn set_rule(&mut self, rule: &LtlRule) {
+        self.rule = *rule;
+    }
+
+    pub fn set_rulestring(&mut self, rulestring: &str) -> Result<(), Error> {
+        self.rule = LtlRule::parse(rulestring)?;
+        Ok(())
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> Result<bool, Error> {
+        Ok(self.cells[self.index(row, column)?])
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, column: u32) -> Result<(), Error> {
+        let idx = self.index(row, column)?;
+        self.cells.toggle(idx);
+        Ok(())
+    }
+
+    pub fn reset_cells(&mut self) {
+        self.cells.clear();
+        self.generation = 0;
+    }
+
+    /// Replace the cells by a soup of live cells with probability
+    /// `density`, see `Universe::randomize()`.
+    pub fn randomize(&mut self, density: f64, entropy: &mut Entropy) {
+        for idx in 0..self.cells.len() {
+            self.cells.set(idx, entropy.next_f64() < density);
+        }
+        self.generation = 0;
+    }
+
+    pub fn tick(&mut self) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        if width == 0 || height == 0 {
+            self.generation += 1;
+            return;
+        }
+        let r = self.rule.radius as usize;
+        // sums[y * stride + x] counts the live cells of padded rows 0..y
+        // and columns 0..x, padded row 0 being row `height - r` (mod
+        // `height`) and padded column 0 column `width - r`
+        let stride = width + 2 * r + 1;
+        let rows = height + 2 * r + 1;
+        self.sums.clear();
+        self.sums.resize(stride * rows, 0);
+        for y in 1..rows {
+            let row = (y - 1 + height * (r / height + 1) - r) % height;
+            let mut line = 0;
+            for x in 1..stride {
+                let column = (x - 1 + width * (r / width + 1) - r) % width;
+                line += self.cells[row * width + column] as u32;
+                self.sums[y * stride + x] = self.sums[(y - 1) * stride + x] + line;
+            }
+        }
+
+        let side = 2 * r + 1;
+        let sums = &self.sums;
+        let mut next = FixedBitSet::with_capacity(self.cells.len());
+        for row in 0..height {
+            for column in 0..width {
+                let (top, left) = (row * stride, column);
+                let (bottom, right) = ((row + side) * stride, column + side);
+                let mut neighbours = sums[bottom + right] + sums[top + left]
+                    - sums[top + right]
+                    - sums[bottom + left];
+                let alive = self.cells[row * width + column];
+                if alive && !self.rule.middle {
+                    neighbours -= 1;
+                }
+                next.set(row * width + column, self.rule.next(alive, neighbours));
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+
+    /// Pointer to the cells, byte-packed in row-major order like
+    /// `Universe::cells()`; read `cells_byte_len()` bytes from it.
+    pub fn cells(&mut self) -> *const u8 {
+        self.packed = packed::pack(&self.cells);
+        self.packed.as_ptr()
+    }
+
+    pub fn cells_byte_len(&self) -> usize {
+        packed::packed_len(self.cells.len())
+    }
+}
+
+impl LargerThanLifeUniverse {
+    /// Bring each listed `(row, column)` cell to life.
+    ///
+    /// Fails without changing anything when a cell is outside the
+    /// universe.
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) -> Result<(), Error> {
+        for &(row, column) in cells {
+            self.index(row, column)?;
+        }
+        for &(row, column) in cells {
+            let idx = self.index(row, column)?;
+            self.cells.insert(idx);
+        }
+        Ok(())
+    }
+
+    fn index(&self, row: u32, column: u32) -> Result<usize, Error> {
+        if row >= self.height || column >= self.width {
+            return Err(Error::OutOfBounds {
+                row: row as u64,
+                column: column as u64,
+                width: self.width,
+                height: self.height,
+            });
+        }
+        Ok(row as usize * self.width as usize + column as usize)
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Pattern, PlacementPolicy, Universe};
+
+/// Many universes behind numeric handles, for side-by-side comparisons,
+/// thumbnails and worker pools.
+///
+/// Patterns added once can be placed into any of the universes without
+/// copying them across the wasm boundary again.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct UniverseManager {
+    universes: BTreeMap<u32, Universe>,
+    next_id: u32,
+    patterns: Vec<Pattern>,
+}
+
+#[wasm_bindgen]
+impl UniverseManager {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> UniverseManager {
+        UniverseManager::default()
+    }
+
+    /// Create an empty universe and return its handle. Fails like
+    /// `Universe::try_new()` when it would be too large.
+    pub fn create(&mut self, height: u32, width: u32) -> Result<u32, Error> {
+        Ok(self.insert(Universe::try_new(height, width)?))
+    }
+
+    /// Take ownership of `universe` and return its handle.
+    pub fn insert(&mut self, universe: Universe) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.universes.insert(id, universe);
+        id
+    }
+
+    /// Hand universe `id` back to the caller, dropping its handle.
+    pub fn take(&mut self, id: u32) -> Result<Universe, Error> {
+        self.universes.remove(&id).ok_or(unknown_universe(id))
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        self.universes.remove(&id).is_some()
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.universes.contains_key(&id)
+    }
+
+    pub fn len(&self) -> u32 {
+        self.universes.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.universes.is_empty()
+    }
+
+    /// Handles of all universes, in creation order.
+    pub fn ids(&self) -> Vec<u32> {
+        self.universes.keys().copied().collect()
+    }
+
+    /// Tick each universe of `ids` once. Fails before ticking anything
+    /// when a handle is unknown.
+    pub fn tick(&mut self, ids: &[u32]) -> Result<(), Error> {
+        if let Some(&id) = ids.iter().find(|id| !self.universes.contains_key(id)) {
+            return Err(unknown_universe(id));
+        }
+        for id in ids {
+            if let Some(universe) = self.universes.get_mut(id) {
+                universe.tick();
+            }
+        }
+        Ok(())
+    }
+
+    pub fn tick_all(&mut self) {
+        for universe in self.universes.values_mut() {
+            universe.tick();
+        }
+    }
+
+    pub fn width(&self, id: u32) -> Result<u32, Error> {
+        self.universe(id).map(Universe::width)
+    }
+
+    pub fn height(&self, id: u32) -> Result<u32, Error> {
+        self.universe(id).map(Universe::height)
+    }
+
+    /// Pointer to the cells of universe `id`, see `Universe::cells()`.
+    pub fn cells(&self, id: u32) -> Result<*const u8, Error> {
+        self.universe(id).map(Universe::cells)
+    }
+
+    /// Keep a copy of `pattern` for `place()` and return its key.
+    pub fn add_pattern(&mut self, pattern: &Pattern) -> u32 {
+        self.patterns.push(pattern.clone());
+        self.patterns.len() as u32 - 1
+    }
+
+    pub fn pattern_count(&self) -> u32 {
+        self.patterns.len() as u32
+    }
+
+    /// `Universe::place_pattern()` with a pattern added through
+    /// `add_pattern()`.
+    pub fn place(
+        &mut self,
+        id: u32,
+        pattern: u32,
+        row: u32,
+        column: u32,
+        policy: PlacementPolicy,
+    ) -> Result<(), Error> {
+        let cached = self
+            .patterns
+            .get(pattern as usize)
+            .ok_or(Error::UnknownHandle {
+                kind: "pattern",
+                id: pattern,
+            })?;
+        let universe = self.universes.get_mut(&id).ok_or(unknown_universe(id))?;
+        universe.place_pattern(cached, row, column, policy)
+    }
+}
+
+impl UniverseManager {
+    pub fn universe(&self, id: u32) -> Result<&Universe, Error> {
+        self.universes.get(&id).ok_or(unknown_universe(id))
+    }
+
+    pub fn universe_mut(&mut self, id: u32) -> Result<&mut Universe, Error> {
+        self.universes.get_mut(&id).ok_or(unknown_universe(id))
+    }
+}
+
+fn unknown_universe(id: u32) -> Error {
+    Error::UnknownHandle {
+        kind: "universe",
+        id,
+    }
+}
@@ -0,0 +1,89 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// How `Universe::merge()` combines each cell with the other universe's.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MergeOp {
+    /// Alive in either, e.g. to layer patterns.
+    Union,
+    /// Alive in both, e.g. to mask a region.
+    Intersect,
+    /// Alive here but not in the other.
+    Difference,
+    /// Alive in exactly one, the cells where two runs differ.
+    Xor,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Combine the cells with those of `other`, cell by cell, a word at a
+    /// time. The result counts as an edit of the current generation, see
+    /// `set_edits_update_init_state()`.
+    ///
+    /// Fails unless both universes have the same dimensions.
+    pub fn merge(&mut self, other: &Universe, op: MergeOp) -> Result<(), Error> {
+        let theirs = self.aligned_cells(other)?;
+        self.wake();
+        let combine = |cells: &mut FixedBitSet| match op {
+            MergeOp::Union => cells.union_with(&theirs),
+            MergeOp::Intersect => cells.intersect_with(&theirs),
+            MergeOp::Difference => cells.difference_with(&theirs),
+            MergeOp::Xor => cells.symmetric_difference_with(&theirs),
+        };
+        combine(&mut self.cells);
+        if self.edits_update_init_state {
+            combine(&mut self.init_states);
+        }
+        self.rehash();
+        self.restart_ages();
+        self.forget_states();
+        self.invalidate_checkpoints(None);
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The indices, as `get_index()` gives them here, of the cells alive in
+    /// one universe but not the other, ascending; from JavaScript a
+    /// `Uint32Array`.
+    ///
+    /// Fails unless both universes have the same dimensions.
+    pub fn diff(&self, other: &Universe) -> Result<Vec<u32>, Error> {
+        let mut differing = self.aligned_cells(other)?;
+        differing.symmetric_difference_with(&self.current_cells());
+        Ok(differing.ones().map(|idx| idx as u32).collect())
+    }
+
+    /// Whether both universes have the same dimensions and the same cells
+    /// alive, comparing whole words rather than listing the differences.
+    pub fn equals(&self, other: &Universe) -> bool {
+        if (other.width, other.height) != (self.width, self.height)
+            || other.population != self.population
+        {
+            return false;
+        }
+        if other.order == self.order {
+            return self.current_cells().as_slice() == other.current_cells().as_slice();
+        }
+        self.aligned_cells(other)
+            .is_ok_and(|theirs| *self.current_cells() == theirs)
+    }
+}
+
+impl Universe {
+    /// The cells of `other` in this universe's storage order, or an error
+    /// when the dimensions differ.
+    fn aligned_cells(&self, other: &Universe) -> Result<FixedBitSet, Error> {
+        if (other.width, other.height) != (self.width, self.height) {
+            return Err(Error::InvalidConfig(
+                "the universes must have the same dimensions",
+            ));
+        }
+        let theirs = other.current_cells().into_owned();
+        Ok(self.in_storage_order(theirs, other.order))
+    }
+}
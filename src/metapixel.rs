@@ -0,0 +1,82 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Pattern, Universe};
+
+/// Distance between neighbouring OTCA metapixels.
+pub const OTCA_PITCH: u32 = 2048;
+
+/// Compile `pattern` into a mega-pattern of metapixels: every live cell
+/// becomes a copy of `on` and every dead cell of its bounding box a copy of
+/// `off`, laid out `pitch` cells apart.
+///
+/// The metapixels themselves are not built in; load the OTCA metapixel's
+/// "on" and "off" states (e.g. with `load_pattern_from_url()`) and pass
+/// `OTCA_PITCH` (2048), whose tiles overlap their neighbours by design.
+/// Any other unit cell with a fixed pitch works the same way. As with
+/// every pattern, the result is shifted so its bounding box starts at
+/// `(0, 0)`.
+///
+/// Fails when the mega-pattern would be larger than a universe can
+/// address.
+#[wasm_bindgen]
+pub fn expand_to_metapixels(
+    pattern: &Pattern,
+    on: &Pattern,
+    off: &Pattern,
+    pitch: u32,
+) -> Result<Pattern, Error> {
+    let extent =
+        |cells: u32, tile: u32| cells.saturating_sub(1) as u64 * pitch as u64 + tile as u64;
+    let width = extent(pattern.width(), on.width().max(off.width()));
+    let height = extent(pattern.height(), on.height().max(off.height()));
+    if width > u32::MAX as u64 || height > u32::MAX as u64 {
+        return Err(Error::DimensionsTooLarge { width, height });
+    }
+
+    let mut live = pattern.cells().iter().peekable();
+    let mut cells = Vec::new();
+    for row in 0..pattern.height() {
+        for col in 0..pattern.width() {
+            let alive = live.next_if(|&&cell| cell == (row, col)).is_some();
+            let tile = if alive { on } else { off };
+            let (top, left) = (row * pitch, col * pitch);
+            cells.extend(tile.cells().iter().map(|&(r, c)| (top + r, left + c)));
+        }
+    }
+    Ok(Pattern::from_cells(
+        &format!("{} (metapixels)", pattern.name()),
+        cells,
+    ))
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// View a metapixel universe at the scale of its metapixels: one byte
+    /// per `pitch` x `pitch` block, row by row, 1 where the block has more
+    /// than `min_population` live cells. Blocks start at `(0, 0)`; partial
+    /// blocks at the right and bottom edges are left out, so the view is
+    /// `width / pitch` blocks wide.
+    ///
+    /// An OTCA metapixel that is on carries a lit display, so a
+    /// `min_population` between the populations of the "off" and "on"
+    /// states tells them apart.
+    pub fn downsample_metapixels(&mut self, pitch: u32, min_population: u32) -> Vec<u8> {
+        self.wake();
+        if pitch == 0 {
+            return Vec::new();
+        }
+        let (columns, rows) = (self.width / pitch, self.height / pitch);
+        let mut counts = vec![0u32; (columns * rows) as usize];
+        for row in 0..rows * pitch {
+            for col in 0..columns * pitch {
+                if self.cells[self.get_index(row, col)] {
+                    counts[((row / pitch) * columns + col / pitch) as usize] += 1;
+                }
+            }
+        }
+        counts
+            .into_iter()
+            .map(|count| (count > min_population) as u8)
+            .collect()
+    }
+}
@@ -0,0 +1,35 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use crate::{Error, Pattern};
+
+impl Pattern {
+    /// Load a pattern file. RLE files (`.rle`) are parsed while they are
+    /// read, so even multi-hundred-megabyte constructions only take the
+    /// memory of their cells; other formats are small enough to be read
+    /// whole and detected like `Pattern::parse()`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Pattern, Error> {
+        let path = path.as_ref();
+        let io_error = |error: std::io::Error| Error::Io(format!("{}: {}", path.display(), error));
+        let file = File::open(path).map_err(io_error)?;
+        let is_rle = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("rle"));
+        let mut pattern = if is_rle {
+            Pattern::read_rle(BufReader::new(file))?
+        } else {
+            let mut text = String::new();
+            BufReader::new(file)
+                .read_to_string(&mut text)
+                .map_err(io_error)?;
+            Pattern::parse(&text)?
+        };
+        if pattern.name().is_empty() {
+            if let Some(stem) = path.file_stem() {
+                pattern.set_name(&stem.to_string_lossy());
+            }
+        }
+        Ok(pattern)
+    }
+}
@@ -0,0 +1,84 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{LogLevel, Universe};
+
+/// Which cells count as a cell's neighbours.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The eight cells around it.
+    #[default]
+    Moore,
+    /// The four cells sharing an edge with it.
+    VonNeumann,
+    /// Six cells of a hexagonal grid in "odd-r" offset coordinates: odd
+    /// rows are drawn shifted half a cell east, so a cell's neighbours are
+    /// its west and east ones plus two in the rows above and below, to the
+    /// west in even rows and to the east in odd ones.
+    ///
+    /// On a torus, wrapping keeps the offsets consistent only when the
+    /// height is even.
+    Hexagonal,
+}
+
+const MOORE: [(i64, i64); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+const VON_NEUMANN: [(i64, i64); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const HEX_EVEN_ROW: [(i64, i64); 6] = [(-1, -1), (-1, 0), (0, -1), (0, 1), (1, -1), (1, 0)];
+const HEX_ODD_ROW: [(i64, i64); 6] = [(-1, 0), (-1, 1), (0, -1), (0, 1), (1, 0), (1, 1)];
+
+impl Neighborhood {
+    pub fn name(self) -> &'static str {
+        match self {
+            Neighborhood::Moore => "moore",
+            Neighborhood::VonNeumann => "von-neumann",
+            Neighborhood::Hexagonal => "hexagonal",
+        }
+    }
+
+    /// `(row, column)` offsets of the neighbours of a cell in `row`.
+    pub(crate) fn offsets(self, row: u32) -> &'static [(i64, i64)] {
+        match self {
+            Neighborhood::Moore => &MOORE,
+            Neighborhood::VonNeumann => &VON_NEUMANN,
+            Neighborhood::Hexagonal if row % 2 == 1 => &HEX_ODD_ROW,
+            Neighborhood::Hexagonal => &HEX_EVEN_ROW,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The neighbourhood rules count over, so renderers can draw square
+    /// or hexagonal cells to match.
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// Count neighbours over `neighborhood` from the next tick on.
+    ///
+    /// The word-parallel engines only know the Moore neighbourhood, so
+    /// universes with another one tick through `Engine::Naive` whatever
+    /// `engine()` says.
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) {
+        log_at!(
+            LogLevel::Info,
+            "neighborhood",
+            "{} -> {}",
+            self.neighborhood.name(),
+            neighborhood.name()
+        );
+        self.neighborhood = neighborhood;
+        self.active_tiles = None;
+        self.reset_checkpoints();
+        self.forget_states();
+    }
+}
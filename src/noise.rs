@@ -0,0 +1,89 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::rng::{EntropySource, Xoshiro256};
+use crate::{Error, LogLevel, Universe};
+
+/// Probabilities with which the births and survivals the rule asks for
+/// actually happen.
+#[derive(Clone, Debug)]
+pub(crate) struct Noise {
+    birth: f64,
+    survival: f64,
+    rng: Xoshiro256,
+}
+
+impl Noise {
+    /// Kill each cell of `next` born or surviving from `cells` whose draw
+    /// fails.
+    pub(crate) fn apply(&mut self, cells: &FixedBitSet, next: &mut FixedBitSet) {
+        let failed: Vec<usize> = next
+            .ones()
+            .filter(|&idx| {
+                let p = if cells[idx] {
+                    self.survival
+                } else {
+                    self.birth
+                };
+                p < 1.0 && self.rng.next_f64() >= p
+            })
+            .collect();
+        for idx in failed {
+            next.set(idx, false);
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Make the rule stochastic: from the next tick on, a dead cell the
+    /// rule would bring to life is born with probability `birth_p`, and a
+    /// live cell it would keep survives with probability `survival_p`.
+    /// Deaths always happen. Draws come from a generator seeded with
+    /// `seed`, so runs are reproducible.
+    ///
+    /// Since generations are no longer a function of the previous one,
+    /// `goto_generation()` replays from checkpoints with fresh draws, and
+    /// `changes_since()` fails.
+    ///
+    /// Fails unless both probabilities are in `[0, 1]`.
+    pub fn set_noise(&mut self, birth_p: f64, survival_p: f64, seed: u64) -> Result<(), Error> {
+        let valid = |p: f64| (0.0..=1.0).contains(&p);
+        if !valid(birth_p) || !valid(survival_p) {
+            return Err(Error::InvalidConfig("probabilities must be in [0, 1]"));
+        }
+        log_at!(
+            LogLevel::Info,
+            "noise",
+            "birth {}, survival {}, seed {}",
+            birth_p,
+            survival_p,
+            seed
+        );
+        self.noise = Some(Noise {
+            birth: birth_p,
+            survival: survival_p,
+            rng: Xoshiro256::new(seed),
+        });
+        self.reset_checkpoints();
+        self.forget_states();
+        Ok(())
+    }
+
+    /// Back to the deterministic rule.
+    pub fn clear_noise(&mut self) {
+        self.noise = None;
+        self.reset_checkpoints();
+        self.forget_states();
+    }
+
+    /// Probability of the births the rule asks for, 1 without noise.
+    pub fn birth_probability(&self) -> f64 {
+        self.noise.as_ref().map_or(1.0, |noise| noise.birth)
+    }
+
+    /// Probability of the survivals the rule asks for, 1 without noise.
+    pub fn survival_probability(&self) -> f64 {
+        self.noise.as_ref().map_or(1.0, |noise| noise.survival)
+    }
+}
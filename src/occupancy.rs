@@ -0,0 +1,120 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::hibernate::Counters;
+use crate::Universe;
+
+/// How often each cell has been alive, see `Universe::occupancy()`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Occupancy {
+    counts: Vec<u32>,
+    /// Generations recorded into `counts`.
+    samples: u32,
+}
+
+impl Occupancy {
+    fn starting_at(cells: &FixedBitSet) -> Occupancy {
+        let mut occupancy = Occupancy {
+            counts: vec![0; cells.len()],
+            samples: 0,
+        };
+        occupancy.record(cells);
+        occupancy
+    }
+
+    pub(crate) fn record(&mut self, cells: &FixedBitSet) {
+        for idx in cells.ones() {
+            self.counts[idx] += 1;
+        }
+        self.samples += 1;
+    }
+
+    /// Reorder the counts like `layout::transpose_into()` does cells.
+    pub(crate) fn transpose(&mut self, stride: u32, lines: u32) {
+        let mut transposed = vec![0; self.counts.len()];
+        for (idx, &count) in self.counts.iter().enumerate() {
+            let (line, offset) = (idx as u32 / stride, idx as u32 % stride);
+            transposed[(offset * lines + line) as usize] = count;
+        }
+        self.counts = transposed;
+    }
+
+    pub(crate) fn compress(self) -> CompressedOccupancy {
+        CompressedOccupancy {
+            counts: Counters::new(self.counts.into_iter()),
+            samples: self.samples,
+        }
+    }
+
+    pub(crate) fn byte_len(&self) -> usize {
+        self.counts.capacity() * std::mem::size_of::<u32>()
+    }
+}
+
+/// `Occupancy` squeezed while its universe hibernates.
+#[derive(Clone, Debug)]
+pub(crate) struct CompressedOccupancy {
+    counts: Counters,
+    samples: u32,
+}
+
+impl CompressedOccupancy {
+    pub(crate) fn restore(&self) -> Occupancy {
+        Occupancy {
+            counts: self.counts.restore(),
+            samples: self.samples,
+        }
+    }
+
+    pub(crate) fn byte_len(&self) -> usize {
+        self.counts.byte_len()
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Start or stop counting how often each cell is alive. Counting
+    /// starts with the current generation and costs a pass over the live
+    /// cells every tick.
+    pub fn set_track_occupancy(&mut self, enabled: bool) {
+        self.wake();
+        self.occupancy = enabled.then(|| Occupancy::starting_at(&self.cells));
+    }
+
+    pub fn is_tracking_occupancy(&self) -> bool {
+        self.current_occupancy().is_some()
+    }
+
+    /// For each cell, in storage order, the fraction of the recorded
+    /// generations it was alive in: a long exposure of the universe.
+    /// Empty when occupancy is not tracked.
+    pub fn occupancy(&self) -> Vec<f32> {
+        match self.current_occupancy() {
+            Some(occupancy) => {
+                let samples = occupancy.samples as f32;
+                occupancy
+                    .counts
+                    .iter()
+                    .map(|&count| count as f32 / samples)
+                    .collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Number of generations the occupancy was recorded over.
+    pub fn occupancy_generations(&self) -> u32 {
+        self.current_occupancy()
+            .map_or(0, |occupancy| occupancy.samples)
+    }
+}
+
+impl Universe {
+    /// Restart tracked occupancy from the current cells, e.g. after a
+    /// reset.
+    pub(crate) fn restart_occupancy(&mut self) {
+        if self.occupancy.is_some() {
+            self.occupancy = Some(Occupancy::starting_at(&self.cells));
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use wasm_bindgen::prelude::*;
+
+use crate::utils::now_ms;
+use crate::{Error, Universe};
+
+/// Wall-clock pacing state, see `Universe::set_target_gps()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Pacer {
+    target_gps: Option<f64>,
+    /// Time the clock started, in milliseconds
+    start_ms: Option<f64>,
+    /// Generations ticked by `advance()` since the clock started
+    ticked: u64,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Advance at `generations_per_second` of real time through
+    /// `advance()`, however often it is called. Fails unless the rate is
+    /// positive and finite.
+    pub fn set_target_gps(&mut self, generations_per_second: f64) -> Result<(), Error> {
+        if !(generations_per_second.is_finite() && generations_per_second > 0.0) {
+            return Err(Error::InvalidConfig(
+                "generations per second must be positive",
+            ));
+        }
+        self.pacer = Pacer {
+            target_gps: Some(generations_per_second),
+            ..Pacer::default()
+        };
+        Ok(())
+    }
+
+    /// Stop pacing; `advance()` then does nothing.
+    pub fn clear_target_gps(&mut self) {
+        self.pacer = Pacer::default();
+    }
+
+    pub fn target_gps(&self) -> Option<f64> {
+        self.pacer.target_gps
+    }
+
+    /// Tick as many generations as the target rate owes for the time
+    /// since the previous call and return how many that was. Call it every
+    /// frame with the frame's timestamp in milliseconds (e.g. the one
+    /// `requestAnimationFrame` passes); the first call only starts the
+    /// clock. Fractions of a generation carry over, so the average rate is
+    /// exact whatever the frame rate.
+    ///
+    /// At most `Limits::max_generations_per_call` generations run per
+    /// call, so a throttled background tab does not stall when it comes
+    /// back; the rest of the backlog is dropped.
+    pub fn advance(&mut self, now_ms: f64) -> Result<u32, Error> {
+        let gps = match self.pacer.target_gps {
+            Some(gps) => gps,
+            None => return Ok(0),
+        };
+        let start = match self.pacer.start_ms {
+            Some(start) if start <= now_ms => start,
+            // first call, or the clock went backwards
+            _ => {
+                self.pacer.start_ms = Some(now_ms);
+                self.pacer.ticked = 0;
+                return Ok(0);
+            }
+        };
+
+        // counted from the start rather than summed per frame, so rounding
+        // does not drift
+        let due = ((now_ms - start) * gps / 1000.0).floor() as u64;
+        let owed = due.saturating_sub(self.pacer.ticked);
+        let limit = self.limits.max_generations_per_call;
+        let generations = if owed > limit as u64 {
+            self.pacer.start_ms = Some(now_ms);
+            self.pacer.ticked = 0;
+            limit
+        } else {
+            self.pacer.ticked = due;
+            owed as u32
+        };
+        self.tick_many(generations)?;
+        Ok(generations)
+    }
+
+    /// `advance()` with the current time.
+    pub fn advance_now(&mut self) -> Result<u32, Error> {
+        self.advance(now_ms())
+    }
+}
@@ -0,0 +1,38 @@
+//! Conversion between `FixedBitSet` storage and a byte-packed layout.
+//!
+//! Byte `i` holds cells `8 * i .. 8 * i + 8`, with the lowest cell index in
+//! the least significant bit, independent of the target's word size.
+
+use fixedbitset::FixedBitSet;
+
+/// Number of bytes needed to pack `len` cells.
+pub(crate) fn packed_len(len: usize) -> usize {
+    len.div_ceil(8)
+}
+
+pub(crate) fn pack(cells: &FixedBitSet) -> Vec<u8> {
+    let mut bytes: Vec<u8> = cells
+        .as_slice()
+        .iter()
+        .flat_map(|block| block.to_le_bytes())
+        .collect();
+    bytes.truncate(packed_len(cells.len()));
+    bytes
+}
+
+/// Unpack `len` cells; fails when `bytes` has the wrong length.
+pub(crate) fn unpack(bytes: &[u8], len: usize) -> Option<FixedBitSet> {
+    if bytes.len() != packed_len(len) {
+        return None;
+    }
+    let mut cells = FixedBitSet::with_capacity(len);
+    for (i, byte) in bytes.iter().enumerate() {
+        for bit in 0..8 {
+            let idx = i * 8 + bit;
+            if byte & (1 << bit) != 0 && idx < len {
+                cells.insert(idx);
+            }
+        }
+    }
+    Some(cells)
+}
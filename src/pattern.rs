@@ -0,0 +1,399 @@
+use std::convert::TryFrom;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// Provenance of a pattern, read from and written to the comment lines of
+/// the pattern formats so it survives import and export.
+///
+/// Empty strings mean unknown; an empty rule means B3/S23.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatternMeta {
+    pub(crate) name: String,
+    pub(crate) author: String,
+    pub(crate) discovered: String,
+    pub(crate) rule: String,
+    pub(crate) comments: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl PatternMeta {
+    pub fn new() -> PatternMeta {
+        PatternMeta::default()
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_owned();
+    }
+
+    pub fn author(&self) -> String {
+        self.author.clone()
+    }
+
+    pub fn set_author(&mut self, author: &str) {
+        self.author = author.to_owned();
+    }
+
+    /// When the pattern was discovered, as written in the source
+    /// (usually a year).
+    pub fn discovered(&self) -> String {
+        self.discovered.clone()
+    }
+
+    pub fn set_discovered(&mut self, discovered: &str) {
+        self.discovered = discovered.to_owned();
+    }
+
+    pub fn rule(&self) -> String {
+        self.rule.clone()
+    }
+
+    pub fn set_rule(&mut self, rule: &str) {
+        self.rule = rule.to_owned();
+    }
+
+    /// Free-form comment lines, in order.
+    pub fn comments(&self) -> Vec<String> {
+        self.comments.clone()
+    }
+
+    pub fn add_comment(&mut self, comment: &str) {
+        self.comments.push(comment.to_owned());
+    }
+
+    pub fn clear_comments(&mut self) {
+        self.comments.clear();
+    }
+}
+
+/// A finite arrangement of live cells, independent of any universe.
+///
+/// Cells are `(row, col)` offsets from the top-left corner of the
+/// pattern's bounding box, sorted and without duplicates.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    pub(crate) meta: PatternMeta,
+    width: u32,
+    height: u32,
+    cells: Vec<(u32, u32)>,
+}
+
+#[wasm_bindgen]
+impl Pattern {
+    /// Parse the plaintext (`.cells`) format: `O` for live cells, `.` for
+    /// dead ones, one row per line. Lines starting with `!` are comments;
+    /// `!Name:`, `!Author:`, `!Discovered:` and `!Rule:` comments fill in
+    /// the metadata.
+    pub fn from_plaintext(text: &str) -> Result<Pattern, Error> {
+        let mut meta = PatternMeta::default();
+        let mut cells = Vec::new();
+        let mut row = 0;
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim_end();
+            if let Some(comment) = line.strip_prefix('!') {
+                let field = |key: &str| comment.strip_prefix(key).map(|v| v.trim().to_owned());
+                if let Some(name) = field("Name:") {
+                    meta.name = name;
+                } else if let Some(author) = field("Author:") {
+                    meta.author = author;
+                } else if let Some(discovered) = field("Discovered:") {
+                    meta.discovered = discovered;
+                } else if let Some(rule) = field("Rule:") {
+                    meta.rule = rule;
+                } else {
+                    meta.add_comment(comment.strip_prefix(' ').unwrap_or(comment));
+                }
+                continue;
+            }
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    'O' | '*' => cells.push((row, col as u32)),
+                    '.' => {}
+                    _ => {
+                        return Err(Error::InvalidPattern {
+                            line: number as u32 + 1,
+                            reason: "expected 'O' or '.'",
+                        })
+                    }
+                }
+            }
+            row += 1;
+        }
+        Ok(Pattern::from_cells("", cells).with_meta(meta))
+    }
+
+    /// Parse `text` in whichever supported format it is in: Life 1.06
+    /// and RLE are recognized by their header (with the `formats`
+    /// feature), anything else is read as plaintext.
+    pub fn parse(text: &str) -> Result<Pattern, Error> {
+        #[cfg(feature = "formats")]
+        {
+            let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+            if lines.clone().next() == Some("#Life 1.06") {
+                return Pattern::from_life106(text);
+            }
+            let looks_like_rle = lines
+                .find(|line| !line.starts_with('#'))
+                .is_some_and(|line| line.starts_with('x') && line.contains('='));
+            if looks_like_rle {
+                return Pattern::from_rle(text);
+            }
+        }
+        Pattern::from_plaintext(text)
+    }
+
+    pub fn name(&self) -> String {
+        self.meta.name()
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.meta.set_name(name);
+    }
+
+    pub fn meta(&self) -> PatternMeta {
+        self.meta.clone()
+    }
+
+    pub fn set_meta(&mut self, meta: PatternMeta) {
+        self.meta = meta;
+    }
+
+    /// Width of the bounding box.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height of the bounding box.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn population(&self) -> u32 {
+        self.cells.len() as u32
+    }
+
+    /// Live cells as flat `[row, col, row, col, ...]` pairs.
+    pub fn cell_coords(&self) -> Vec<u32> {
+        self.cells
+            .iter()
+            .flat_map(|&(row, col)| [row, col])
+            .collect()
+    }
+
+    /// The pattern in plaintext format, with its metadata as comments.
+    pub fn to_plaintext(&self) -> String {
+        let meta = &self.meta;
+        let mut text = String::new();
+        for (key, value) in [
+            ("Name", &meta.name),
+            ("Author", &meta.author),
+            ("Discovered", &meta.discovered),
+            ("Rule", &meta.rule),
+        ] {
+            if !value.is_empty() {
+                text.push_str(&format!("!{}: {}\n", key, value));
+            }
+        }
+        for comment in &meta.comments {
+            text.push('!');
+            if !comment.is_empty() {
+                text.push(' ');
+                text.push_str(comment);
+            }
+            text.push('\n');
+        }
+        let mut cells = self.cells.iter().peekable();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let alive = cells.next_if(|&&cell| cell == (row, col)).is_some();
+                text.push(if alive { 'O' } else { '.' });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl Pattern {
+    /// A pattern from arbitrary `(row, col)` cells, normalized so the
+    /// bounding box starts at `(0, 0)`.
+    pub fn from_cells(name: &str, mut cells: Vec<(u32, u32)>) -> Pattern {
+        let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+        let left = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+        for cell in cells.iter_mut() {
+            *cell = (cell.0 - top, cell.1 - left);
+        }
+        cells.sort_unstable();
+        cells.dedup();
+        let height = cells.iter().map(|&(row, _)| row + 1).max().unwrap_or(0);
+        let width = cells.iter().map(|&(_, col)| col + 1).max().unwrap_or(0);
+        let mut meta = PatternMeta::default();
+        meta.set_name(name);
+        Pattern {
+            meta,
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn cells(&self) -> &[(u32, u32)] {
+        &self.cells
+    }
+
+    pub(crate) fn with_meta(mut self, meta: PatternMeta) -> Pattern {
+        self.meta = meta;
+        self
+    }
+}
+
+/// What `Universe::place_pattern()` does with cells that end up past the
+/// edges of the universe.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlacementPolicy {
+    /// Continue on the opposite edge, like the torus itself.
+    Wrap,
+    /// Drop the cells that do not fit.
+    Clip,
+    /// Fail unless the whole pattern fits.
+    #[default]
+    Error,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Bring `pattern` to life with its top-left corner at `(row, column)`,
+    /// treating cells past the edges according to `policy`. Cells already
+    /// alive stay alive.
+    ///
+    /// With `PlacementPolicy::Error` nothing changes when the pattern does
+    /// not fit.
+    pub fn place_pattern(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32,
+        policy: PlacementPolicy,
+    ) -> Result<(), Error> {
+        let cells = self.placed_cells(pattern, row, column, policy)?;
+        self.set_cells(&cells)
+    }
+
+    /// Resize the universe to the pattern's bounding box plus `margin`
+    /// dead cells on every side and place `pattern` in it, so nothing
+    /// wraps into the pattern before it has grown past the margin. The
+    /// pattern also becomes the initial state.
+    pub fn fit_to_pattern(&mut self, pattern: &Pattern, margin: u32) -> Result<(), Error> {
+        let (width, height) = padded_size(pattern, margin);
+        self.resize_for(pattern, width, height, margin)
+    }
+
+    /// Like `fit_to_pattern()`, for a spaceship moving `dx` columns and
+    /// `dy` rows per period: the dimensions are rounded up to the same
+    /// number of periods' worth of displacement, so the ship crosses the
+    /// torus cleanly and the whole universe recurs exactly.
+    ///
+    /// Returns that number of periods.
+    pub fn fit_to_spaceship(
+        &mut self,
+        pattern: &Pattern,
+        margin: u32,
+        dx: i32,
+        dy: i32,
+    ) -> Result<u64, Error> {
+        let (width, height) = padded_size(pattern, margin);
+        let (dx, dy) = (dx.unsigned_abs() as u64, dy.unsigned_abs() as u64);
+        let periods_for = |size: u64, step: u64| match step {
+            0 => 0,
+            step => size.div_ceil(step),
+        };
+        let periods = periods_for(width, dx).max(periods_for(height, dy));
+        let stretch = |size: u64, step: u64| if step == 0 { size } else { periods * step };
+        self.resize_for(pattern, stretch(width, dx), stretch(height, dy), margin)?;
+        Ok(periods)
+    }
+}
+
+/// The pattern's bounding box grown by `margin` on every side.
+fn padded_size(pattern: &Pattern, margin: u32) -> (u64, u64) {
+    let margin = 2 * margin as u64;
+    (
+        pattern.width as u64 + margin,
+        pattern.height as u64 + margin,
+    )
+}
+
+impl Universe {
+    /// The universe cells `pattern` covers when placed at `(row, column)`.
+    pub(crate) fn placed_cells(
+        &self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32,
+        policy: PlacementPolicy,
+    ) -> Result<Vec<(u32, u32)>, Error> {
+        if pattern.cells.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (height, width) = (self.height as u64, self.width as u64);
+        let offset = |&(r, c): &(u32, u32)| (row as u64 + r as u64, column as u64 + c as u64);
+        let cells = match policy {
+            PlacementPolicy::Error => {
+                // the bottom-right corner of the bounding box is the farthest cell
+                self.check_bounds(
+                    row as u64 + pattern.height as u64 - 1,
+                    column as u64 + pattern.width as u64 - 1,
+                )?;
+                pattern
+                    .cells
+                    .iter()
+                    .map(|&(r, c)| (row + r, column + c))
+                    .collect()
+            }
+            PlacementPolicy::Clip => pattern
+                .cells
+                .iter()
+                .map(offset)
+                .filter(|&(r, c)| r < height && c < width)
+                .map(|(r, c)| (r as u32, c as u32))
+                .collect(),
+            PlacementPolicy::Wrap if width == 0 || height == 0 => Vec::new(),
+            PlacementPolicy::Wrap => pattern
+                .cells
+                .iter()
+                .map(offset)
+                .map(|(r, c)| ((r % height) as u32, (c % width) as u32))
+                .collect(),
+        };
+        Ok(cells)
+    }
+
+    fn resize_for(
+        &mut self,
+        pattern: &Pattern,
+        width: u64,
+        height: u64,
+        margin: u32,
+    ) -> Result<(), Error> {
+        let too_large = Error::DimensionsTooLarge { width, height };
+        let (width, height) = match (u32::try_from(width), u32::try_from(height)) {
+            (Ok(w), Ok(h)) if w.checked_mul(h).is_some() => (w, h),
+            _ => return Err(too_large),
+        };
+        self.check_capacity(width, height)?;
+        self.width = width;
+        self.height = height;
+        self.resize_storage();
+        self.place_pattern(pattern, margin, margin, PlacementPolicy::Error)?;
+        self.init_states.clone_from(&self.cells);
+        Ok(())
+    }
+}
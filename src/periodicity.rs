@@ -0,0 +1,118 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// How much better a multiple of a period has to correlate to be
+/// suggested alongside it.
+const HARMONIC_MARGIN: f64 = 0.05;
+
+/// A period suggested by `population_periods()`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PeriodCandidate {
+    pub period: u32,
+    /// Autocorrelation of the population at this lag, in `(0, 1]`; 1
+    /// means the population history repeats exactly.
+    pub confidence: f64,
+}
+
+/// Suggest periods of a population history by autocorrelating it, for
+/// boards too big to compare whole states.
+///
+/// Candidates are the lags up to `max_period` (and half the history)
+/// where the correlation peaks, most confident first. Multiples of a
+/// candidate are left out unless they correlate clearly better. An
+/// oscillator whose population never changes only shows up as period 1.
+#[wasm_bindgen]
+pub fn population_periods(populations: &[u32], max_period: u32) -> Vec<PeriodCandidate> {
+    let n = populations.len();
+    let mean = populations.iter().map(|&p| p as f64).sum::<f64>() / n.max(1) as f64;
+    let deviations: Vec<f64> = populations.iter().map(|&p| p as f64 - mean).collect();
+    if n > 1 && deviations.iter().all(|&d| d == 0.0) {
+        return vec![PeriodCandidate {
+            period: 1,
+            confidence: 1.0,
+        }];
+    }
+
+    let max_lag = (max_period as usize).min(n / 2);
+    // one lag beyond max_lag, so the last candidate has a right neighbour
+    let correlation: Vec<f64> = (0..=max_lag + 1)
+        .map(|lag| correlate(&deviations, lag))
+        .collect();
+    let mut candidates: Vec<PeriodCandidate> = (1..=max_lag)
+        .filter(|&lag| {
+            let r = correlation[lag];
+            r > 0.0 && (lag == 1 || r >= correlation[lag - 1]) && r >= correlation[lag + 1]
+        })
+        .map(|lag| {
+            let exact = populations[..n - lag] == populations[lag..];
+            PeriodCandidate {
+                period: lag as u32,
+                confidence: if exact {
+                    1.0
+                } else {
+                    correlation[lag].min(1.0)
+                },
+            }
+        })
+        .collect();
+    let peaks = candidates.clone();
+    candidates.retain(|candidate| {
+        !peaks.iter().any(|divisor| {
+            divisor.period < candidate.period
+                && candidate.period % divisor.period == 0
+                && divisor.confidence + HARMONIC_MARGIN >= candidate.confidence
+        })
+    });
+    candidates.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(a.period.cmp(&b.period))
+    });
+    candidates
+}
+
+/// Pearson correlation of the series with itself shifted by `lag`, over
+/// the overlapping part; 0 when either side is constant.
+fn correlate(deviations: &[f64], lag: usize) -> f64 {
+    if lag >= deviations.len() {
+        return 0.0;
+    }
+    let (head, tail) = (&deviations[..deviations.len() - lag], &deviations[lag..]);
+    let (mut sum, mut head_sq, mut tail_sq) = (0.0, 0.0, 0.0);
+    for (&a, &b) in head.iter().zip(tail) {
+        sum += a * b;
+        head_sq += a * a;
+        tail_sq += b * b;
+    }
+    if head_sq == 0.0 || tail_sq == 0.0 {
+        return 0.0;
+    }
+    sum / (head_sq * tail_sq).sqrt()
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Tick `generations` times and suggest periods from the population
+    /// of every generation seen, see `population_periods()`.
+    ///
+    /// Fails when `generations` exceeds `Limits::max_generations_per_call`.
+    pub fn estimate_periods(
+        &mut self,
+        generations: u32,
+        max_period: u32,
+    ) -> Result<Vec<PeriodCandidate>, Error> {
+        self.check_generations(generations)?;
+        self.wake();
+        let mut populations = Vec::with_capacity(generations as usize + 1);
+        populations.push(self.cells.count_ones(..) as u32);
+        for done in 0..generations {
+            self.progress_step(done, generations)?;
+            self.tick();
+            populations.push(self.last_tick.population);
+        }
+        self.report_progress(generations, generations);
+        Ok(population_periods(&populations, max_period))
+    }
+}
@@ -0,0 +1,119 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// Colours of `Universe::render_rgba()`, each `0xRRGGBBAA`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub alive: u32,
+    pub dead: u32,
+    /// What live cells fade to as they age, see `gradient_span`.
+    pub old: u32,
+    /// Generations a cell takes to go from `alive` to `old` when ages are
+    /// tracked (`set_track_ages()`); 0 colours every live cell `alive`.
+    pub gradient_span: u16,
+}
+
+#[wasm_bindgen]
+impl Palette {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Palette {
+        Palette::default()
+    }
+}
+
+impl Default for Palette {
+    /// The black on white of `www/index.js`, without a gradient.
+    fn default() -> Self {
+        Palette {
+            alive: 0x0000_00ff,
+            dead: 0xffff_ffff,
+            old: 0x0000_00ff,
+            gradient_span: 0,
+        }
+    }
+}
+
+/// The buffer behind `render_rgba()` and the colours it is drawn with.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Pixels {
+    rgba: Vec<u8>,
+    palette: Palette,
+}
+
+impl Pixels {
+    /// Free the buffer, `render_rgba()` allocates it again.
+    pub(crate) fn release(&mut self) {
+        self.rgba = Vec::new();
+    }
+
+    pub(crate) fn byte_len(&self) -> usize {
+        self.rgba.capacity()
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    pub fn palette(&self) -> Palette {
+        self.pixels.palette
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.pixels.palette = palette;
+    }
+
+    /// Draw the cells into a buffer of 4 bytes (red, green, blue, alpha)
+    /// per cell, row by row, and return a pointer to it: wrapped in a
+    /// `Uint8ClampedArray` of `rgba_len()` bytes it makes an `ImageData`
+    /// that `putImageData()` shows in one call.
+    ///
+    /// The pointer stays valid until the next call that allocates in wasm
+    /// memory.
+    pub fn render_rgba(&mut self) -> *const u8 {
+        let palette = self.pixels.palette;
+        let alive = palette.alive.to_be_bytes();
+        let dead = palette.dead.to_be_bytes();
+
+        let mut rgba = std::mem::take(&mut self.pixels.rgba);
+        rgba.resize(self.width as usize * self.height as usize * 4, 0);
+        let ages = self.current_ages().filter(|_| palette.gradient_span > 0);
+        let ages = ages.as_ref().map(|ages| ages.values());
+        let cells = self.current_cells();
+        let mut pixels = rgba.chunks_exact_mut(4);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                let color = match ages {
+                    _ if !cells[idx] => dead,
+                    Some(ages) => fade(&palette, ages[idx]),
+                    None => alive,
+                };
+                if let Some(pixel) = pixels.next() {
+                    pixel.copy_from_slice(&color);
+                }
+            }
+        }
+        self.pixels.rgba = rgba;
+        self.pixels.rgba.as_ptr()
+    }
+
+    /// Bytes behind `render_rgba()`: `width * height * 4`.
+    pub fn rgba_len(&self) -> usize {
+        self.width as usize * self.height as usize * 4
+    }
+}
+
+/// The colour of a live cell `age` generations old, blending each channel
+/// from `alive` to `old`.
+fn fade(palette: &Palette, age: u16) -> [u8; 4] {
+    let span = u32::from(palette.gradient_span);
+    let t = u32::from(age.saturating_sub(1)).min(span);
+    let (young, old) = (palette.alive.to_be_bytes(), palette.old.to_be_bytes());
+    let mut color = [0; 4];
+    for (channel, (&from, &to)) in color.iter_mut().zip(young.iter().zip(old.iter())) {
+        let (from, to) = (u32::from(from), u32::from(to));
+        *channel = ((from * (span - t) + to * t) / span) as u8;
+    }
+    color
+}
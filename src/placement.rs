@@ -0,0 +1,177 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Pattern, PlacementPolicy, Universe};
+
+/// Clockwise rotation applied to a pattern.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    None,
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+/// One of the eight orientations of a pattern: the rotations, with or
+/// without a reflection.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Transform {
+    #[default]
+    Identity,
+    /// Clockwise quarter turn.
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrored left to right.
+    FlipHorizontal,
+    /// Mirrored top to bottom.
+    FlipVertical,
+    /// Mirrored across the main diagonal: rows become columns.
+    Transpose,
+    /// Mirrored across the other diagonal.
+    AntiTranspose,
+}
+
+impl Transform {
+    /// All eight orientations.
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// The rotation and left-right flip `Pattern::transformed()` takes.
+    fn parts(self) -> (Rotation, bool) {
+        match self {
+            Transform::Identity => (Rotation::None, false),
+            Transform::Rotate90 => (Rotation::Quarter, false),
+            Transform::Rotate180 => (Rotation::Half, false),
+            Transform::Rotate270 => (Rotation::ThreeQuarters, false),
+            Transform::FlipHorizontal => (Rotation::None, true),
+            Transform::FlipVertical => (Rotation::Half, true),
+            Transform::Transpose => (Rotation::ThreeQuarters, true),
+            Transform::AntiTranspose => (Rotation::Quarter, true),
+        }
+    }
+}
+
+/// One pattern of a scene built with `Universe::place_many()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pattern: Pattern,
+    /// Row of the top-left corner of the transformed pattern.
+    pub row: u32,
+    /// Column of the top-left corner of the transformed pattern.
+    pub column: u32,
+    pub rotation: Rotation,
+    /// Mirror the pattern left to right, before rotating it.
+    pub flip: bool,
+    pub policy: PlacementPolicy,
+}
+
+#[wasm_bindgen]
+impl Placement {
+    /// `pattern` at `(row, column)`, untransformed and required to fit.
+    #[wasm_bindgen(constructor)]
+    pub fn new(pattern: &Pattern, row: u32, column: u32) -> Placement {
+        Placement {
+            pattern: pattern.clone(),
+            row,
+            column,
+            rotation: Rotation::None,
+            flip: false,
+            policy: PlacementPolicy::Error,
+        }
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        self.pattern.clone()
+    }
+}
+
+#[wasm_bindgen]
+impl Pattern {
+    /// A copy mirrored left to right when `flip` is set, then rotated,
+    /// with the bounding box back at `(0, 0)`.
+    pub fn transformed(&self, rotation: Rotation, flip: bool) -> Pattern {
+        let (width, height) = (self.width(), self.height());
+        let cells = self
+            .cells()
+            .iter()
+            .map(|&(row, col)| {
+                let col = if flip { width - 1 - col } else { col };
+                match rotation {
+                    Rotation::None => (row, col),
+                    Rotation::Quarter => (col, height - 1 - row),
+                    Rotation::Half => (height - 1 - row, width - 1 - col),
+                    Rotation::ThreeQuarters => (width - 1 - col, row),
+                }
+            })
+            .collect();
+        Pattern::from_cells("", cells).with_meta(self.meta.clone())
+    }
+
+    /// A copy in orientation `transform`, with the bounding box back at
+    /// `(0, 0)`.
+    pub fn oriented(&self, transform: Transform) -> Pattern {
+        let (rotation, flip) = transform.parts();
+        self.transformed(rotation, flip)
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Stamp `pattern` in orientation `transform` with the top-left corner
+    /// of its bounding box at `(row, column)`, wrapping past the edges
+    /// like the torus. Cells already alive stay alive.
+    ///
+    /// Fails when `(row, column)` is outside the universe.
+    pub fn insert_pattern(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32,
+        transform: Transform,
+    ) -> Result<(), Error> {
+        self.check_bounds(row as u64, column as u64)?;
+        self.place_pattern(
+            &pattern.oriented(transform),
+            row,
+            column,
+            PlacementPolicy::Wrap,
+        )
+    }
+
+    /// `place_many()` for JavaScript, which cannot pass slices of structs.
+    pub fn place_all(&mut self, placements: Vec<Placement>) -> Result<(), Error> {
+        self.place_many(&placements)
+    }
+}
+
+impl Universe {
+    /// Place every pattern of a scene in one edit: either all placements
+    /// succeed, or the first failure is returned and nothing changes.
+    pub fn place_many(&mut self, placements: &[Placement]) -> Result<(), Error> {
+        let mut cells = Vec::new();
+        for placement in placements {
+            let pattern = placement
+                .pattern
+                .transformed(placement.rotation, placement.flip);
+            cells.extend(self.placed_cells(
+                &pattern,
+                placement.row,
+                placement.column,
+                placement.policy,
+            )?);
+        }
+        self.set_cells(&cells)
+    }
+}
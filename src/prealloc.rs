@@ -0,0 +1,57 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{arena, Error, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Create a universe whose buffers (cells, initial state, envelope,
+    /// next generation and event records) are allocated once for `max_cells`
+    /// cells and never reallocated, for predictable memory use in
+    /// embedded or kiosk deployments.
+    ///
+    /// Resizing works in place up to `max_cells`; `hibernate()` keeps the
+    /// buffers. Checkpoints still allocate their tiles, so leave the
+    /// checkpoint interval at 0 for a fully static footprint.
+    pub fn new_preallocated(height: u32, width: u32, max_cells: usize) -> Result<Universe, Error> {
+        let mut universe = Universe::from_cells(width, height, FixedBitSet::new());
+        universe.capacity = Some(max_cells);
+        universe.check_capacity(width, height)?;
+
+        let size = (width * height) as usize;
+        for bits in [
+            &mut universe.cells,
+            &mut universe.init_states,
+            &mut universe.envelope,
+            &mut universe.scratch,
+        ] {
+            bits.grow(max_cells);
+            arena::resize_in_place(bits, size);
+        }
+        universe.events.reserve();
+        Ok(universe)
+    }
+
+    /// Maximum number of cells of a preallocated universe, 0 otherwise.
+    pub fn capacity(&self) -> usize {
+        self.capacity.unwrap_or(0)
+    }
+
+    pub fn is_preallocated(&self) -> bool {
+        self.capacity.is_some()
+    }
+}
+
+impl Universe {
+    pub(crate) fn check_capacity(&self, width: u32, height: u32) -> Result<(), Error> {
+        crate::check_dimensions(width, height)?;
+        let requested = width as usize * height as usize;
+        match self.capacity {
+            Some(capacity) if requested > capacity => Err(Error::CapacityExceeded {
+                requested,
+                capacity,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// Callback receiving `(done, total)` generations, see
+/// `Universe::set_progress_hook()`.
+pub type ProgressHook = Box<dyn FnMut(u32, u32)>;
+
+/// How far the current (or last) long-running call got.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Generations done so far.
+    pub done: u32,
+    /// Generations the call will run at most.
+    pub total: u32,
+}
+
+#[wasm_bindgen]
+impl Progress {
+    /// `done / total`, 1 when there is nothing to do.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.done as f64 / self.total as f64
+        }
+    }
+}
+
+/// Where progress of long-running calls is reported.
+#[derive(Default)]
+pub(crate) struct ProgressReporter {
+    progress: Progress,
+    hook: Option<ProgressHook>,
+    /// Report every `interval` generations (and at the end).
+    interval: u32,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Progress of the current or last `tick_many()`, `goto_generation()`,
+    /// `run_until()`, `estimate_periods()` or `changes_since()` call.
+    pub fn progress(&self) -> Progress {
+        self.progress.progress
+    }
+
+    /// Call `callback(done, total)` every `interval` generations (and at
+    /// the end) of long-running calls, e.g. to drive a progress bar.
+    ///
+    /// The page only repaints once the call returns, so run it in a worker
+    /// or in slices of `tick_many()` to show movement.
+    pub fn set_progress_callback(&mut self, callback: js_sys::Function, interval: u32) {
+        self.set_progress_hook(
+            Box::new(move |done, total| {
+                // a failing callback must not abort the simulation
+                let _ = callback.call2(&JsValue::NULL, &done.into(), &total.into());
+            }),
+            interval,
+        );
+    }
+
+    pub fn clear_progress_callback(&mut self) {
+        self.progress.hook = None;
+    }
+}
+
+impl Universe {
+    /// `set_progress_callback()` for native embedders.
+    pub fn set_progress_hook(&mut self, hook: ProgressHook, interval: u32) {
+        self.progress.hook = Some(hook);
+        self.progress.interval = interval.max(1);
+    }
+
+    /// Called before each generation of a long-running call: fails once
+    /// cancelled, otherwise records (and maybe reports) the progress.
+    pub(crate) fn progress_step(&mut self, done: u32, total: u32) -> Result<(), Error> {
+        self.check_cancelled(done)?;
+        self.report_progress(done, total);
+        Ok(())
+    }
+
+    /// Record that `done` of `total` generations are done.
+    pub(crate) fn report_progress(&mut self, done: u32, total: u32) {
+        let reporter = &mut self.progress;
+        reporter.progress = Progress { done, total };
+        if let Some(hook) = &mut reporter.hook {
+            if done.is_multiple_of(reporter.interval) || done == total {
+                hook(done, total);
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, OffscreenCanvasRenderingContext2d};
+
+use crate::Universe;
+
+/// The calls `draw()` needs, shared by on- and offscreen contexts.
+trait Canvas2d {
+    fn set_fill(&self, color: &str);
+    fn begin_path(&self);
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64);
+    fn fill(&self);
+}
+
+impl Canvas2d for CanvasRenderingContext2d {
+    fn set_fill(&self, color: &str) {
+        self.set_fill_style_str(color);
+    }
+
+    fn begin_path(&self) {
+        CanvasRenderingContext2d::begin_path(self);
+    }
+
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        CanvasRenderingContext2d::rect(self, x, y, width, height);
+    }
+
+    fn fill(&self) {
+        CanvasRenderingContext2d::fill(self);
+    }
+}
+
+impl Canvas2d for OffscreenCanvasRenderingContext2d {
+    fn set_fill(&self, color: &str) {
+        self.set_fill_style_str(color);
+    }
+
+    fn begin_path(&self) {
+        OffscreenCanvasRenderingContext2d::begin_path(self);
+    }
+
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        OffscreenCanvasRenderingContext2d::rect(self, x, y, width, height);
+    }
+
+    fn fill(&self) {
+        OffscreenCanvasRenderingContext2d::fill(self);
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Draw every cell onto `ctx` as a `cell_size` pixel square, laid out
+    /// like `www/index.js` does: one pixel of grid line between the cells
+    /// and around them, which is left for the page to stroke.
+    ///
+    /// The cells of each colour are collected into one path and filled
+    /// once, so a frame costs two fills however large the universe is.
+    pub fn draw(
+        &self,
+        ctx: &CanvasRenderingContext2d,
+        cell_size: u32,
+        alive_color: &str,
+        dead_color: &str,
+    ) {
+        self.draw_cells(ctx, cell_size, alive_color, dead_color);
+    }
+
+    /// `draw()` onto an `OffscreenCanvas`, e.g. from a Worker.
+    pub fn draw_offscreen(
+        &self,
+        ctx: &OffscreenCanvasRenderingContext2d,
+        cell_size: u32,
+        alive_color: &str,
+        dead_color: &str,
+    ) {
+        self.draw_cells(ctx, cell_size, alive_color, dead_color);
+    }
+}
+
+impl Universe {
+    fn draw_cells(&self, ctx: &dyn Canvas2d, cell_size: u32, alive_color: &str, dead_color: &str) {
+        let cells = self.current_cells();
+        let pitch = f64::from(cell_size + 1);
+        let size = f64::from(cell_size);
+        for &(alive, color) in [(false, dead_color), (true, alive_color)].iter() {
+            ctx.set_fill(color);
+            ctx.begin_path();
+            for row in 0..self.height {
+                for col in 0..self.width {
+                    if cells[self.get_index(row, col)] == alive {
+                        ctx.rect(
+                            f64::from(col) * pitch + 1.0,
+                            f64::from(row) * pitch + 1.0,
+                            size,
+                            size,
+                        );
+                    }
+                }
+            }
+            ctx.fill();
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{LogLevel, Universe};
+
+/// Moving every cell at once, e.g. to recentre a drifting spaceship or set
+/// up a symmetric start. The current and the initial state move together;
+/// the generation count is kept, while checkpoints, the `step_back()`
+/// history and the statistics tied to cell positions start over.
+#[wasm_bindgen]
+impl Universe {
+    /// Move every cell `dr` rows down and `dc` columns right (up and left
+    /// for negative values). With `wrap` cells leaving one edge come back
+    /// on the opposite one, otherwise they are dropped.
+    pub fn shift(&mut self, dr: i32, dc: i32, wrap: bool) {
+        let (height, width) = (self.height as i64, self.width as i64);
+        self.remap(self.width, self.height, |row, column| {
+            let (row, column) = (row as i64 + dr as i64, column as i64 + dc as i64);
+            if wrap {
+                return Some((
+                    row.rem_euclid(height) as u32,
+                    column.rem_euclid(width) as u32,
+                ));
+            }
+            let inside = (0..height).contains(&row) && (0..width).contains(&column);
+            inside.then_some((row as u32, column as u32))
+        });
+    }
+
+    /// Turn the universe a quarter clockwise; width and height swap.
+    pub fn rotate_cw(&mut self) {
+        let height = self.height;
+        self.remap(self.height, self.width, |row, column| {
+            Some((column, height - 1 - row))
+        });
+    }
+
+    /// Mirror the universe left to right.
+    pub fn flip_horizontal(&mut self) {
+        let width = self.width;
+        self.remap(self.width, self.height, |row, column| {
+            Some((row, width - 1 - column))
+        });
+    }
+
+    /// Mirror the universe top to bottom.
+    pub fn flip_vertical(&mut self) {
+        let height = self.height;
+        self.remap(self.width, self.height, |row, column| {
+            Some((height - 1 - row, column))
+        });
+    }
+}
+
+impl Universe {
+    /// Move the cell at each `(row, column)` to `to(row, column)`, or drop
+    /// it, in a universe of `width` x `height` with as many cells as now.
+    fn remap(&mut self, width: u32, height: u32, to: impl Fn(u32, u32) -> Option<(u32, u32)>) {
+        self.wake();
+        let cells = self.live_coordinates(&self.cells);
+        let init_states = self.live_coordinates(&self.init_states);
+        log_at!(
+            LogLevel::Info,
+            "reorient",
+            "{}x{} -> {}x{}",
+            self.width,
+            self.height,
+            width,
+            height
+        );
+        self.width = width;
+        self.height = height;
+        self.cells.clear();
+        self.init_states.clear();
+        for (row, column) in cells.into_iter().filter_map(|(r, c)| to(r, c)) {
+            let idx = self.get_index(row, column);
+            self.cells.insert(idx);
+        }
+        for (row, column) in init_states.into_iter().filter_map(|(r, c)| to(r, c)) {
+            let idx = self.get_index(row, column);
+            self.init_states.insert(idx);
+        }
+        self.rehash();
+        self.reset_envelope();
+        self.restart_occupancy();
+        self.restart_ages();
+        self.restart_boundary_traffic();
+        self.reset_checkpoints();
+        self.rewind.clear();
+        self.forget_states();
+        self.deltas.clear();
+    }
+}
@@ -0,0 +1,80 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// Where the existing cells end up when `Universe::resize()` changes the
+/// dimensions.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    /// Keep the top-left corner in place; rows and columns are added or
+    /// cropped at the bottom and right.
+    TopLeft,
+    /// Keep the middle in place; the difference is split between opposite
+    /// edges (the extra one at the bottom or right when it is odd).
+    #[default]
+    Center,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Change the dimensions keeping the live cells, unlike `set_width()`
+    /// and `set_height()`: the grid grows or is cropped around `anchor`.
+    /// The initial state is carried over the same way.
+    ///
+    /// The generation count and everything recorded about earlier
+    /// generations restart, as after any resize. Fails when the universe
+    /// would be too large or a preallocated one would outgrow its
+    /// capacity.
+    pub fn resize(&mut self, width: u32, height: u32, anchor: Anchor) -> Result<(), Error> {
+        self.check_capacity(width, height)?;
+        self.wake();
+        let (row_offset, column_offset) = match anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Center => (
+                (height as i64 - self.height as i64) / 2,
+                (width as i64 - self.width as i64) / 2,
+            ),
+        };
+        let cells = self.live_coordinates(&self.cells);
+        let init_states = self.live_coordinates(&self.init_states);
+
+        self.width = width;
+        self.height = height;
+        self.resize_storage();
+        let moved = |coordinates: Vec<(u32, u32)>| {
+            coordinates.into_iter().filter_map(move |(row, column)| {
+                let row = row as i64 + row_offset;
+                let column = column as i64 + column_offset;
+                let inside =
+                    (0..height as i64).contains(&row) && (0..width as i64).contains(&column);
+                inside.then_some((row as u32, column as u32))
+            })
+        };
+        for (row, column) in moved(cells) {
+            let idx = self.get_index(row, column);
+            self.cells.insert(idx);
+        }
+        for (row, column) in moved(init_states) {
+            let idx = self.get_index(row, column);
+            self.init_states.insert(idx);
+        }
+        self.restart_history();
+        Ok(())
+    }
+}
+
+impl Universe {
+    pub(crate) fn live_coordinates(&self, cells: &FixedBitSet) -> Vec<(u32, u32)> {
+        let mut coordinates = Vec::new();
+        for row in 0..self.height {
+            for column in 0..self.width {
+                if cells[self.get_index(row, column)] {
+                    coordinates.push((row, column));
+                }
+            }
+        }
+        coordinates
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+
+use fixedbitset::{Block, FixedBitSet};
+use wasm_bindgen::prelude::*;
+
+use crate::hibernate::Compressed;
+use crate::{TickStats, Universe};
+
+/// Ring buffer of the compressed states of the generations before the
+/// current one, newest last, for `step_back()`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Rewind {
+    states: VecDeque<Compressed>,
+    /// Generations kept; 0 disables recording
+    capacity: usize,
+    /// Bytes held by `states`
+    bytes: usize,
+}
+
+impl Rewind {
+    fn push(&mut self, cells: &FixedBitSet) {
+        if self.capacity == 0 {
+            return;
+        }
+        let state = Compressed::new(cells);
+        self.bytes += state.byte_len();
+        self.states.push_back(state);
+        self.trim();
+    }
+
+    fn pop(&mut self) -> Option<Compressed> {
+        let state = self.states.pop_back()?;
+        self.bytes -= state.byte_len();
+        Some(state)
+    }
+
+    fn trim(&mut self) {
+        while self.states.len() > self.capacity {
+            if let Some(state) = self.states.pop_front() {
+                self.bytes -= state.byte_len();
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.states.clear();
+        self.bytes = 0;
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Keep the states of the last `generations` generations so the UI can
+    /// scrub back through them with `step_back()`; 0 (the default) keeps
+    /// none. Each state is stored compressed, like a hibernating universe.
+    pub fn set_history_capacity(&mut self, generations: usize) {
+        self.rewind.capacity = generations;
+        self.rewind.trim();
+    }
+
+    pub fn history_capacity(&self) -> usize {
+        self.rewind.capacity
+    }
+
+    /// Generations `step_back()` can currently undo.
+    pub fn history_len(&self) -> usize {
+        self.rewind.states.len()
+    }
+
+    /// Bytes held by the kept states.
+    pub fn history_bytes(&self) -> usize {
+        self.rewind.bytes
+    }
+
+    /// Go back to the previous generation. Returns false, changing nothing,
+    /// when no earlier state is kept.
+    ///
+    /// `deltas()` then lists the cells that changed back.
+    pub fn step_back(&mut self) -> bool {
+        self.wake();
+        let state = match self.rewind.pop() {
+            Some(state) => state,
+            None => return false,
+        };
+        let restored = state.restore(self.cells.len());
+        let previous = std::mem::replace(&mut self.cells, restored);
+        self.deltas.clear();
+        for (i, (&old, &new)) in previous
+            .as_slice()
+            .iter()
+            .zip(self.cells.as_slice())
+            .enumerate()
+        {
+            let mut changed = old ^ new;
+            while changed != 0 {
+                let bit = changed.trailing_zeros() as usize;
+                changed &= changed - 1;
+                self.deltas.push((i * Block::BITS as usize + bit) as u32);
+            }
+        }
+        self.generation -= 1;
+        self.last_tick = TickStats {
+            generation: self.generation,
+            ..TickStats::default()
+        };
+        self.rehash();
+        self.checkpoints.mark_all();
+        self.forget_states();
+        self.restart_ages();
+        true
+    }
+
+    /// Go forward one generation by ticking, so edits made after stepping
+    /// back carry forward into the replayed generations.
+    pub fn step_forward(&mut self) {
+        self.tick();
+    }
+}
+
+impl Universe {
+    /// Called by `tick()` with the generation it just replaced.
+    pub(crate) fn record_rewind(&mut self, previous: &FixedBitSet) {
+        self.rewind.push(previous);
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt::Write;
+use std::io::BufRead;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Limits, Pattern, PatternMeta, Rule, Universe};
+
+/// Longest line `to_rle()` writes, as recommended for the format.
+const LINE_WIDTH: usize = 70;
+
+#[wasm_bindgen]
+impl Pattern {
+    /// Parse the run length encoded format: an `x = .., y = ..` header
+    /// line, then runs of `b` (dead) and `o` (alive) cells, `$` ending a
+    /// row and `!` ending the pattern.
+    ///
+    /// The metadata comes from `#N` (name), `#O` (author and date) and
+    /// `#C` (comment) lines and the header's `rule`; other `#` lines are
+    /// skipped.
+    ///
+    /// Live cells outside the header's size, or more of them than
+    /// `Limits::max_pattern_bytes` holds, are an error.
+    pub fn from_rle(text: &str) -> Result<Pattern, Error> {
+        let mut parser = RleParser::default();
+        for line in text.lines() {
+            if parser.line(line)? {
+                break;
+            }
+        }
+        parser.finish()
+    }
+
+    /// The pattern in run length encoded format, with its metadata.
+    pub fn to_rle(&self) -> String {
+        let meta = &self.meta;
+        let mut text = String::new();
+        if !meta.name.is_empty() {
+            let _ = writeln!(text, "#N {}", meta.name);
+        }
+        let credit = meta.credit();
+        if !credit.is_empty() {
+            let _ = writeln!(text, "#O {}", credit);
+        }
+        for comment in &meta.comments {
+            let _ = writeln!(text, "#C {}", comment);
+        }
+        let rule = if meta.rule.is_empty() {
+            "B3/S23"
+        } else {
+            &meta.rule
+        };
+        let _ = writeln!(
+            text,
+            "x = {}, y = {}, rule = {}",
+            self.width(),
+            self.height(),
+            rule
+        );
+
+        let mut body = RleWriter::default();
+        let (mut row, mut col) = (0, 0);
+        for &(r, c) in self.cells() {
+            if r > row {
+                body.push(r - row, '$');
+                row = r;
+                col = 0;
+            }
+            body.push(c - col, 'b');
+            body.push(1, 'o');
+            col = c + 1;
+        }
+        body.push(1, '!');
+        text.push_str(&body.finish());
+        text
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A universe just big enough for an RLE pattern, e.g. one pasted from
+    /// LifeWiki, evolving under the rule of its header (B3/S23 when there
+    /// is none). The pattern is also the initial state.
+    ///
+    /// Fails when the text is not valid RLE or its rule is not a Life-like
+    /// B/S rule.
+    pub fn from_rle(rle: &str) -> Result<Universe, Error> {
+        let pattern = Pattern::from_rle(rle)?;
+        let rule = match pattern.meta.rule.as_str() {
+            "" => Rule::default(),
+            rulestring => Rule::parse(rulestring)?,
+        };
+        let mut universe = Universe::new(0, 0);
+        universe.fit_to_pattern(&pattern, 0)?;
+        universe.rule = rule;
+        Ok(universe)
+    }
+
+    /// The live cells as RLE, trimmed to their bounding box and with the
+    /// universe's rule in the header.
+    pub fn to_rle(&self) -> String {
+        let cells = self.row_major_cells();
+        let width = self.width as usize;
+        let mut pattern = Pattern::from_cells(
+            "",
+            cells
+                .ones()
+                .map(|idx| ((idx / width) as u32, (idx % width) as u32))
+                .collect(),
+        );
+        if self.rule != Rule::conway() {
+            pattern.meta.set_rule(&self.rule.to_string());
+        }
+        pattern.to_rle()
+    }
+}
+
+impl Pattern {
+    /// `from_rle()` reading `reader` one line at a time, so a huge file
+    /// never has to be held in memory next to its cells.
+    pub fn read_rle<R: BufRead>(mut reader: R) -> Result<Pattern, Error> {
+        let mut parser = RleParser::default();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_line(&mut line)
+                .map_err(|error| Error::Io(error.to_string()))?;
+            if read == 0 || parser.line(&line)? {
+                break;
+            }
+        }
+        parser.finish()
+    }
+}
+
+/// Line by line state of `Pattern::from_rle()`, so large files can be
+/// parsed as they are read.
+pub(crate) struct RleParser {
+    meta: PatternMeta,
+    cells: Vec<(u32, u32)>,
+    header: bool,
+    row: u32,
+    col: u32,
+    run: Option<u32>,
+    /// Lines seen so far.
+    lines: u32,
+    /// `(width, height)` from the header.
+    size: (u32, u32),
+    /// Most cells `cells` may hold, from `Limits::max_pattern_bytes`.
+    max_cells: usize,
+}
+
+impl Default for RleParser {
+    fn default() -> Self {
+        RleParser {
+            meta: PatternMeta::default(),
+            cells: Vec::new(),
+            header: false,
+            row: 0,
+            col: 0,
+            run: None,
+            lines: 0,
+            size: (0, 0),
+            max_cells: Limits::default().max_pattern_bytes / std::mem::size_of::<(u32, u32)>(),
+        }
+    }
+}
+
+impl RleParser {
+    /// Feed the next line; returns whether the pattern has ended.
+    pub(crate) fn line(&mut self, line: &str) -> Result<bool, Error> {
+        self.lines += 1;
+        let line = line.trim();
+        let number = self.lines;
+        let error = |reason| Error::InvalidPattern {
+            line: number,
+            reason,
+        };
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut chars = comment.chars();
+            let tag = chars.next();
+            let value = chars.as_str().trim();
+            match tag {
+                Some('N') => self.meta.name = value.to_owned(),
+                Some('O') => self.meta.set_credit(value),
+                Some('C') | Some('c') => self.meta.add_comment(value),
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if !self.header {
+            if line.is_empty() {
+                return Ok(false);
+            }
+            if !line.starts_with('x') {
+                return Err(error("expected an 'x = .., y = ..' header"));
+            }
+            self.header = true;
+            let field = |name: &str| {
+                line.split(',')
+                    .filter_map(|field| field.split_once('='))
+                    .find(|(key, _)| key.trim() == name)
+                    .map(|(_, value)| value.trim())
+            };
+            let dimension = |name| {
+                field(name)
+                    .and_then(|value| value.parse().ok())
+                    .ok_or_else(|| error("expected an 'x = .., y = ..' header"))
+            };
+            self.size = (dimension("x")?, dimension("y")?);
+            match field("rule") {
+                // the default, kept implicit like in `PatternMeta`
+                Some(rule) if rule.eq_ignore_ascii_case("B3/S23") => {}
+                Some(rule) => self.meta.rule = rule.to_owned(),
+                None => {}
+            }
+            return Ok(false);
+        }
+
+        for c in line.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                let count = self.run.unwrap_or(0);
+                self.run = Some(
+                    count
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(digit))
+                        .ok_or_else(|| error("run count too large"))?,
+                );
+                continue;
+            }
+            if c.is_whitespace() {
+                continue;
+            }
+            let count = self.run.take().unwrap_or(1);
+            let (row, col) = (self.row, self.col);
+            let too_long = || error("run too long");
+            match c {
+                'b' | '.' => self.col = col.checked_add(count).ok_or_else(too_long)?,
+                '$' => {
+                    self.row = row.checked_add(count).ok_or_else(too_long)?;
+                    self.col = 0;
+                }
+                '!' => return Ok(true),
+                c if c.is_ascii_alphabetic() => {
+                    // `o`, and the states of multi-state rules, are alive
+                    let end = col.checked_add(count).ok_or_else(too_long)?;
+                    let (width, height) = self.size;
+                    if end > width || row >= height {
+                        return Err(error("cells outside the 'x = .., y = ..' size"));
+                    }
+                    if self.cells.len() + count as usize > self.max_cells {
+                        return Err(Error::MemoryLimit {
+                            what: "pattern",
+                            limit: self.max_cells * std::mem::size_of::<(u32, u32)>(),
+                        });
+                    }
+                    self.cells.extend((col..end).map(|c| (row, c)));
+                    self.col = end;
+                }
+                _ => return Err(error("unexpected character in RLE data")),
+            }
+        }
+        Ok(false)
+    }
+
+    pub(crate) fn finish(self) -> Result<Pattern, Error> {
+        if !self.header {
+            return Err(Error::InvalidPattern {
+                line: self.lines,
+                reason: "missing 'x = .., y = ..' header",
+            });
+        }
+        Ok(Pattern::from_cells("", self.cells).with_meta(self.meta))
+    }
+}
+
+/// Collects runs of the same tag and wraps the output at `LINE_WIDTH`.
+#[derive(Default)]
+struct RleWriter {
+    out: String,
+    line: usize,
+    pending: Option<(u32, char)>,
+}
+
+impl RleWriter {
+    fn push(&mut self, count: u32, tag: char) {
+        if count == 0 {
+            return;
+        }
+        match &mut self.pending {
+            Some((n, t)) if *t == tag => *n += count,
+            _ => {
+                self.flush();
+                self.pending = Some((count, tag));
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some((count, tag)) = self.pending.take() {
+            let item = if count == 1 {
+                tag.to_string()
+            } else {
+                format!("{}{}", count, tag)
+            };
+            if self.line + item.len() > LINE_WIDTH {
+                self.out.push('\n');
+                self.line = 0;
+            }
+            self.line += item.len();
+            self.out.push_str(&item);
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.flush();
+        self.out.push('\n');
+        self.out
+    }
+}
+
+impl PatternMeta {
+    /// Split an `author, date` credit as found in RLE `#O` lines; the
+    /// part after the last comma counts as the date when it starts with
+    /// a digit.
+    pub(crate) fn set_credit(&mut self, credit: &str) {
+        match credit.rsplit_once(',') {
+            Some((author, date)) if date.trim_start().starts_with(|c: char| c.is_ascii_digit()) => {
+                self.author = author.trim().to_owned();
+                self.discovered = date.trim().to_owned();
+            }
+            _ => self.author = credit.trim().to_owned(),
+        }
+    }
+
+    /// The inverse of `set_credit()`.
+    pub(crate) fn credit(&self) -> String {
+        match (self.author.is_empty(), self.discovered.is_empty()) {
+            (_, true) => self.author.clone(),
+            (true, false) => format!(", {}", self.discovered),
+            (false, false) => format!("{}, {}", self.author, self.discovered),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// Where randomized universes get their randomness from.
+///
+/// Hosts pick the trade-off through `Entropy`: a seeded generator for
+/// reproducible soups, `Math.random` for convenience, or their own
+/// callback (e.g. backed by `crypto.getRandomValues`) for quality.
+pub trait EntropySource {
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64;
+}
+
+/// xoshiro256** seeded through splitmix64: small, fast and reproducible
+/// on every target, unlike `Math.random`.
+#[derive(Clone, Debug)]
+pub struct Xoshiro256 {
+    state: [u64; 4],
+}
+
+impl Xoshiro256 {
+    pub fn new(seed: u64) -> Xoshiro256 {
+        let mut splitmix = seed;
+        let mut next = || {
+            splitmix = splitmix.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = splitmix;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        Xoshiro256 {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+}
+
+impl EntropySource for Xoshiro256 {
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// `Math.random()`. Only available with a JavaScript host.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MathRandom;
+
+impl EntropySource for MathRandom {
+    fn next_f64(&mut self) -> f64 {
+        js_sys::Math::random()
+    }
+}
+
+/// A JavaScript function returning a number in `[0, 1)` on every call.
+#[derive(Clone, Debug)]
+pub struct JsCallback(pub js_sys::Function);
+
+impl EntropySource for JsCallback {
+    fn next_f64(&mut self) -> f64 {
+        self.0
+            .call0(&JsValue::NULL)
+            .unwrap_throw()
+            .as_f64()
+            .expect_throw("entropy callback must return a number")
+    }
+}
+
+/// An `EntropySource` handed over from JavaScript to `randomize()`.
+#[wasm_bindgen]
+pub struct Entropy {
+    source: Box<dyn EntropySource>,
+}
+
+#[wasm_bindgen]
+impl Entropy {
+    /// Reproducible randomness: the same seed gives the same soup on
+    /// every target.
+    pub fn seeded(seed: u64) -> Entropy {
+        Entropy::from_source(Xoshiro256::new(seed))
+    }
+
+    pub fn math_random() -> Entropy {
+        Entropy::from_source(MathRandom)
+    }
+
+    /// Randomness from `callback`, called once per cell; it must return a
+    /// number in `[0, 1)`.
+    pub fn from_callback(callback: js_sys::Function) -> Entropy {
+        Entropy::from_source(JsCallback(callback))
+    }
+
+    pub fn next_f64(&mut self) -> f64 {
+        self.source.next_f64()
+    }
+}
+
+impl Entropy {
+    pub fn from_source(source: impl EntropySource + 'static) -> Entropy {
+        Entropy {
+            source: Box::new(source),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A `height` x `width` random soup where each cell is alive with
+    /// probability `density`; the same seed always gives the same soup,
+    /// unlike `new_randomized()`. Fails like `try_new()`.
+    pub fn new_seeded(height: u32, width: u32, density: f64, seed: u64) -> Result<Universe, Error> {
+        let mut universe = Universe::try_new(height, width)?;
+        universe.randomize_seeded(density, seed);
+        Ok(universe)
+    }
+
+    /// `randomize()` with `Entropy::seeded(seed)`.
+    pub fn randomize_seeded(&mut self, density: f64, seed: u64) {
+        self.randomize(density, &mut Entropy::seeded(seed));
+    }
+
+    /// Replace the cells, and the initial state, by a random soup where
+    /// each cell is alive with probability `density`.
+    pub fn randomize(&mut self, density: f64, entropy: &mut Entropy) {
+        self.wake();
+        fill_random(&mut self.cells, density, entropy.source.as_mut());
+        self.init_states.clone_from(&self.cells);
+        self.restart_history();
+    }
+
+    /// Randomize only the `rows` x `columns` rectangle whose top left
+    /// corner is `(row, column)`, each cell alive with probability
+    /// `density`, e.g. for a spray tool. The cells outside the rectangle and
+    /// the initial state are left alone.
+    ///
+    /// Fails when the rectangle does not fit inside the universe.
+    pub fn randomize_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        rows: u32,
+        columns: u32,
+        density: f64,
+        entropy: &mut Entropy,
+    ) -> Result<(), Error> {
+        self.check_region(row, column, rows, columns)?;
+        self.fill_region(row, column, rows, columns, |_, _| {
+            entropy.source.next_f64() < density
+        });
+        Ok(())
+    }
+}
+
+/// `len` cells, each alive with probability `density`.
+pub(crate) fn random_cells(
+    len: usize,
+    density: f64,
+    source: &mut dyn EntropySource,
+) -> FixedBitSet {
+    let mut cells = FixedBitSet::with_capacity(len);
+    fill_random(&mut cells, density, source);
+    cells
+}
+
+fn fill_random(cells: &mut FixedBitSet, density: f64, source: &mut dyn EntropySource) {
+    for idx in 0..cells.len() {
+        cells.set(idx, source.next_f64() < density);
+    }
+}
@@ -0,0 +1,323 @@
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::isotropic::Transitions;
+#[cfg(feature = "rules")]
+use crate::{isotropic, Error};
+use crate::{LogLevel, Universe};
+
+/// Set in `to_bits()` for non-totalistic rules, which need their
+/// rulestring stored as well.
+pub(crate) const NON_TOTALISTIC: u32 = 1 << 31;
+
+/// A Life-like rule: which live-neighbour counts make a dead cell come
+/// alive and which keep a live cell alive.
+///
+/// Isotropic non-totalistic rules also tell apart the arrangements of
+/// the live neighbours, see `parse()`. Without the `rules` feature only
+/// Conway's rule and its complement are available.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// Bit `n` set when a dead cell with `n` live neighbours is born,
+    /// whatever their arrangement.
+    birth: u16,
+    /// Bit `n` set when a live cell with `n` live neighbours survives,
+    /// whatever their arrangement.
+    survival: u16,
+    /// The next state of every 3 x 3 neighbourhood, for non-totalistic
+    /// rules only
+    #[cfg(feature = "rules")]
+    transitions: Option<Transitions>,
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::conway()
+    }
+}
+
+#[wasm_bindgen]
+impl Rule {
+    /// Parse a rulestring in B/S notation, e.g. `"B3/S23"` or `"b36s23"`,
+    /// or in the older S/B notation, e.g. `"23/3"`.
+    ///
+    /// Counts may be followed by Hensel's letters for isotropic
+    /// non-totalistic rules: `"B2-a/S12"` gives births for two neighbours
+    /// unless they are adjacent at a corner and an edge (`2a`), and
+    /// `"B3/S2ce3"` survivals for three neighbours and for two on corners
+    /// or edges only.
+    #[cfg(feature = "rules")]
+    pub fn parse(rulestring: &str) -> Result<Rule, Error> {
+        let text = rulestring.trim().to_ascii_lowercase();
+        if text.is_empty() {
+            return Err(Error::InvalidRule("empty rulestring"));
+        }
+        if !text.contains(['b', 's']) {
+            let (survival, birth) = text
+                .split_once('/')
+                .ok_or(Error::InvalidRule("expected B/S or S/B notation"))?;
+            return Ok(Rule::from_counts(
+                &isotropic::parse_counts(birth)?,
+                &isotropic::parse_counts(survival)?,
+            ));
+        }
+
+        let (mut birth, mut survival) = (None, None);
+        let mut rest = text.as_str();
+        while let Some(letter) = rest.chars().next() {
+            let slot = match letter {
+                'b' => &mut birth,
+                's' => &mut survival,
+                _ => return Err(Error::InvalidRule("expected B/S or S/B notation")),
+            };
+            if slot.is_some() {
+                return Err(Error::InvalidRule("B or S given twice"));
+            }
+            let counts = &rest[1..];
+            let end = counts
+                .find(|c: char| !c.is_ascii_digit() && !isotropic::is_hensel_letter(c))
+                .unwrap_or(counts.len());
+            *slot = Some(isotropic::parse_counts(&counts[..end])?);
+            rest = counts[end..].strip_prefix('/').unwrap_or(&counts[end..]);
+        }
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Rule::from_counts(&birth, &survival)),
+            _ => Err(Error::InvalidRule("both B and S are required")),
+        }
+    }
+
+    /// Conway's Game of Life, B3/S23.
+    pub fn conway() -> Rule {
+        Rule {
+            birth: 1 << 3,
+            survival: 1 << 2 | 1 << 3,
+            #[cfg(feature = "rules")]
+            transitions: None,
+        }
+    }
+
+    /// Whether a dead cell with `neighbours` live neighbours is born; for
+    /// non-totalistic rules, whatever their arrangement.
+    pub fn births(&self, neighbours: u8) -> bool {
+        neighbours <= 8 && self.birth & 1 << neighbours != 0
+    }
+
+    /// Whether a live cell with `neighbours` live neighbours survives; for
+    /// non-totalistic rules, whatever their arrangement.
+    pub fn survives(&self, neighbours: u8) -> bool {
+        neighbours <= 8 && self.survival & 1 << neighbours != 0
+    }
+
+    /// The black/white complement: the rule under which the inverse video
+    /// of a pattern evolves like the pattern does under this one. A dead
+    /// cell with `n` live neighbours is a live one with `8 - n` in the
+    /// inverse, so births follow from the complement of the survivals and
+    /// survivals from that of the births.
+    pub fn complement(&self) -> Rule {
+        #[cfg(feature = "rules")]
+        if let Some(transitions) = self.transitions {
+            return Rule::from_transitions(&transitions.complement());
+        }
+        let mirror = |mask: u16| {
+            (0..=8)
+                .filter(|n| mask & 1 << n == 0)
+                .fold(0, |complement, n| complement | 1 << (8 - n))
+        };
+        Rule {
+            birth: mirror(self.survival),
+            survival: mirror(self.birth),
+            #[cfg(feature = "rules")]
+            transitions: None,
+        }
+    }
+
+    /// Whether the next state only depends on the number of live
+    /// neighbours, not on their arrangement.
+    pub fn is_totalistic(&self) -> bool {
+        self.transitions().is_none()
+    }
+
+    /// Whether the rule is its own complement, like Day & Night, so
+    /// patterns and their inverse video behave alike.
+    pub fn is_self_complementary(&self) -> bool {
+        self.complement() == *self
+    }
+
+    /// The rulestring in canonical B/S notation, e.g. `"B36/S23"`.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn rulestring(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Rule {
+    /// Birth mask in the low and survival mask in the high half, as stored
+    /// in snapshots, plus `NON_TOTALISTIC` for rules these do not cover.
+    #[cfg_attr(not(feature = "formats"), allow(dead_code))]
+    pub(crate) fn to_bits(self) -> u32 {
+        let flag = match self.transitions() {
+            Some(_) => NON_TOTALISTIC,
+            None => 0,
+        };
+        self.birth as u32 | (self.survival as u32) << 16 | flag
+    }
+
+    /// Inverse of `to_bits()` for totalistic rules; `None` for counts
+    /// above 8 and for `NON_TOTALISTIC`.
+    #[cfg_attr(not(feature = "formats"), allow(dead_code))]
+    pub(crate) fn from_bits(bits: u32) -> Option<Rule> {
+        let (birth, survival) = (bits as u16, (bits >> 16) as u16);
+        (birth < 1 << 9 && survival < 1 << 9).then_some(Rule {
+            birth,
+            survival,
+            #[cfg(feature = "rules")]
+            transitions: None,
+        })
+    }
+
+    /// A rule from the letter masks of each count (see
+    /// `isotropic::parse_counts()`), totalistic when every count has all
+    /// arrangements or none.
+    #[cfg(feature = "rules")]
+    fn from_counts(birth: &[u16; 9], survival: &[u16; 9]) -> Rule {
+        let whole = |counts: &[u16; 9]| {
+            (0..=8)
+                .filter(|&n| counts[n] == isotropic::full(n))
+                .fold(0, |mask, n| mask | 1 << n)
+        };
+        let totalistic =
+            |counts: &[u16; 9]| (0..=8).all(|n| counts[n] == 0 || counts[n] == isotropic::full(n));
+        Rule {
+            birth: whole(birth),
+            survival: whole(survival),
+            transitions: match totalistic(birth) && totalistic(survival) {
+                true => None,
+                false => Some(Transitions::from_counts(birth, survival)),
+            },
+        }
+    }
+
+    #[cfg(feature = "rules")]
+    fn from_transitions(transitions: &Transitions) -> Rule {
+        Rule::from_counts(&transitions.counts(false), &transitions.counts(true))
+    }
+
+    /// The table of a non-totalistic rule.
+    #[cfg(feature = "rules")]
+    pub(crate) fn transitions(&self) -> Option<Transitions> {
+        self.transitions
+    }
+
+    /// Without rulestrings every rule is totalistic.
+    #[cfg(not(feature = "rules"))]
+    pub(crate) fn transitions(&self) -> Option<Transitions> {
+        None
+    }
+
+    /// The next state of the middle cell of a 3 x 3 neighbourhood, given
+    /// as 9 bits in row-major order.
+    pub(crate) fn apply(&self, pattern: u16) -> bool {
+        match self.transitions() {
+            Some(transitions) => transitions.get(pattern),
+            None => self.next(
+                pattern & 1 << 4 != 0,
+                (pattern & !(1 << 4)).count_ones() as u8,
+            ),
+        }
+    }
+
+    /// State of a cell in the next generation.
+    pub(crate) fn next(&self, alive: bool, neighbours: u8) -> bool {
+        if alive {
+            self.survives(neighbours)
+        } else {
+            self.births(neighbours)
+        }
+    }
+}
+
+/// The complement of a rulestring, see `Rule::complement()`, e.g.
+/// `"B0123478/S01234678"` for `"B3/S23"`.
+#[cfg(feature = "rules")]
+#[wasm_bindgen]
+pub fn complement_rule(rulestring: &str) -> Result<String, Error> {
+    Ok(Rule::parse(rulestring)?.complement().to_string())
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A dead universe evolving under `rulestring`, e.g. `"B36/S23"` for
+    /// HighLife. Fails when the rulestring does not parse.
+    #[cfg(feature = "rules")]
+    pub fn with_rule(height: u32, width: u32, rulestring: &str) -> Result<Universe, Error> {
+        let rule = Rule::parse(rulestring)?;
+        let mut universe = Universe::try_new(height, width)?;
+        universe.rule = rule;
+        Ok(universe)
+    }
+
+    /// The rule `tick()` applies; Conway's B3/S23 unless changed.
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Apply `rule` from the next tick on. The cells are kept, checkpoints
+    /// are dropped since they were computed under the previous rule.
+    pub fn set_rule(&mut self, rule: &Rule) {
+        log_at!(LogLevel::Info, "rule", "{} -> {}", self.rule, rule);
+        self.rule = *rule;
+        self.reset_checkpoints();
+        self.forget_states();
+    }
+
+    /// `set_rule()` from a rulestring. Fails, keeping the current rule,
+    /// when it does not parse.
+    #[cfg(feature = "rules")]
+    pub fn set_rulestring(&mut self, rulestring: &str) -> Result<(), Error> {
+        self.set_rule(&Rule::parse(rulestring)?);
+        Ok(())
+    }
+
+    /// Switch to the inverse video: kill every live cell, bring every dead
+    /// one to life and switch to the complementary rule, so the universe
+    /// keeps evolving equivalently. Doing it twice restores the original.
+    ///
+    /// On `Topology::Dead` the cells beyond the edges stay dead rather
+    /// than coming to life with the rest, so there the inverse evolves
+    /// differently near the edges.
+    pub fn complement_state(&mut self) {
+        self.wake();
+        self.cells.toggle_range(..);
+        self.rehash();
+        self.rule = self.rule.complement();
+        // the history so far ran under the other rule
+        self.reset_checkpoints();
+        self.forget_states();
+        self.rewind.clear();
+        self.restart_ages();
+        self.reset_envelope();
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "rules")]
+        if let Some(transitions) = self.transitions {
+            return write!(
+                f,
+                "B{}/S{}",
+                isotropic::format_counts(&transitions.counts(false)),
+                isotropic::format_counts(&transitions.counts(true))
+            );
+        }
+        let digits = |mask: u16| -> String {
+            (0..=8)
+                .filter(|n| mask & 1 << n != 0)
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))
+    }
+}
@@ -0,0 +1,135 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::rng::{EntropySource, Xoshiro256};
+use crate::{Error, Rule, StopCondition, StopReason, Universe};
+
+/// Soups `analyze_rule()` runs to estimate explosiveness.
+const SAMPLE_SOUPS: u32 = 8;
+/// Side of each soup, placed in the middle of a universe `SOUP_ROOM`
+/// times as wide.
+const SOUP_SIZE: u32 = 16;
+const SOUP_ROOM: u32 = 4;
+/// Generations a soup gets to explode.
+const SOUP_GENERATIONS: u32 = 256;
+
+/// Well-known Life-like rules, by canonical rulestring.
+const NAMED_RULES: &[(&str, &str)] = &[
+    ("B3/S23", "Life"),
+    ("B36/S23", "HighLife"),
+    ("B3678/S34678", "Day & Night"),
+    ("B2/S", "Seeds"),
+    ("B3/S012345678", "Life without Death"),
+    ("B1357/S1357", "Replicator"),
+    ("B1357/S02468", "Fredkin"),
+    ("B3/S12345", "Maze"),
+    ("B3/S1234", "Mazectric"),
+    ("B35678/S5678", "Diamoeba"),
+    ("B36/S125", "2x2"),
+    ("B368/S245", "Morley"),
+    ("B4678/S35678", "Anneal"),
+    ("B34/S34", "34 Life"),
+    ("B345/S5", "Long Life"),
+    ("B3/S45678", "Coral"),
+    ("B1/S1", "Gnarl"),
+    ("B357/S1358", "Amoeba"),
+    ("B37/S23", "DryLife"),
+    ("B38/S23", "Pedestrian Life"),
+    ("B234/S", "Serviettes"),
+    ("B3/S12", "Flock"),
+    ("B345/S4567", "Assimilation"),
+    ("B3678/S235678", "Stains"),
+    ("B45678/S2345", "Walled Cities"),
+    ("B5678/S45678", "Vote"),
+    ("B378/S012345678", "Plow World"),
+];
+
+/// Properties of a rule, see `analyze_rule()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleReport {
+    rule: Rule,
+    exploded: u32,
+}
+
+#[wasm_bindgen]
+impl RuleReport {
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Whether dead cells with no live neighbours are born, which makes
+    /// the whole background flash on every other generation (or stay on,
+    /// with S8).
+    pub fn has_b0(&self) -> bool {
+        self.rule.births(0)
+    }
+
+    pub fn is_self_complementary(&self) -> bool {
+        self.rule.is_self_complementary()
+    }
+
+    /// Share of the sample soups that grew to more live cells than twice
+    /// their starting area: 0 for rules where soups die out or settle, 1
+    /// for explosive ones.
+    pub fn explosiveness(&self) -> f64 {
+        self.exploded as f64 / SAMPLE_SOUPS as f64
+    }
+
+    /// Common name of the rule, or an empty string.
+    pub fn name(&self) -> String {
+        rule_name(&self.rule).unwrap_or_default().to_string()
+    }
+
+    /// Names the rule is known under, including as the black/white
+    /// complement of a named rule.
+    pub fn equivalents(&self) -> Vec<String> {
+        let mut names: Vec<String> = rule_name(&self.rule)
+            .map(str::to_string)
+            .into_iter()
+            .collect();
+        if let Some(name) = rule_name(&self.rule.complement()) {
+            if !self.rule.is_self_complementary() {
+                names.push(format!("{} (black/white complement)", name));
+            }
+        }
+        names
+    }
+}
+
+/// Report the properties of a rulestring: B0, self-complementarity, known
+/// names, and how explosive it is on a few seeded random soups.
+///
+/// Fails when the rulestring does not parse.
+#[wasm_bindgen]
+pub fn analyze_rule(rulestring: &str) -> Result<RuleReport, Error> {
+    let rule = Rule::parse(rulestring)?;
+    let side = SOUP_SIZE * SOUP_ROOM;
+    let offset = (side - SOUP_SIZE) / 2;
+    let mut rng = Xoshiro256::new(0x5eed);
+    let mut exploded = 0;
+    for _ in 0..SAMPLE_SOUPS {
+        let mut cells = FixedBitSet::with_capacity((side * side) as usize);
+        for row in offset..offset + SOUP_SIZE {
+            for col in offset..offset + SOUP_SIZE {
+                cells.set((row * side + col) as usize, rng.next_f64() < 0.5);
+            }
+        }
+        let mut universe = Universe::from_cells(side, side, cells);
+        universe.rule = rule;
+        let growth = StopCondition::population_above(2 * SOUP_SIZE * SOUP_SIZE);
+        let report = universe.run_until(&growth, SOUP_GENERATIONS)?;
+        if report.reason == StopReason::PopulationAbove {
+            exploded += 1;
+        }
+    }
+    Ok(RuleReport { rule, exploded })
+}
+
+fn rule_name(rule: &Rule) -> Option<&'static str> {
+    let rulestring = rule.to_string();
+    NAMED_RULES
+        .iter()
+        .find(|(known, _)| *known == rulestring)
+        .map(|&(_, name)| name)
+}
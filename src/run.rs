@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::mem;
+
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "formats")]
+use crate::Census;
+use crate::{Error, Universe};
+
+/// Approximate cost of one entry in the cycle detection table.
+const SEEN_ENTRY_BYTES: usize = mem::size_of::<u64>() + mem::size_of::<u32>();
+
+/// What `run_until()` is waiting for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Condition {
+    Extinct,
+    Stable,
+    PeriodDetected,
+    PopulationAbove(u32),
+    BoundingBoxTouchesEdge,
+}
+
+/// A stop condition for `Universe::run_until()`.
+///
+/// Built through the associated constructors so the same value can be
+/// created from Rust and from JavaScript.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StopCondition {
+    condition: Condition,
+}
+
+#[wasm_bindgen]
+impl StopCondition {
+    /// Stop once no cell is alive.
+    pub fn extinct() -> StopCondition {
+        StopCondition {
+            condition: Condition::Extinct,
+        }
+    }
+
+    /// Stop once a tick leaves the universe unchanged (a still life).
+    pub fn stable() -> StopCondition {
+        StopCondition {
+            condition: Condition::Stable,
+        }
+    }
+
+    /// Stop once a previously seen state comes back (period >= 1).
+    pub fn period_detected() -> StopCondition {
+        StopCondition {
+            condition: Condition::PeriodDetected,
+        }
+    }
+
+    /// Stop once more than `population` cells are alive.
+    pub fn population_above(population: u32) -> StopCondition {
+        StopCondition {
+            condition: Condition::PopulationAbove(population),
+        }
+    }
+
+    /// Stop once a live cell reaches the outermost row or column.
+    pub fn bounding_box_touches_edge() -> StopCondition {
+        StopCondition {
+            condition: Condition::BoundingBoxTouchesEdge,
+        }
+    }
+}
+
+/// Why `run_until()` returned.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    Extinct,
+    Stable,
+    PeriodDetected,
+    PopulationAbove,
+    BoundingBoxTouchesEdge,
+    /// The condition never held within `max_generations`.
+    MaxGenerations,
+}
+
+/// Outcome of `run_until()`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunReport {
+    pub reason: StopReason,
+    /// Number of ticks executed by this call.
+    pub generations: u32,
+    /// Detected period, or 0 when no repetition was observed.
+    pub period: u32,
+}
+
+/// Outcome of `run_until_stable()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StabilizationReport {
+    /// Whether a still life or cycle was reached within the budget.
+    pub stabilized: bool,
+    /// 1 for a still life (including extinction), > 1 for an oscillating
+    /// state, 0 when the run did not stabilize.
+    pub period: u32,
+    /// Ticks after which the final cycle was first entered.
+    pub stabilized_at: u32,
+    /// Number of ticks executed by this call.
+    pub generations: u32,
+    /// Live cells in the final state.
+    pub population: u32,
+    /// Named objects in the final state
+    #[cfg(feature = "formats")]
+    census: Census,
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen]
+impl StabilizationReport {
+    /// The objects the final state is made of, see `Universe::census()`.
+    pub fn census(&self) -> Census {
+        self.census.clone()
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Run a soup to completion: tick until the state settles into a still
+    /// life or a cycle, or `max_generations` ticks have run.
+    /// With the `formats` feature the report also takes a census of the
+    /// final state.
+    pub fn run_until_stable(&mut self, max_generations: u32) -> Result<StabilizationReport, Error> {
+        let report = self.run_until(&StopCondition::period_detected(), max_generations)?;
+        let stabilized = report.reason == StopReason::PeriodDetected;
+
+        Ok(StabilizationReport {
+            stabilized,
+            period: report.period,
+            stabilized_at: if stabilized {
+                report.generations - report.period
+            } else {
+                0
+            },
+            generations: report.generations,
+            population: self.population,
+            #[cfg(feature = "formats")]
+            census: self.census()?,
+        })
+    }
+
+    /// Tick until `condition` holds or `max_generations` ticks have run.
+    ///
+    /// Conditions on the current state (extinction, population, edges) are
+    /// checked before the first tick, so an already extinct universe
+    /// returns with `generations == 0`.
+    ///
+    /// Fails when `max_generations` exceeds `Limits::max_generations_per_call`,
+    /// or when period detection outgrows `Limits::max_history_bytes`.
+    pub fn run_until(
+        &mut self,
+        condition: &StopCondition,
+        max_generations: u32,
+    ) -> Result<RunReport, Error> {
+        let report = self.run_until_condition(condition, max_generations)?;
+        // the run is complete, even when it stopped early
+        self.report_progress(report.generations, report.generations);
+        Ok(report)
+    }
+}
+
+impl Universe {
+    fn run_until_condition(
+        &mut self,
+        condition: &StopCondition,
+        max_generations: u32,
+    ) -> Result<RunReport, Error> {
+        // the state checks read the cells before the first tick
+        self.wake();
+        let limits = self.limits;
+        if max_generations > limits.max_generations_per_call {
+            return Err(Error::GenerationLimit {
+                requested: max_generations,
+                limit: limits.max_generations_per_call,
+            });
+        }
+
+        // state hash -> number of ticks executed when it was seen
+        let mut seen: HashMap<u64, u32> = HashMap::new();
+        seen.insert(self.state_hash(), 0);
+
+        let mut generations = 0;
+        loop {
+            if let Some(reason) = self.check_state(condition.condition) {
+                return Ok(RunReport {
+                    reason,
+                    generations,
+                    period: 0,
+                });
+            }
+            if generations == max_generations {
+                return Ok(RunReport {
+                    reason: StopReason::MaxGenerations,
+                    generations,
+                    period: 0,
+                });
+            }
+
+            self.progress_step(generations, max_generations)?;
+            self.tick();
+            generations += 1;
+
+            match condition.condition {
+                Condition::Stable if !self.last_tick.changed => {
+                    return Ok(RunReport {
+                        reason: StopReason::Stable,
+                        generations,
+                        period: 1,
+                    });
+                }
+                Condition::PeriodDetected => {
+                    if let Some(first) = seen.insert(self.state_hash(), generations) {
+                        return Ok(RunReport {
+                            reason: StopReason::PeriodDetected,
+                            generations,
+                            period: generations - first,
+                        });
+                    }
+                    if seen.len() * SEEN_ENTRY_BYTES > limits.max_history_bytes {
+                        return Err(Error::MemoryLimit {
+                            what: "cycle detection history",
+                            limit: limits.max_history_bytes,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks the conditions that only depend on the current state.
+    fn check_state(&self, condition: Condition) -> Option<StopReason> {
+        match condition {
+            Condition::Extinct if self.population == 0 => Some(StopReason::Extinct),
+            Condition::PopulationAbove(n) if self.population > n => {
+                Some(StopReason::PopulationAbove)
+            }
+            Condition::BoundingBoxTouchesEdge if self.touches_edge() => {
+                Some(StopReason::BoundingBoxTouchesEdge)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether any live cell lies on the outermost rows or columns.
+    fn touches_edge(&self) -> bool {
+        if self.width == 0 || self.height == 0 {
+            return false;
+        }
+        let last_row = self.height - 1;
+        let last_col = self.width - 1;
+        (0..self.width).any(|col| {
+            self.cells[self.get_index(0, col)] || self.cells[self.get_index(last_row, col)]
+        }) || (0..self.height).any(|row| {
+            self.cells[self.get_index(row, 0)] || self.cells[self.get_index(row, last_col)]
+        })
+    }
+}
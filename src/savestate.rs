@@ -0,0 +1,93 @@
+use wasm_bindgen::prelude::*;
+
+use crate::hibernate::Compressed;
+use crate::{Error, Rule, StorageOrder, TickStats, Universe};
+
+/// A moment bookmarked by `Universe::save_snapshot()`.
+#[derive(Clone, Debug)]
+pub(crate) struct SaveState {
+    cells: Compressed,
+    order: StorageOrder,
+    width: u32,
+    height: u32,
+    generation: u32,
+    rule: Rule,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Bookmark the current cells, generation and rule under `name`,
+    /// compressed like `hibernate()` does, replacing any earlier
+    /// save-state of that name. Unlike `init_states`, any number can be
+    /// kept.
+    pub fn save_snapshot(&mut self, name: &str) {
+        let state = SaveState {
+            cells: Compressed::new(&self.current_cells()),
+            order: self.order,
+            width: self.width,
+            height: self.height,
+            generation: self.generation,
+            rule: self.rule,
+        };
+        self.save_states.insert(name.to_owned(), state);
+    }
+
+    /// Go back (or forward) to the save-state `name`: its cells,
+    /// generation and rule replace the current ones. The initial state and
+    /// the save-states themselves stay; checkpoints and the `step_back()`
+    /// history, which belong to another timeline, are dropped.
+    ///
+    /// Fails when there is no such save-state, or the universe has been
+    /// resized since it was saved.
+    pub fn load_snapshot(&mut self, name: &str) -> Result<(), Error> {
+        let state = self
+            .save_states
+            .get(name)
+            .ok_or_else(|| Error::NoSaveState(name.to_owned()))?;
+        if (state.width, state.height) != (self.width, self.height) {
+            return Err(Error::InvalidSnapshot(
+                "the save-state has other dimensions",
+            ));
+        }
+        let cells = state
+            .cells
+            .restore(self.width as usize * self.height as usize);
+        let cells = self.in_storage_order(cells, state.order);
+        let (generation, rule) = (state.generation, state.rule);
+        self.wake();
+        self.cells = cells;
+        self.generation = generation;
+        self.rule = rule;
+        self.last_tick = TickStats {
+            generation,
+            ..TickStats::default()
+        };
+        self.rehash();
+        self.deltas.clear();
+        self.reset_checkpoints();
+        self.rewind.clear();
+        self.forget_states();
+        self.restart_ages();
+        Ok(())
+    }
+
+    /// The names of the save-states, sorted.
+    pub fn list_snapshots(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.save_states.keys().cloned().collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Forget the save-state `name`. Returns whether there was one.
+    pub fn delete_snapshot(&mut self, name: &str) -> bool {
+        self.save_states.remove(name).is_some()
+    }
+
+    /// Bytes held by the compressed save-states.
+    pub fn snapshots_byte_len(&self) -> usize {
+        self.save_states
+            .values()
+            .map(|state| state.cells.byte_len())
+            .sum()
+    }
+}
@@ -0,0 +1,65 @@
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use wasm_bindgen::prelude::*;
+
+use crate::{packed, Error, Rule, Universe};
+
+/// What a universe serializes to: the dimensions, the generation, the rule
+/// in B/S notation and the byte-packed cells in row-major order, as in
+/// `to_snapshot_bytes()`.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct State {
+    width: u32,
+    height: u32,
+    generation: u32,
+    rule: String,
+    cells: Vec<u8>,
+}
+
+impl Serialize for Universe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        State {
+            width: self.width,
+            height: self.height,
+            generation: self.generation,
+            rule: self.rule.to_string(),
+            cells: packed::pack(&self.row_major_cells()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Universe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Universe, D::Error> {
+        let state = State::deserialize(deserializer)?;
+        let rule = Rule::parse(&state.rule).map_err(de::Error::custom)?;
+        let mut universe =
+            Universe::from_packed(state.width, state.height, state.generation, &state.cells)
+                .map_err(de::Error::custom)?;
+        universe.rule = rule;
+        Ok(universe)
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The compact binary form, see `to_snapshot_bytes()`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_snapshot_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Universe, Error> {
+        Universe::from_snapshot_bytes(bytes)
+    }
+
+    /// The universe as JSON text, `{ width, height, generation, rule,
+    /// cells }` with `cells` an array of byte-packed, row-major bytes; the
+    /// form to keep in localStorage or send to a server.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a universe always serializes")
+    }
+
+    pub fn from_json(json: &str) -> Result<Universe, Error> {
+        serde_json::from_str(json).map_err(|_| Error::InvalidSnapshot("not a JSON universe"))
+    }
+}
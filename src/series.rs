@@ -0,0 +1,137 @@
+use std::collections::VecDeque;
+use std::mem;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{TickStats, Universe};
+
+/// Bytes recorded per generation.
+const ENTRY_BYTES: usize = 3 * mem::size_of::<u32>();
+
+/// Population, births and deaths per generation, from `start` on.
+///
+/// The oldest generations are dropped once the series outgrows
+/// `Limits::max_history_bytes`, or `capacity` generations when set.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Series {
+    start: u32,
+    /// Generations kept; 0 leaves it to the byte budget
+    capacity: u32,
+    population: VecDeque<u32>,
+    births: VecDeque<u32>,
+    deaths: VecDeque<u32>,
+}
+
+impl Series {
+    pub(crate) fn starting_at(generation: u32, population: u32) -> Series {
+        let mut series = Series::default();
+        series.restart(generation, population);
+        series
+    }
+
+    pub(crate) fn restart(&mut self, generation: u32, population: u32) {
+        self.start = generation;
+        self.population.clear();
+        self.births.clear();
+        self.deaths.clear();
+        self.population.push_back(population);
+        self.births.push_back(0);
+        self.deaths.push_back(0);
+    }
+
+    /// Append the generation `stats` describes, replacing what was
+    /// recorded for it and later generations before going back in time.
+    pub(crate) fn record(&mut self, stats: &TickStats, budget: usize) {
+        let keep = match stats.generation.checked_sub(self.start) {
+            Some(keep) if keep as usize <= self.population.len() => keep as usize,
+            // not adjacent to what was recorded, start afresh
+            _ => {
+                self.start = stats.generation;
+                0
+            }
+        };
+        self.population.truncate(keep);
+        self.births.truncate(keep);
+        self.deaths.truncate(keep);
+        self.population.push_back(stats.population);
+        self.births.push_back(stats.births);
+        self.deaths.push_back(stats.deaths);
+        self.trim(budget);
+    }
+
+    /// Drop the oldest generations until the series fits both `budget`
+    /// and `capacity`, keeping at least the latest.
+    fn trim(&mut self, budget: usize) {
+        let capacity = match self.capacity {
+            0 => usize::MAX,
+            capacity => capacity as usize,
+        };
+        while self.population.len() > 1
+            && (self.population.len() > capacity || self.population.len() * ENTRY_BYTES > budget)
+        {
+            self.population.pop_front();
+            self.births.pop_front();
+            self.deaths.pop_front();
+            self.start += 1;
+        }
+    }
+
+    /// Values of `values` for generations `start..end`, clipped to what
+    /// was recorded.
+    fn window(&self, values: &VecDeque<u32>, start: u32, end: u32) -> Vec<f64> {
+        let first = start.saturating_sub(self.start) as usize;
+        let last = (end.saturating_sub(self.start) as usize).min(values.len());
+        values
+            .range(first.min(last)..last)
+            .map(|&value| value as f64)
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// First generation of the recorded series.
+    pub fn series_start(&self) -> u32 {
+        self.series.start
+    }
+
+    /// Number of generations recorded, from `series_start()` on.
+    pub fn series_len(&self) -> u32 {
+        self.series.population.len() as u32
+    }
+
+    /// Live cells of the generations `start..end`, as far as recorded,
+    /// ready to hand to a charting library.
+    pub fn population_series(&self, start: u32, end: u32) -> Vec<f64> {
+        self.series.window(&self.series.population, start, end)
+    }
+
+    /// Live cells of every recorded generation, from `series_start()` on;
+    /// from JavaScript a `Uint32Array`.
+    pub fn population_history(&self) -> Vec<u32> {
+        self.series.population.iter().copied().collect()
+    }
+
+    /// Keep at most the latest `capacity` generations of the series
+    /// (0 bounds it by `Limits::max_history_bytes` only), dropping older
+    /// ones right away.
+    pub fn set_series_capacity(&mut self, capacity: u32) {
+        self.series.capacity = capacity;
+        self.series.trim(self.limits.max_history_bytes);
+    }
+
+    pub fn series_capacity(&self) -> u32 {
+        self.series.capacity
+    }
+
+    /// Cells born in the generations `start..end`, as far as recorded.
+    pub fn births_series(&self, start: u32, end: u32) -> Vec<f64> {
+        self.series.window(&self.series.births, start, end)
+    }
+
+    /// Cells that died in the generations `start..end`, as far as
+    /// recorded.
+    pub fn deaths_series(&self, start: u32, end: u32) -> Vec<f64> {
+        self.series.window(&self.series.deaths, start, end)
+    }
+}
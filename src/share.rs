@@ -0,0 +1,118 @@
+use wasm_bindgen::prelude::*;
+
+use crate::hibernate::{push_varint, read_varint, Compressed};
+use crate::rule::NON_TOTALISTIC;
+use crate::{Error, Limits, Rule, Universe};
+
+const VERSION: u8 = 1;
+
+/// The URL and filename safe alphabet of RFC 4648.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[wasm_bindgen]
+impl Universe {
+    /// The dimensions, rule and live cells as a short base64url string
+    /// (no padding) that fits in the hash fragment of a link.
+    ///
+    /// The cells are stored as run lengths, or byte-packed for dense soups
+    /// where runs would be longer, like `hibernate()` does.
+    pub fn to_share_string(&self) -> String {
+        let mut bytes = vec![VERSION];
+        push_varint(&mut bytes, self.width);
+        push_varint(&mut bytes, self.height);
+        push_varint(&mut bytes, self.rule.to_bits());
+        if !self.rule.is_totalistic() {
+            let rulestring = self.rule.to_string();
+            push_varint(&mut bytes, rulestring.len() as u32);
+            bytes.extend_from_slice(rulestring.as_bytes());
+        }
+        Compressed::new(&self.row_major_cells()).write(&mut bytes);
+        encode(&bytes)
+    }
+
+    /// A universe from a string made by `to_share_string()`, at
+    /// generation 0 with the shared cells as its initial state.
+    ///
+    /// Share strings come from links, so boards of more than
+    /// `Limits::max_decoded_cells` cells are rejected before any memory is
+    /// allocated for them.
+    pub fn from_share_string(text: &str) -> Result<Universe, Error> {
+        Universe::from_share_string_with_limits(text, &Limits::default())
+    }
+
+    /// `from_share_string()` under `limits`, which the universe keeps.
+    pub fn from_share_string_with_limits(text: &str, limits: &Limits) -> Result<Universe, Error> {
+        let invalid = || Error::InvalidSnapshot("not a share string");
+        let bytes = decode(text.trim_start_matches('#')).ok_or_else(invalid)?;
+        let (&version, mut rest) = bytes.split_first().ok_or_else(invalid)?;
+        if version != VERSION {
+            return Err(Error::InvalidSnapshot("unsupported share string version"));
+        }
+        let width = read_varint(&mut rest).ok_or_else(invalid)?;
+        let height = read_varint(&mut rest).ok_or_else(invalid)?;
+        let bits = read_varint(&mut rest).ok_or_else(invalid)?;
+        let rule = if bits & NON_TOTALISTIC != 0 {
+            let len = read_varint(&mut rest).ok_or_else(invalid)? as usize;
+            if len > rest.len() {
+                return Err(invalid());
+            }
+            let (rulestring, after) = rest.split_at(len);
+            rest = after;
+            std::str::from_utf8(rulestring)
+                .ok()
+                .and_then(|rulestring| Rule::parse(rulestring).ok())
+        } else {
+            Rule::from_bits(bits)
+        }
+        .ok_or(Error::InvalidSnapshot("invalid rule"))?;
+        let len = width
+            .checked_mul(height)
+            .ok_or(Error::InvalidSnapshot("dimensions overflow"))?;
+        if len > limits.max_decoded_cells {
+            return Err(Error::DimensionsTooLarge {
+                width: width as u64,
+                height: height as u64,
+            });
+        }
+        let cells = Compressed::read(rest)
+            .and_then(|cells| cells.try_restore(len as usize))
+            .ok_or(Error::InvalidSnapshot(
+                "cell data does not match dimensions",
+            ))?;
+        let mut universe = Universe::from_cells(width, height, cells);
+        universe.rule = rule;
+        universe.limits = *limits;
+        Ok(universe)
+    }
+}
+
+fn encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut group = [0; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from(group[0]) << 16 | u32::from(group[1]) << 8 | u32::from(group[2]);
+        for i in 0..=chunk.len() {
+            text.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    text
+}
+
+/// The inverse of `encode()`; `None` on characters outside the alphabet
+/// or a length no encoding has.
+fn decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3 + 2);
+    for chunk in text.as_bytes().chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut bits = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = ALPHABET.iter().position(|&a| a == c)? as u32;
+            bits |= value << (18 - 6 * i);
+        }
+        bytes.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(bytes)
+}
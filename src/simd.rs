@@ -0,0 +1,117 @@
+use core::arch::wasm32::*;
+
+use fixedbitset::{Block, FixedBitSet};
+
+use crate::simulate::{scatter, BITS};
+use crate::{Rule, Topology, Universe};
+
+/// Cells per vector.
+const LANES: usize = 16;
+
+impl Universe {
+    /// The next generation with WebAssembly SIMD: each line is unpacked
+    /// to one byte per cell, padded with its wrapped-around neighbours, so
+    /// the eight neighbour counts of 16 cells are eight unaligned loads and
+    /// adds, and the rule is applied with table lookups.
+    ///
+    /// Only the torus is vectorized; other topologies run bit-parallel.
+    pub(crate) fn step_simd(&self, next: &mut FixedBitSet) {
+        if self.topology != Topology::Torus {
+            return self.step_bit_parallel(next);
+        }
+        let (lines, len) = self.lines();
+        next.clone_from(&self.cells);
+        next.clear();
+        if lines == 0 || len == 0 {
+            return;
+        }
+        let stride = len.div_ceil(BITS);
+        let grid = self.gather_lines();
+        let padded = len + 2;
+        let mut bytes = vec![0u8; lines * padded];
+        for (line, out) in bytes.chunks_mut(padded).enumerate() {
+            unpack(&grid[line * stride..][..stride], len, out);
+        }
+
+        let tables = Tables::new(&self.rule);
+        let mut out = vec![0; stride];
+        for line in 0..lines {
+            let row = |line: usize| &bytes[line * padded..][..padded];
+            let above = row((line + lines - 1) % lines);
+            let here = row(line);
+            let below = row((line + 1) % lines);
+            out.iter_mut().for_each(|word| *word = 0);
+
+            let mut x = 0;
+            while x + LANES <= len {
+                // SAFETY: x + 2 + LANES <= padded, so every load is in bounds
+                let next = unsafe { tables.apply(above, here, below, x) };
+                let bits = u8x16_bitmask(i8x16_shl(next, 7)) as Block;
+                // LANES divides BITS, so the 16 bits never straddle words
+                out[x / BITS] |= bits << (x % BITS);
+                x += LANES;
+            }
+            for x in x..len {
+                let count = above[x..x + 3].iter().chain(&below[x..x + 3]).sum::<u8>()
+                    + here[x]
+                    + here[x + 2];
+                let alive = self.rule.next(here[x + 1] == 1, count);
+                out[x / BITS] |= (alive as Block) << (x % BITS);
+            }
+            scatter(&out, line * len, len, next.as_mut_slice());
+        }
+    }
+}
+
+/// `len` cells of `words` as bytes 0 or 1 at `out[1..=len]`, with the last
+/// cell copied to `out[0]` and the first to `out[len + 1]`.
+fn unpack(words: &[Block], len: usize, out: &mut [u8]) {
+    for x in 0..len {
+        out[x + 1] = (words[x / BITS] >> (x % BITS) & 1) as u8;
+    }
+    out[0] = out[len];
+    out[len + 1] = out[1];
+}
+
+/// Birth and survival by neighbour count, as swizzle tables.
+struct Tables {
+    birth: v128,
+    survival: v128,
+}
+
+impl Tables {
+    fn new(rule: &Rule) -> Tables {
+        let table = |keep: &dyn Fn(u8) -> bool| {
+            let mut bytes = [0u8; LANES];
+            for n in 0..=8 {
+                bytes[n as usize] = keep(n) as u8;
+            }
+            // SAFETY: reads the 16 bytes of `bytes`
+            unsafe { v128_load(bytes.as_ptr() as *const v128) }
+        };
+        Tables {
+            birth: table(&|n| rule.births(n)),
+            survival: table(&|n| rule.survives(n)),
+        }
+    }
+
+    /// Next state of the 16 cells from `x` on, as bytes 0 or 1.
+    ///
+    /// # Safety
+    ///
+    /// The three padded lines must hold at least `x + 2 + LANES` bytes.
+    unsafe fn apply(&self, above: &[u8], here: &[u8], below: &[u8], x: usize) -> v128 {
+        let load = |line: &[u8], dx: usize| v128_load(line.as_ptr().add(x + dx) as *const v128);
+        let mut count = u8x16_add(load(here, 0), load(here, 2));
+        for line in [above, below].iter() {
+            for dx in 0..3 {
+                count = u8x16_add(count, load(line, dx));
+            }
+        }
+        let alive = load(here, 1);
+        v128_or(
+            v128_and(u8x16_swizzle(self.survival, count), alive),
+            v128_andnot(u8x16_swizzle(self.birth, count), alive),
+        )
+    }
+}
@@ -0,0 +1,324 @@
+use std::ops::Range;
+
+use fixedbitset::{Block, FixedBitSet};
+
+use crate::topology::Edge;
+use crate::{Rule, StorageOrder, Universe};
+
+pub(crate) const BITS: usize = Block::BITS as usize;
+
+impl Universe {
+    /// The next generation computed a whole word of cells at a time: each
+    /// storage line (a row, or a column in column-major order) is gathered
+    /// into aligned words, its eight neighbour lines are formed by
+    /// shifting, and the counts are summed with bit-sliced adders.
+    ///
+    /// The Moore neighbourhood is symmetric under transposition, so
+    /// column-major universes run through the same code with rows and
+    /// columns swapped; only the edges (see `edges()`) tell them apart.
+    ///
+    /// Lines only depend on their neighbours in the current generation,
+    /// so with the `native-parallel` feature large universes share them
+    /// out between threads.
+    pub(crate) fn step_bit_parallel(&self, next: &mut FixedBitSet) {
+        let (lines, len) = self.lines();
+        next.clone_from(&self.cells);
+        next.clear();
+        if lines == 0 || len == 0 {
+            return;
+        }
+        let grid = self.gather_lines();
+        let (ends, outside) = self.edges();
+        let source = Lines {
+            grid: &grid,
+            lines,
+            len,
+            ends,
+            outside,
+            masks: RuleMasks::new(&self.rule),
+        };
+        let stride = len.div_ceil(BITS);
+        let next_words = next.as_mut_slice();
+        for (range, out) in source.step_split() {
+            for (line, words) in range.zip(out.chunks(stride)) {
+                scatter(words, line * len, len, next_words);
+            }
+        }
+    }
+
+    /// Number and length of the lines the cells are stored in.
+    pub(crate) fn lines(&self) -> (usize, usize) {
+        match self.order {
+            StorageOrder::RowMajor => (self.height as usize, self.width as usize),
+            StorageOrder::ColumnMajor => (self.width as usize, self.height as usize),
+        }
+    }
+
+    /// Every line copied to start on a word boundary, `len.div_ceil(BITS)`
+    /// words each.
+    pub(crate) fn gather_lines(&self) -> Vec<Block> {
+        let (lines, len) = self.lines();
+        let stride = len.div_ceil(BITS);
+        let mut grid = vec![0; lines * stride];
+        for (line, words) in grid.chunks_mut(stride).enumerate() {
+            gather(self.cells.as_slice(), line * len, len, words);
+        }
+        grid
+    }
+}
+
+/// Universes with fewer cells tick on one thread; below this, spawning
+/// costs more than it saves.
+#[cfg(all(feature = "native-parallel", not(target_arch = "wasm32")))]
+const PARALLEL_MIN_CELLS: usize = 1 << 16;
+
+/// What `step_bit_parallel()` computes the next generation from, apart
+/// from the rest of the universe so that threads can share it.
+struct Lines<'a> {
+    grid: &'a [Block],
+    lines: usize,
+    len: usize,
+    ends: Edge,
+    outside: Edge,
+    masks: RuleMasks,
+}
+
+impl Lines<'_> {
+    /// The next generation of consecutive ranges of lines, covering them
+    /// all, `len.div_ceil(BITS)` words per line.
+    #[cfg(not(all(feature = "native-parallel", not(target_arch = "wasm32"))))]
+    fn step_split(&self) -> Vec<(Range<usize>, Vec<Block>)> {
+        vec![(0..self.lines, self.step_range(0..self.lines))]
+    }
+
+    /// With the `native-parallel` feature, large universes are split into
+    /// one range per available core, each stepped on its own thread.
+    #[cfg(all(feature = "native-parallel", not(target_arch = "wasm32")))]
+    fn step_split(&self) -> Vec<(Range<usize>, Vec<Block>)> {
+        let threads = match self.lines * self.len {
+            cells if cells < PARALLEL_MIN_CELLS => 1,
+            _ => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+        let chunk = self.lines.div_ceil(threads.min(self.lines));
+        let ranges: Vec<Range<usize>> = (0..self.lines)
+            .step_by(chunk)
+            .map(|start| start..(start + chunk).min(self.lines))
+            .collect();
+        if ranges.len() == 1 {
+            return vec![(0..self.lines, self.step_range(0..self.lines))];
+        }
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = ranges
+                .iter()
+                .map(|range| {
+                    let range = range.clone();
+                    scope.spawn(move || self.step_range(range))
+                })
+                .collect();
+            ranges
+                .into_iter()
+                .zip(workers)
+                .map(|(range, worker)| (range, worker.join().expect("tick worker panicked")))
+                .collect()
+        })
+    }
+
+    fn line_at(&self, line: usize) -> &[Block] {
+        let stride = self.len.div_ceil(BITS);
+        &self.grid[line * stride..][..stride]
+    }
+
+    fn step_range(&self, range: Range<usize>) -> Vec<Block> {
+        let (lines, len) = (self.lines, self.len);
+        let stride = len.div_ceil(BITS);
+        // the lines before the first and after the last, and which line
+        // their ends continue on when flipped
+        let zero = vec![0; stride];
+        let (first, last) = match self.outside {
+            Edge::Flip => (
+                reversed(self.line_at(lines - 1), len),
+                reversed(self.line_at(0), len),
+            ),
+            Edge::Dead => (zero.clone(), zero),
+            Edge::Wrap => (self.line_at(lines - 1).to_vec(), self.line_at(0).to_vec()),
+            Edge::Mirror => (self.line_at(0).to_vec(), self.line_at(lines - 1).to_vec()),
+        };
+        let (first_index, last_index) = match self.outside {
+            Edge::Mirror => (0, lines - 1),
+            _ => (lines - 1, 0),
+        };
+
+        let mut shifted = vec![0; 6 * stride];
+        let mut out = vec![0; range.len() * stride];
+        for (line, out) in range.zip(out.chunks_mut(stride)) {
+            let (above, above_index) = match line {
+                0 => (&first[..], first_index),
+                _ => (self.line_at(line - 1), line - 1),
+            };
+            let here = self.line_at(line);
+            let (below, below_index) = match line + 1 {
+                next if next == lines => (&last[..], last_index),
+                next => (self.line_at(next), next),
+            };
+            {
+                let (west, east) = shifted.split_at_mut(3 * stride);
+                let sources = [(above, above_index), (here, line), (below, below_index)];
+                for (i, &(source, index)) in sources.iter().enumerate() {
+                    // cells one before the start and one after the end
+                    let (before, after) = match self.ends {
+                        Edge::Wrap => (bit(source, len - 1), bit(source, 0)),
+                        Edge::Dead => (0, 0),
+                        Edge::Mirror => (bit(source, 0), bit(source, len - 1)),
+                        Edge::Flip => {
+                            let partner = self.line_at(lines - 1 - index);
+                            (bit(partner, len - 1), bit(partner, 0))
+                        }
+                    };
+                    shift_west(source, len, before, &mut west[i * stride..][..stride]);
+                    shift_east(source, len, after, &mut east[i * stride..][..stride]);
+                }
+            }
+            for (j, word) in out.iter_mut().enumerate() {
+                let neighbours = [
+                    above[j],
+                    below[j],
+                    shifted[j],
+                    shifted[stride + j],
+                    shifted[2 * stride + j],
+                    shifted[3 * stride + j],
+                    shifted[4 * stride + j],
+                    shifted[5 * stride + j],
+                ];
+                *word = self.masks.apply(here[j], count(&neighbours));
+            }
+        }
+        out
+    }
+}
+
+/// Copy bits `start..start + len` of `cells` into `words`, starting at bit
+/// 0 and zero above `len`.
+fn gather(cells: &[Block], start: usize, len: usize, words: &mut [Block]) {
+    let (first, offset) = (start / BITS, start % BITS);
+    for (j, word) in words.iter_mut().enumerate() {
+        let low = cells.get(first + j).map_or(0, |&word| word >> offset);
+        let high = match cells.get(first + j + 1) {
+            Some(&next) if offset != 0 => next << (BITS - offset),
+            _ => 0,
+        };
+        *word = low | high;
+    }
+    mask_tail(words, len);
+}
+
+/// OR `len` bits of `words` into `cells` from bit `start` on.
+pub(crate) fn scatter(words: &[Block], start: usize, len: usize, cells: &mut [Block]) {
+    let (first, offset) = (start / BITS, start % BITS);
+    for (j, &word) in words.iter().enumerate() {
+        let word = if (j + 1) * BITS > len {
+            word & tail_mask(len)
+        } else {
+            word
+        };
+        cells[first + j] |= word << offset;
+        if offset != 0 {
+            if let Some(next) = cells.get_mut(first + j + 1) {
+                *next |= word >> (BITS - offset);
+            }
+        }
+    }
+}
+
+/// Bits of the last word of a `len` bit line that belong to it.
+pub(crate) fn tail_mask(len: usize) -> Block {
+    match len % BITS {
+        0 => !0,
+        bits => (1 << bits) - 1,
+    }
+}
+
+fn mask_tail(words: &mut [Block], len: usize) {
+    if let Some(last) = words.last_mut() {
+        *last &= tail_mask(len);
+    }
+}
+
+pub(crate) fn bit(line: &[Block], x: usize) -> Block {
+    line[x / BITS] >> (x % BITS) & 1
+}
+
+/// The first `len` bits of `line` in reverse order.
+fn reversed(line: &[Block], len: usize) -> Vec<Block> {
+    let mut out = vec![0; line.len()];
+    for x in 0..len {
+        out[x / BITS] |= bit(line, len - 1 - x) << (x % BITS);
+    }
+    out
+}
+
+/// Each cell's west neighbour: the line moved one bit up, with `before`
+/// (the cell beyond the start) shifted into bit 0.
+fn shift_west(line: &[Block], len: usize, before: Block, out: &mut [Block]) {
+    let mut carry = before;
+    for (word, &source) in out.iter_mut().zip(line) {
+        *word = (source << 1) | carry;
+        carry = source >> (BITS - 1);
+    }
+    mask_tail(out, len);
+}
+
+/// Each cell's east neighbour: the line moved one bit down, with `after`
+/// (the cell beyond the end) shifted into bit `len - 1`.
+fn shift_east(line: &[Block], len: usize, after: Block, out: &mut [Block]) {
+    for (j, word) in out.iter_mut().enumerate() {
+        let carry = line.get(j + 1).map_or(0, |&next| next << (BITS - 1));
+        *word = (line[j] >> 1) | carry;
+    }
+    let last = (len - 1) / BITS;
+    out[last] |= after << ((len - 1) % BITS);
+}
+
+/// Per-bit sums of eight neighbour words, as four bit planes (least
+/// significant first).
+pub(crate) fn count(neighbours: &[Block; 8]) -> [Block; 4] {
+    let mut sum = [0; 4];
+    for &word in neighbours {
+        let mut carry = word;
+        for plane in sum.iter_mut() {
+            let overflow = *plane & carry;
+            *plane ^= carry;
+            carry = overflow;
+        }
+    }
+    sum
+}
+
+/// The counts a rule gives births and survivals for, as all-ones or zero
+/// words so they can be applied without branching per cell.
+pub(crate) struct RuleMasks {
+    counts: Vec<(u8, Block, Block)>,
+}
+
+impl RuleMasks {
+    pub(crate) fn new(rule: &Rule) -> RuleMasks {
+        let flag = |set: bool| if set { !0 } else { 0 };
+        RuleMasks {
+            counts: (0..=8)
+                .filter(|&n| rule.births(n) || rule.survives(n))
+                .map(|n| (n, flag(rule.births(n)), flag(rule.survives(n))))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn apply(&self, alive: Block, sum: [Block; 4]) -> Block {
+        let mut next = 0;
+        for &(n, birth, survival) in &self.counts {
+            let mut equal = !0;
+            for (bit, &plane) in sum.iter().enumerate() {
+                equal &= if n >> bit & 1 == 1 { plane } else { !plane };
+            }
+            next |= equal & ((alive & survival) | (!alive & birth));
+        }
+        next
+    }
+}
@@ -0,0 +1,170 @@
+use js_sys::{ArrayBuffer, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use crate::rule::NON_TOTALISTIC;
+use crate::{packed, Error, Rule, Universe};
+
+const MAGIC: &[u8; 4] = b"GOLS";
+const VERSION: u32 = 2;
+/// magic, version, width, height, generation, rule
+const HEADER_LEN: usize = 4 + 5 * 4;
+/// Version 1 had no rule and always meant B3/S23.
+const V1_HEADER_LEN: usize = 4 + 4 * 4;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Copy the universe into a fresh `ArrayBuffer` owned by JavaScript.
+    ///
+    /// The buffer is not a view of wasm memory, so it can be listed as a
+    /// transferable in `postMessage` and handed to a Worker without a copy.
+    pub fn snapshot_transferable(&self) -> ArrayBuffer {
+        Uint8Array::from(&self.to_snapshot_bytes()[..]).buffer()
+    }
+
+    /// Rebuild a universe from a buffer made by `snapshot_transferable()`.
+    pub fn from_transferable(buffer: &ArrayBuffer) -> Result<Universe, Error> {
+        Universe::from_snapshot_bytes(&Uint8Array::new(buffer).to_vec())
+    }
+
+    /// Export as a plain object `{ width, height, generation, rule, cells }`
+    /// with `cells` as a byte-packed, row-major `Uint8Array`. Only plain
+    /// data is used, so it survives structured clone (IndexedDB, Redux
+    /// stores, workers).
+    pub fn to_plain_object(&self) -> Object {
+        let object = Object::new();
+        let cells = Uint8Array::from(&packed::pack(&self.row_major_cells())[..]);
+        set_field(&object, "width", &self.width.into());
+        set_field(&object, "height", &self.height.into());
+        set_field(&object, "generation", &self.generation.into());
+        set_field(&object, "rule", &JsValue::from_str(&self.rule.to_string()));
+        set_field(&object, "cells", &cells.into());
+        object
+    }
+
+    /// Rebuild a universe from an object made by `to_plain_object()`.
+    /// Objects without a `rule` evolve under B3/S23.
+    pub fn from_plain_object(object: &Object) -> Result<Universe, Error> {
+        let width = get_u32(object, "width")?;
+        let height = get_u32(object, "height")?;
+        let generation = get_u32(object, "generation")?;
+        let cells = Reflect::get(object, &"cells".into())
+            .ok()
+            .and_then(|cells| cells.dyn_into::<Uint8Array>().ok())
+            .ok_or(Error::InvalidSnapshot("missing cells"))?;
+        let rule = match Reflect::get(object, &"rule".into())
+            .ok()
+            .and_then(|r| r.as_string())
+        {
+            Some(rulestring) => Rule::parse(&rulestring)?,
+            None => Rule::default(),
+        };
+
+        let mut universe = Universe::from_packed(width, height, generation, &cells.to_vec())?;
+        universe.rule = rule;
+        Ok(universe)
+    }
+}
+
+fn set_field(object: &Object, key: &str, value: &JsValue) {
+    // setting a property on a fresh plain object cannot fail
+    Reflect::set(object, &key.into(), value).unwrap_throw();
+}
+
+fn get_u32(object: &Object, key: &'static str) -> Result<u32, Error> {
+    Reflect::get(object, &key.into())
+        .ok()
+        .and_then(|value| value.as_f64())
+        .filter(|value| value.fract() == 0.0 && *value >= 0.0 && *value <= u32::MAX as f64)
+        .map(|value| value as u32)
+        .ok_or(Error::InvalidSnapshot("missing or invalid numeric field"))
+}
+
+impl Universe {
+    /// Binary snapshot: a little-endian header (magic `GOLS`, version,
+    /// width, height, generation, rule) followed by the byte-packed cells
+    /// in row-major order. Version 1 snapshots, without the rule, still
+    /// load.
+    ///
+    /// Non-totalistic rules do not fit the rule word; it gets its top bit
+    /// set and the header is followed by the length of the rulestring and
+    /// the rulestring itself.
+    pub fn to_snapshot_bytes(&self) -> Vec<u8> {
+        let cells = packed::pack(&self.row_major_cells());
+        let mut bytes = Vec::with_capacity(HEADER_LEN + cells.len());
+        bytes.extend_from_slice(MAGIC);
+        let header = [
+            VERSION,
+            self.width,
+            self.height,
+            self.generation,
+            self.rule.to_bits(),
+        ];
+        for word in header.iter() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        if !self.rule.is_totalistic() {
+            let rulestring = self.rule.to_string();
+            bytes.extend_from_slice(&(rulestring.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(rulestring.as_bytes());
+        }
+        bytes.extend_from_slice(&cells);
+        bytes
+    }
+
+    pub fn from_snapshot_bytes(bytes: &[u8]) -> Result<Universe, Error> {
+        if bytes.len() < V1_HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err(Error::InvalidSnapshot("not a universe snapshot"));
+        }
+        let word = |i: usize| {
+            let start = 4 + 4 * i;
+            u32::from_le_bytes([
+                bytes[start],
+                bytes[start + 1],
+                bytes[start + 2],
+                bytes[start + 3],
+            ])
+        };
+        let (rule, header_len) = match word(0) {
+            1 => (Rule::default(), V1_HEADER_LEN),
+            VERSION if bytes.len() >= HEADER_LEN && word(4) & NON_TOTALISTIC != 0 => {
+                let invalid = Error::InvalidSnapshot("invalid rule");
+                let len = bytes
+                    .get(HEADER_LEN..HEADER_LEN + 4)
+                    .ok_or(invalid.clone())?;
+                let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+                let start = HEADER_LEN + 4;
+                let rulestring = bytes
+                    .get(start..start.saturating_add(len))
+                    .and_then(|text| std::str::from_utf8(text).ok())
+                    .ok_or(invalid.clone())?;
+                (Rule::parse(rulestring).map_err(|_| invalid)?, start + len)
+            }
+            VERSION if bytes.len() >= HEADER_LEN => (
+                Rule::from_bits(word(4)).ok_or(Error::InvalidSnapshot("invalid rule"))?,
+                HEADER_LEN,
+            ),
+            VERSION => return Err(Error::InvalidSnapshot("not a universe snapshot")),
+            _ => return Err(Error::InvalidSnapshot("unsupported snapshot version")),
+        };
+        let mut universe = Universe::from_packed(word(1), word(2), word(3), &bytes[header_len..])?;
+        universe.rule = rule;
+        Ok(universe)
+    }
+
+    pub(crate) fn from_packed(
+        width: u32,
+        height: u32,
+        generation: u32,
+        bytes: &[u8],
+    ) -> Result<Universe, Error> {
+        let size = width
+            .checked_mul(height)
+            .ok_or(Error::InvalidSnapshot("dimensions overflow"))?;
+        let cells = packed::unpack(bytes, size as usize).ok_or(Error::InvalidSnapshot(
+            "cell data does not match dimensions",
+        ))?;
+        let mut universe = Universe::from_cells(width, height, cells);
+        universe.generation = generation;
+        Ok(universe)
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{packed, Error, Rule};
+
+/// Side of a tile in cells; a tile row is one `u64`, column 0 in bit 0.
+const TILE: u32 = 64;
+
+type Tile = [u64; TILE as usize];
+
+/// A bounded torus like `Universe` for huge, mostly empty boards: the
+/// cells are kept in 64 x 64 tiles, and only tiles with live cells are
+/// stored. `tick()` visits the live tiles and their neighbours, so empty
+/// space costs neither memory nor time.
+///
+/// Rules with B0 are not supported, since every empty tile would come
+/// alive.
+#[wasm_bindgen]
+pub struct SparseUniverse {
+    width: u32,
+    height: u32,
+    tiles: HashMap<(u32, u32), Box<Tile>>,
+    rule: Rule,
+    generation: u32,
+    /// Byte-packed copy handed out by `cells()`
+    export: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl SparseUniverse {
+    pub fn new(height: u32, width: u32) -> SparseUniverse {
+        SparseUniverse {
+            width,
+            height,
+            tiles: HashMap::new(),
+            rule: Rule::default(),
+            generation: 0,
+            export: Vec::new(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.tiles
+            .values()
+            .flat_map(|tile| tile.iter())
+            .map(|row| row.count_ones())
+            .sum()
+    }
+
+    /// Number of stored tiles, i.e. those with a live cell.
+    pub fn tile_count(&self) -> u32 {
+        self.tiles.len() as u32
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Fails for rules with B0, whose background would fill every empty
+    /// tile, and for non-totalistic rules.
+    pub fn set_rule(&mut self, rule: &Rule) -> Result<(), Error> {
+        if rule.births(0) {
+            return Err(Error::InvalidRule("B0 rules need a dense universe"));
+        }
+        if !rule.is_totalistic() {
+            return Err(Error::InvalidRule("non-totalistic rules need a Universe"));
+        }
+        self.rule = *rule;
+        Ok(())
+    }
+
+    pub fn set_rulestring(&mut self, rulestring: &str) -> Result<(), Error> {
+        self.set_rule(&Rule::parse(rulestring)?)
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> Result<bool, Error> {
+        self.check_bounds(row, column)?;
+        Ok(self.cell(row as i64, column as i64))
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, column: u32) -> Result<(), Error> {
+        let alive = self.get_cell(row, column)?;
+        self.put(row, column, !alive);
+        Ok(())
+    }
+
+    pub fn reset_cells(&mut self) {
+        self.tiles.clear();
+        self.generation = 0;
+    }
+
+    /// The live cells as `[row, column, row, column, ...]`, for drawing
+    /// without touching the empty space.
+    pub fn live_cells(&self) -> Vec<u32> {
+        let mut cells = Vec::new();
+        for (&(tile_row, tile_col), tile) in &self.tiles {
+            for (r, &bits) in tile.iter().enumerate() {
+                let mut bits = bits;
+                while bits != 0 {
+                    let c = bits.trailing_zeros();
+                    cells.push(tile_row * TILE + r as u32);
+                    cells.push(tile_col * TILE + c);
+                    bits &= bits - 1;
+                }
+            }
+        }
+        cells
+    }
+
+    pub fn tick(&mut self) {
+        let (tile_rows, tile_cols) = (self.height.div_ceil(TILE), self.width.div_ceil(TILE));
+        let mut candidates = HashSet::new();
+        for &(tile_row, tile_col) in self.tiles.keys() {
+            for dr in [tile_rows - 1, 0, 1].iter() {
+                for dc in [tile_cols - 1, 0, 1].iter() {
+                    candidates.insert(((tile_row + dr) % tile_rows, (tile_col + dc) % tile_cols));
+                }
+            }
+        }
+        let masks = RuleMasks::new(&self.rule);
+        let tiles = candidates
+            .into_iter()
+            .filter_map(|key| {
+                let tile = self.step_tile(key, &masks);
+                tile.iter().any(|&row| row != 0).then_some((key, tile))
+            })
+            .collect();
+        self.tiles = tiles;
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+
+    /// Pointer to all cells in the byte-packed, row-major layout of
+    /// `Universe::cells()`, so the same drawing code works for both;
+    /// read `cells_byte_len()` bytes from it.
+    ///
+    /// This materializes the whole board, so for really huge ones prefer
+    /// `live_cells()`.
+    pub fn cells(&mut self) -> *const u8 {
+        let width = self.width as usize;
+        let mut cells = FixedBitSet::with_capacity(width * self.height as usize);
+        let live = self.live_cells();
+        for cell in live.chunks(2) {
+            cells.insert(cell[0] as usize * width + cell[1] as usize);
+        }
+        self.export = packed::pack(&cells);
+        self.export.as_ptr()
+    }
+
+    pub fn cells_byte_len(&self) -> usize {
+        (self.width as usize * self.height as usize).div_ceil(8)
+    }
+}
+
+impl SparseUniverse {
+    /// Set cells to be alive, passing the row and column of each.
+    ///
+    /// Fails without changing anything when a cell is outside the
+    /// universe.
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) -> Result<(), Error> {
+        for &(row, column) in cells {
+            self.check_bounds(row, column)?;
+        }
+        for &(row, column) in cells {
+            self.put(row, column, true);
+        }
+        Ok(())
+    }
+
+    fn check_bounds(&self, row: u32, column: u32) -> Result<(), Error> {
+        if row < self.height && column < self.width {
+            return Ok(());
+        }
+        Err(Error::OutOfBounds {
+            row: row as u64,
+            column: column as u64,
+            width: self.width,
+            height: self.height,
+        })
+    }
+
+    fn put(&mut self, row: u32, column: u32, alive: bool) {
+        let key = (row / TILE, column / TILE);
+        let bit = 1 << (column % TILE);
+        let tile = self.tiles.entry(key).or_insert_with(|| Box::new([0; 64]));
+        let bits = &mut tile[(row % TILE) as usize];
+        if alive {
+            *bits |= bit;
+        } else {
+            *bits &= !bit;
+        }
+        if tile.iter().all(|&row| row == 0) {
+            self.tiles.remove(&key);
+        }
+    }
+
+    /// The cell at `(row, column)`, wrapping around the edges.
+    fn cell(&self, row: i64, column: i64) -> bool {
+        let row = row.rem_euclid(self.height as i64) as u32;
+        let column = column.rem_euclid(self.width as i64) as u32;
+        self.tiles
+            .get(&(row / TILE, column / TILE))
+            .is_some_and(|tile| tile[(row % TILE) as usize] >> (column % TILE) & 1 == 1)
+    }
+
+    /// Row `row` (wrapping) of the tiles in tile column `tile_col`.
+    fn tile_row(&self, row: i64, tile_col: u32) -> u64 {
+        let row = row.rem_euclid(self.height as i64) as u32;
+        self.tiles
+            .get(&(row / TILE, tile_col))
+            .map_or(0, |tile| tile[(row % TILE) as usize])
+    }
+
+    /// The next generation of one tile. Tiles on the right and bottom
+    /// edges may be partial, so the cells beyond the edge (which wrap) are
+    /// looked up one by one.
+    fn step_tile(&self, (tile_row, tile_col): (u32, u32), masks: &RuleMasks) -> Box<Tile> {
+        let top = (tile_row * TILE) as i64;
+        let left = (tile_col * TILE) as i64;
+        let rows = (self.height - tile_row * TILE).min(TILE) as i64;
+        let columns = (self.width - tile_col * TILE).min(TILE);
+        let valid = if columns == TILE {
+            !0
+        } else {
+            (1 << columns) - 1
+        };
+        // each row with the cells left of its first and right of its last
+        // column
+        let line = |row: i64| {
+            let bits = self.tile_row(top + row, tile_col);
+            let west = self.cell(top + row, left - 1) as u64;
+            let east = self.cell(top + row, left + columns as i64) as u64;
+            (
+                bits,
+                (bits << 1 | west) & valid,
+                bits >> 1 | east << (columns - 1),
+            )
+        };
+
+        let mut next = Box::new([0; 64]);
+        let (mut above, mut here) = (line(-1), line(0));
+        for row in 0..rows {
+            let below = line(row + 1);
+            let neighbours = [
+                above.0, above.1, above.2, here.1, here.2, below.0, below.1, below.2,
+            ];
+            next[row as usize] = masks.apply(here.0, count(&neighbours)) & valid;
+            above = here;
+            here = below;
+        }
+        next
+    }
+}
+
+/// Per-bit sums of eight neighbour rows, as four bit planes (least
+/// significant first), like the `Universe` engines compute them.
+fn count(neighbours: &[u64; 8]) -> [u64; 4] {
+    let mut sum = [0; 4];
+    for &word in neighbours {
+        let mut carry = word;
+        for plane in sum.iter_mut() {
+            let overflow = *plane & carry;
+            *plane ^= carry;
+            carry = overflow;
+        }
+    }
+    sum
+}
+
+/// The rule as all-ones or zero words per neighbour count.
+struct RuleMasks {
+    counts: Vec<(u8, u64, u64)>,
+}
+
+impl RuleMasks {
+    fn new(rule: &Rule) -> RuleMasks {
+        let flag = |set: bool| if set { !0 } else { 0 };
+        RuleMasks {
+            counts: (0..=8)
+                .filter(|&n| rule.births(n) || rule.survives(n))
+                .map(|n| (n, flag(rule.births(n)), flag(rule.survives(n))))
+                .collect(),
+        }
+    }
+
+    fn apply(&self, alive: u64, sum: [u64; 4]) -> u64 {
+        let mut next = 0;
+        for &(n, birth, survival) in &self.counts {
+            let mut equal = !0;
+            for (bit, &plane) in sum.iter().enumerate() {
+                equal &= if n >> bit & 1 == 1 { plane } else { !plane };
+            }
+            next |= equal & ((alive & survival) | (!alive & birth));
+        }
+        next
+    }
+}
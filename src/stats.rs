@@ -0,0 +1,55 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// What happened during a single tick.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickStats {
+    /// Generation reached by the tick.
+    pub generation: u32,
+    /// Cells that came alive.
+    pub births: u32,
+    /// Cells that died.
+    pub deaths: u32,
+    /// Live cells after the tick.
+    pub population: u32,
+    /// Whether any cell changed state.
+    pub changed: bool,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Tick once and return a summary, so hosts get the per-frame
+    /// essentials without further calls.
+    pub fn tick_with_stats(&mut self) -> TickStats {
+        self.tick();
+        self.last_tick
+    }
+
+    /// Summary of the most recent tick.
+    pub fn last_tick(&self) -> TickStats {
+        self.last_tick
+    }
+
+    /// Number of ticks since the universe was created or reset.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Live cells, maintained by `tick()` and the cell edits rather than
+    /// counted on every call.
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    /// Cells that came alive in the most recent tick.
+    pub fn births_last_tick(&self) -> u32 {
+        self.last_tick.births
+    }
+
+    /// Cells that died in the most recent tick.
+    pub fn deaths_last_tick(&self) -> u32 {
+        self.last_tick.deaths
+    }
+}
@@ -0,0 +1,107 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// Generations whose state hashes `status()` compares against, so the
+/// longest period it recognizes.
+const RECENT_STATES: usize = 64;
+
+/// Whether a universe still does anything interesting, see `status()`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusKind {
+    Running,
+    /// No cell is alive.
+    Extinct,
+    /// The last tick changed nothing.
+    StillLife,
+    /// The state repeats every `Status::period` generations.
+    Oscillating,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Status {
+    pub kind: StatusKind,
+    /// Generations per cycle: 1 for still lifes (and extinction), 0 while
+    /// running.
+    pub period: u32,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Whether the universe has died out or settled into a still life or
+    /// an oscillator with a period of up to 64, from the state hashes of
+    /// the recent generations, so the animation loop can pause once
+    /// nothing interesting happens anymore.
+    ///
+    /// Cheap enough to call every frame: ticks only record their hash.
+    pub fn status(&self) -> Status {
+        if self.population == 0 {
+            return Status {
+                kind: StatusKind::Extinct,
+                period: 1,
+            };
+        }
+        let repeat = self
+            .recent_states
+            .iter()
+            .rev()
+            .find(|&&(_, hash)| hash == self.zobrist);
+        match repeat {
+            Some(&(generation, _)) => {
+                let period = self.generation - generation;
+                Status {
+                    kind: if period == 1 {
+                        StatusKind::StillLife
+                    } else {
+                        StatusKind::Oscillating
+                    },
+                    period,
+                }
+            }
+            None => Status {
+                kind: StatusKind::Running,
+                period: 0,
+            },
+        }
+    }
+
+    /// Tick up to `generations` times in one call, e.g. for a fast-forward
+    /// button, and return how many ticks ran. Stops early once `status()`
+    /// is no longer running, i.e. the universe died out or settled into a
+    /// still life or an oscillator, and when cancelled.
+    ///
+    /// Runs at most `Limits::max_generations_per_call` ticks instead of
+    /// failing.
+    pub fn run(&mut self, generations: u32) -> u32 {
+        let generations = generations.min(self.limits.max_generations_per_call);
+        let mut done = 0;
+        while done < generations && self.status().kind == StatusKind::Running {
+            if self.progress_step(done, generations).is_err() {
+                break;
+            }
+            self.tick();
+            done += 1;
+        }
+        self.report_progress(done, done);
+        done
+    }
+}
+
+impl Universe {
+    /// Called by `tick()` before the current generation is replaced.
+    pub(crate) fn record_state(&mut self) {
+        if self.recent_states.len() == RECENT_STATES {
+            self.recent_states.pop_front();
+        }
+        self.recent_states
+            .push_back((self.generation, self.zobrist));
+    }
+
+    /// Forget the recent states after an edit, which breaks the chain of
+    /// ticks between them.
+    pub(crate) fn forget_states(&mut self) {
+        self.recent_states.clear();
+    }
+}
@@ -0,0 +1,105 @@
+//! `Engine::Naive`: every cell looked up in a 512 entry table by the 9 bit
+//! pattern of its 3 x 3 block (see `isotropic`), so rule families only
+//! need to say what each pattern becomes.
+
+use fixedbitset::FixedBitSet;
+
+use crate::isotropic::{Transitions, CENTRE};
+use crate::{Neighborhood, Rule, Universe};
+
+/// The middle and right columns of a pattern, which become the left and
+/// middle ones one cell east.
+const KEPT_COLUMNS: u16 = 0b011_011_011;
+
+/// Transition tables for one rule and neighbourhood, kept until either
+/// changes.
+#[derive(Clone, Debug)]
+pub(crate) struct TransitionTables {
+    rule: Rule,
+    neighborhood: Neighborhood,
+    /// For cells in even and in odd rows, which only differ for the
+    /// hexagonal neighbourhood
+    rows: [Transitions; 2],
+}
+
+impl TransitionTables {
+    fn new(rule: &Rule, neighborhood: Neighborhood) -> TransitionTables {
+        let table = |row| match rule.transitions() {
+            // non-totalistic tables already cover the whole block
+            Some(transitions) => transitions,
+            None => {
+                let counted = neighborhood
+                    .offsets(row)
+                    .iter()
+                    .fold(0, |mask, &(dr, dc)| mask | 1 << ((dr + 1) * 3 + dc + 1));
+                Transitions::from_fn(|pattern| {
+                    rule.next(
+                        pattern & CENTRE != 0,
+                        (pattern & counted).count_ones() as u8,
+                    )
+                })
+            }
+        };
+        TransitionTables {
+            rule: *rule,
+            neighborhood,
+            rows: [table(0), table(1)],
+        }
+    }
+
+    fn matches(&self, rule: &Rule, neighborhood: Neighborhood) -> bool {
+        self.rule == *rule && self.neighborhood == neighborhood
+    }
+}
+
+impl Universe {
+    /// Reference engine, and the only one for neighbourhoods other than
+    /// Moore's and non-totalistic rules.
+    ///
+    /// Inside the universe each cell's pattern is the previous one shifted
+    /// a column west plus the three cells of the new east column; cells on
+    /// the edges gather their blocks through `resolve()`.
+    pub(crate) fn step_table(&mut self, next: &mut FixedBitSet) {
+        let current = self.transitions.as_ref();
+        if !current.is_some_and(|tables| tables.matches(&self.rule, self.neighborhood)) {
+            self.transitions = Some(TransitionTables::new(&self.rule, self.neighborhood));
+        }
+        let tables = self.transitions.as_ref().expect("built above");
+        next.clone_from(&self.cells);
+
+        for row in 0..self.height {
+            let table = &tables.rows[row as usize % 2];
+            let interior = row > 0 && row + 1 < self.height;
+            let mut pattern = 0;
+            for col in 0..self.width {
+                pattern = if interior && col > 1 && col + 1 < self.width {
+                    (pattern >> 1) & KEPT_COLUMNS | self.column_bits(row, col + 1) << 2
+                } else {
+                    self.block(row, col)
+                };
+                next.set(self.get_index(row, col), table.get(pattern));
+            }
+        }
+    }
+
+    /// The pattern of the 3 x 3 block around a cell, with whatever the
+    /// topology puts beyond the edges.
+    fn block(&self, row: u32, col: u32) -> u16 {
+        let mut pattern = 0;
+        for bit in 0..9 {
+            let (r, c) = (row as i64 + bit / 3 - 1, col as i64 + bit % 3 - 1);
+            if let Some((r, c)) = self.resolve(r, c) {
+                pattern |= (self.cells[self.get_index(r, c)] as u16) << bit;
+            }
+        }
+        pattern
+    }
+
+    /// The cells of `col` in the rows around an interior `row`, in the
+    /// pattern bits of a block's west column.
+    fn column_bits(&self, row: u32, col: u32) -> u16 {
+        self.cells[self.get_index(row - 1, col)] as u16
+            | (self.cells[self.get_index(row, col)] as u16) << 3
+            | (self.cells[self.get_index(row + 1, col)] as u16) << 6
+    }
+}
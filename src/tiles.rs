@@ -0,0 +1,164 @@
+use fixedbitset::{Block, FixedBitSet};
+
+use crate::simulate::{bit, count, scatter, tail_mask, RuleMasks, BITS};
+use crate::{hibernate, Rule, StorageOrder, Topology, Universe};
+
+/// Storage lines per tile; a tile is one word wide.
+const TILE_LINES: usize = 32;
+
+/// What `Engine::ActiveTiles` remembers between ticks: the generation it
+/// computed last, gathered into lines like `gather_lines()` does, and
+/// which tiles changed on the way there.
+#[derive(Clone, Debug)]
+pub(crate) struct ActiveTiles {
+    grid: Vec<Block>,
+    /// One bit per tile, band by band (`TILE_LINES` lines each), one tile
+    /// per word of a line.
+    active: FixedBitSet,
+    rule: Rule,
+    topology: Topology,
+    order: StorageOrder,
+    lines: usize,
+    len: usize,
+}
+
+impl ActiveTiles {
+    pub(crate) fn byte_len(&self) -> usize {
+        std::mem::size_of_val(&self.grid[..]) + hibernate::bitset_byte_len(&self.active)
+    }
+}
+
+impl Universe {
+    /// The next generation, re-evaluating only the tiles that may change.
+    ///
+    /// A tile whose neighbourhood of 3 x 3 tiles did not change in the last
+    /// generation, and was not edited since, evaluates to what it already
+    /// is. Edits of any kind are found by comparing the cells against the
+    /// generation remembered from the last tick, and a different rule,
+    /// topology or layout starts the tracking over.
+    pub(crate) fn step_active_tiles(&mut self, next: &mut FixedBitSet) {
+        let (lines, len) = self.lines();
+        let torus = match self.topology {
+            Topology::Torus => true,
+            Topology::Dead => false,
+            Topology::Mirror | Topology::KleinBottle => {
+                self.active_tiles = None;
+                return self.step_bit_parallel(next);
+            }
+        };
+        next.clone_from(&self.cells);
+        next.clear();
+        if lines == 0 || len == 0 {
+            return;
+        }
+        let stride = len.div_ceil(BITS);
+        let bands = lines.div_ceil(TILE_LINES);
+        let grid = self.gather_lines();
+
+        let tracked = self.active_tiles.take().filter(|tiles| {
+            (
+                tiles.rule,
+                tiles.topology,
+                tiles.order,
+                tiles.lines,
+                tiles.len,
+            ) == (self.rule, self.topology, self.order, lines, len)
+        });
+        let mut active = FixedBitSet::with_capacity(bands * stride);
+        match &tracked {
+            Some(tiles) => {
+                active.union_with(&tiles.active);
+                // edits since the last tick
+                for (i, (&old, &new)) in tiles.grid.iter().zip(&grid).enumerate() {
+                    if old != new {
+                        active.insert(i / stride / TILE_LINES * stride + i % stride);
+                    }
+                }
+            }
+            None => active.insert_range(..),
+        }
+
+        // the active tiles and their neighbours, wrapping around (which at
+        // worst evaluates a few settled tiles on bounded universes)
+        let mut evaluate = FixedBitSet::with_capacity(bands * stride);
+        for tile in active.ones() {
+            let (band, word) = (tile / stride, tile % stride);
+            for &db in [bands - 1, 0, 1].iter() {
+                for &dw in [stride - 1, 0, 1].iter() {
+                    evaluate.insert((band + db) % bands * stride + (word + dw) % stride);
+                }
+            }
+        }
+
+        let masks = RuleMasks::new(&self.rule);
+        let zero = vec![0; stride];
+        let line_at = |line: usize| &grid[line * stride..][..stride];
+        let mut out = grid.clone();
+        let mut changed = FixedBitSet::with_capacity(bands * stride);
+        for tile in evaluate.ones() {
+            let (band, word) = (tile / stride, tile % stride);
+            let band_lines = band * TILE_LINES..((band + 1) * TILE_LINES).min(lines);
+            for line in band_lines {
+                let above = match line {
+                    0 if torus => line_at(lines - 1),
+                    0 => &zero[..],
+                    _ => line_at(line - 1),
+                };
+                let below = match line + 1 {
+                    next if next < lines => line_at(next),
+                    _ if torus => line_at(0),
+                    _ => &zero[..],
+                };
+                let here = line_at(line);
+                let (w0, e0) = neighbour_words(above, word, len, torus);
+                let (w1, e1) = neighbour_words(here, word, len, torus);
+                let (w2, e2) = neighbour_words(below, word, len, torus);
+                let neighbours = [above[word], below[word], w0, w1, w2, e0, e1, e2];
+                let mut value = masks.apply(here[word], count(&neighbours));
+                if word == stride - 1 {
+                    value &= tail_mask(len);
+                }
+                if value != here[word] {
+                    out[line * stride + word] = value;
+                    changed.insert(tile);
+                }
+            }
+        }
+
+        let next_words = next.as_mut_slice();
+        for (line, words) in out.chunks(stride).enumerate() {
+            scatter(words, line * len, len, next_words);
+        }
+        self.active_tiles = Some(ActiveTiles {
+            grid: out,
+            active: changed,
+            rule: self.rule,
+            topology: self.topology,
+            order: self.order,
+            lines,
+            len,
+        });
+    }
+}
+
+/// The west and east neighbours of the cells in word `word` of `line`,
+/// like `shift_west()` and `shift_east()` compute them for a whole line.
+fn neighbour_words(line: &[Block], word: usize, len: usize, torus: bool) -> (Block, Block) {
+    let last = line.len() - 1;
+    let before = match word {
+        0 if torus => bit(line, len - 1),
+        0 => 0,
+        _ => line[word - 1] >> (BITS - 1),
+    };
+    let mut west = (line[word] << 1) | before;
+    let mut east = line[word] >> 1;
+    if word < last {
+        east |= line[word + 1] << (BITS - 1);
+    } else {
+        west &= tail_mask(len);
+        if torus {
+            east |= bit(line, 0) << ((len - 1) % BITS);
+        }
+    }
+    (west, east)
+}
@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::utils::now_ms;
+
+thread_local! {
+    static TIMINGS: RefCell<BTreeMap<String, TimingSummary>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Aggregated durations of one named phase, in milliseconds.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TimingSummary {
+    pub count: u32,
+    pub total_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+}
+
+#[wasm_bindgen]
+impl TimingSummary {
+    pub fn mean_ms(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total_ms / self.count as f64
+        }
+    }
+
+    fn record(&mut self, ms: f64) {
+        self.min_ms = if self.count == 0 {
+            ms
+        } else {
+            self.min_ms.min(ms)
+        };
+        self.max_ms = self.max_ms.max(ms);
+        self.total_ms += ms;
+        self.last_ms = ms;
+        self.count += 1;
+    }
+}
+
+/// A phase measured from JavaScript, e.g. rendering a frame, so it shows up
+/// next to the Rust phases in DevTools traces and in `timing_summary()`.
+#[wasm_bindgen]
+pub struct PhaseTimer {
+    name: String,
+    start: f64,
+}
+
+#[wasm_bindgen]
+impl PhaseTimer {
+    pub fn start(name: &str) -> PhaseTimer {
+        PhaseTimer {
+            start: begin(name),
+            name: name.to_owned(),
+        }
+    }
+
+    /// Finish the phase and return its duration in milliseconds.
+    pub fn end(self) -> f64 {
+        end(&self.name, self.start)
+    }
+}
+
+/// Aggregated durations recorded under `name`, if any.
+#[wasm_bindgen]
+pub fn timing_summary(name: &str) -> Option<TimingSummary> {
+    TIMINGS.with(|timings| timings.borrow().get(name).copied())
+}
+
+/// Names of all phases recorded so far, sorted.
+#[wasm_bindgen]
+pub fn timing_names() -> Vec<String> {
+    TIMINGS.with(|timings| timings.borrow().keys().cloned().collect())
+}
+
+#[wasm_bindgen]
+pub fn reset_timings() {
+    TIMINGS.with(|timings| timings.borrow_mut().clear());
+}
+
+/// Start measuring `name`: sets the `<name>:start` performance mark and
+/// returns the start time for `end()`.
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub(crate) fn begin(name: &str) -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    if let Some(performance) = performance() {
+        let _ = performance.mark(&format!("{}:start", name));
+    }
+    now_ms()
+}
+
+/// Finish measuring `name`: adds a `name` performance measure spanning the
+/// marks and aggregates the duration. Returns the duration.
+pub(crate) fn end(name: &str, start: f64) -> f64 {
+    let ms = now_ms() - start;
+    #[cfg(target_arch = "wasm32")]
+    if let Some(performance) = performance() {
+        let start_mark = format!("{}:start", name);
+        let end_mark = format!("{}:end", name);
+        let _ = performance.mark(&end_mark);
+        let _ = performance.measure_with_start_mark_and_end_mark(name, &start_mark, &end_mark);
+        // keep the buffer from growing by one entry per tick; the measure
+        // already records the span
+        performance.clear_marks_with_mark_name(&start_mark);
+        performance.clear_marks_with_mark_name(&end_mark);
+    }
+    TIMINGS.with(|timings| {
+        timings
+            .borrow_mut()
+            .entry(name.to_owned())
+            .or_default()
+            .record(ms)
+    });
+    ms
+}
+
+/// `performance` of the current global (window or worker), if it has one.
+#[cfg(target_arch = "wasm32")]
+fn performance() -> Option<web_sys::Performance> {
+    use wasm_bindgen::JsCast;
+
+    js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+        .ok()?
+        .dyn_into()
+        .ok()
+}
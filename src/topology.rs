@@ -0,0 +1,104 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{LogLevel, StorageOrder, Universe};
+
+/// What lies beyond the edges of a universe.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// Opposite edges are joined: patterns leaving one side come back on
+    /// the other.
+    #[default]
+    Torus,
+    /// Everything outside is dead, so patterns leaving the grid disappear,
+    /// as in bounded simulators.
+    Dead,
+    /// Outside cells mirror the cells just inside each edge.
+    Mirror,
+    /// The west and east edges are joined as on a torus; crossing the north
+    /// or south edge also flips the pattern left to right.
+    KleinBottle,
+}
+
+impl Topology {
+    pub fn name(self) -> &'static str {
+        match self {
+            Topology::Torus => "torus",
+            Topology::Dead => "dead",
+            Topology::Mirror => "mirror",
+            Topology::KleinBottle => "klein-bottle",
+        }
+    }
+}
+
+/// How one edge of a line of storage behaves, see `Universe::edges()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Edge {
+    Wrap,
+    Dead,
+    Mirror,
+    /// Wrap, and continue on the line mirrored across the other axis.
+    Flip,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        log_at!(
+            LogLevel::Info,
+            "topology",
+            "{} -> {}",
+            self.topology.name(),
+            topology.name()
+        );
+        self.topology = topology;
+        self.reset_checkpoints();
+        self.forget_states();
+    }
+}
+
+impl Universe {
+    /// The cell found at `(row, column)`, which may lie one cell beyond an
+    /// edge, or `None` when that is outside a bounded universe.
+    pub(crate) fn resolve(&self, row: i64, column: i64) -> Option<(u32, u32)> {
+        let (height, width) = (self.height as i64, self.width as i64);
+        let (row, column) = match self.topology {
+            Topology::Torus => (row.rem_euclid(height), column.rem_euclid(width)),
+            Topology::Dead => {
+                if row < 0 || row >= height || column < 0 || column >= width {
+                    return None;
+                }
+                (row, column)
+            }
+            Topology::Mirror => (row.clamp(0, height - 1), column.clamp(0, width - 1)),
+            Topology::KleinBottle => {
+                let column = if row < 0 || row >= height {
+                    width - 1 - column
+                } else {
+                    column
+                };
+                (row.rem_euclid(height), column.rem_euclid(width))
+            }
+        };
+        Some((row as u32, column as u32))
+    }
+
+    /// Behaviour of the ends of each storage line, and of the lines before
+    /// the first and after the last.
+    pub(crate) fn edges(&self) -> (Edge, Edge) {
+        let (west_east, north_south) = match self.topology {
+            Topology::Torus => (Edge::Wrap, Edge::Wrap),
+            Topology::Dead => (Edge::Dead, Edge::Dead),
+            Topology::Mirror => (Edge::Mirror, Edge::Mirror),
+            Topology::KleinBottle => (Edge::Wrap, Edge::Flip),
+        };
+        match self.order {
+            StorageOrder::RowMajor => (west_east, north_south),
+            StorageOrder::ColumnMajor => (north_south, west_east),
+        }
+    }
+}
@@ -1,5 +1,3 @@
-use web_sys::console;
-
 #[allow(dead_code)]
 pub fn set_panic_hook() {
     // When the `console_error_panic_hook` feature is enabled, we can call the
@@ -20,19 +18,62 @@ macro_rules! log {
     };
 }
 
+/// Scoped measurement of a phase. On wasm32 it shows up as a
+/// `console.time` line and as a `performance.measure` entry in DevTools
+/// traces; on every target the duration is aggregated for
+/// `timing_summary()`.
+///
+/// Compiles to nothing without the `timing` feature.
 pub struct Timer<'a> {
+    #[cfg(feature = "timing")]
     name: &'a str,
+    #[cfg(feature = "timing")]
+    start: f64,
+    #[cfg(not(feature = "timing"))]
+    name: std::marker::PhantomData<&'a str>,
 }
 
 impl<'a> Timer<'a> {
+    #[cfg(feature = "timing")]
     pub fn new(name: &'a str) -> Timer<'a> {
-        console::time_with_label(&name);
-        Timer { name }
+        #[cfg(target_arch = "wasm32")]
+        web_sys::console::time_with_label(name);
+        Timer {
+            name,
+            start: crate::timing::begin(name),
+        }
+    }
+
+    #[cfg(not(feature = "timing"))]
+    pub fn new(_name: &'a str) -> Timer<'a> {
+        Timer {
+            name: std::marker::PhantomData,
+        }
     }
 }
 
+#[cfg(feature = "timing")]
 impl<'a> Drop for Timer<'a> {
     fn drop(&mut self) {
-        console::time_end_with_label(&self.name);
+        crate::timing::end(self.name, self.start);
+        #[cfg(target_arch = "wasm32")]
+        web_sys::console::time_end_with_label(self.name);
+    }
+}
+
+/// Milliseconds from an arbitrary origin, for measuring durations on wasm
+/// (where `std::time::Instant` is unavailable) and natively alike.
+pub fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        ORIGIN.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
     }
 }
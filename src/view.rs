@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{packed, Universe};
+
+/// Where `cells()` points, so JavaScript can build a `Uint8Array` over
+/// wasm memory without knowing the word size: `byte_len` bytes from
+/// `ptr`, byte `i` holding cells `8 * i .. 8 * i + 8` in storage order,
+/// the lowest cell index in the least significant bit.
+///
+/// A view goes stale when the memory grows, detaching its buffer, or the
+/// universe reallocates its cells (e.g. on a resize). Compare
+/// `memory_generation` with `memory_generation()` and `ptr` with a fresh
+/// view before reading.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellsView {
+    ptr: usize,
+    byte_len: usize,
+    memory_generation: u32,
+}
+
+#[wasm_bindgen]
+impl CellsView {
+    pub fn ptr(&self) -> usize {
+        self.ptr
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.byte_len
+    }
+
+    /// `memory_generation()` when the view was taken.
+    pub fn memory_generation(&self) -> u32 {
+        self.memory_generation
+    }
+}
+
+static MEMORY_PAGES: AtomicUsize = AtomicUsize::new(0);
+static MEMORY_GROWTHS: AtomicU32 = AtomicU32::new(0);
+
+/// A counter that moves on whenever the wasm memory has grown since the
+/// previous call, i.e. whenever views over the old buffer are detached.
+/// Constant outside wasm.
+#[wasm_bindgen]
+pub fn memory_generation() -> u32 {
+    let pages = memory_pages();
+    if MEMORY_PAGES.swap(pages, Ordering::Relaxed) != pages {
+        MEMORY_GROWTHS.fetch_add(1, Ordering::Relaxed);
+    }
+    MEMORY_GROWTHS.load(Ordering::Relaxed)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn memory_pages() -> usize {
+    core::arch::wasm32::memory_size::<0>()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn memory_pages() -> usize {
+    0
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A copy of the cells in the layout of `cells()`, a `Uint8Array` in
+    /// JavaScript that stays valid whatever happens to wasm memory.
+    pub fn cells_u8(&self) -> Vec<u8> {
+        packed::pack(&self.current_cells())
+    }
+
+    /// `cells()` with its length and the memory generation, see
+    /// `CellsView`.
+    pub fn cells_view(&self) -> CellsView {
+        // before taking the pointer, so a growth in between shows
+        let memory_generation = memory_generation();
+        CellsView {
+            ptr: self.cells() as usize,
+            byte_len: self.cells_byte_len(),
+            memory_generation,
+        }
+    }
+}
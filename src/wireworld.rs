@@ -0,0 +1,142 @@
+use wasm_bindgen::prelude::*;
+
+use crate::byte_grid::ByteGrid;
+use crate::Error;
+
+/// The state of a Wireworld cell; `Wireworld::cells()` holds these as
+/// bytes.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum WireState {
+    #[default]
+    Empty = 0,
+    /// Wire the electrons travel along.
+    Conductor = 1,
+    ElectronHead = 2,
+    /// Where an electron just was, keeping it from turning back.
+    ElectronTail = 3,
+}
+
+impl WireState {
+    fn from_byte(byte: u8) -> WireState {
+        match byte {
+            1 => WireState::Conductor,
+            2 => WireState::ElectronHead,
+            3 => WireState::ElectronTail,
+            _ => WireState::Empty,
+        }
+    }
+
+    /// The next state with `heads` electron heads among the neighbours.
+    fn next(self, heads: u8) -> WireState {
+        match self {
+            WireState::Empty => WireState::Empty,
+            WireState::ElectronHead => WireState::ElectronTail,
+            WireState::ElectronTail => WireState::Conductor,
+            WireState::Conductor if heads == 1 || heads == 2 => WireState::ElectronHead,
+            WireState::Conductor => WireState::Conductor,
+        }
+    }
+}
+
+/// A Wireworld grid, for drawing logic circuits: electrons (a head
+/// followed by a tail) travel along conductors, and a conductor next to
+/// one or two heads becomes a head itself. Everything outside the grid is
+/// empty.
+#[wasm_bindgen]
+pub struct Wireworld {
+    grid: ByteGrid,
+    generation: u32,
+}
+
+#[wasm_bindgen]
+impl Wireworld {
+    /// An empty grid. Fails with `Error::DimensionsTooLarge` when it
+    /// would have more cells than it can address.
+    pub fn new(height: u32, width: u32) -> Result<Wireworld, Error> {
+        Ok(Wireworld {
+            grid: ByteGrid::new(height, width)?,
+            generation: 0,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.grid.height
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> Result<WireState, Error> {
+        let idx = self.grid.index(row, column)?;
+        Ok(WireState::from_byte(self.grid.cells[idx]))
+    }
+
+    pub fn set_cell(&mut self, row: u32, column: u32, state: WireState) -> Result<(), Error> {
+        let idx = self.grid.index(row, column)?;
+        self.grid.cells[idx] = state as u8;
+        Ok(())
+    }
+
+    /// Pointer to the state of every cell, a `WireState` byte each in
+    /// row-major order. Read `cells_len()` bytes from it.
+    pub fn cells(&self) -> *const u8 {
+        self.grid.cells.as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.grid.cells.len()
+    }
+
+    /// Number of electron heads, i.e. electrons.
+    pub fn electron_count(&self) -> u32 {
+        let head = WireState::ElectronHead as u8;
+        self.grid.cells.iter().filter(|&&byte| byte == head).count() as u32
+    }
+
+    /// Turn every electron back into conductor, keeping the circuit.
+    pub fn clear_electrons(&mut self) {
+        for byte in self.grid.cells.iter_mut() {
+            if *byte != WireState::Empty as u8 {
+                *byte = WireState::Conductor as u8;
+            }
+        }
+    }
+
+    /// Empty every cell, circuit included.
+    pub fn reset_cells(&mut self) {
+        self.grid.cells.iter_mut().for_each(|byte| *byte = 0);
+        self.generation = 0;
+    }
+
+    pub fn tick(&mut self) {
+        let head = WireState::ElectronHead as u8;
+        self.grid.step(
+            false,
+            |byte| byte == head,
+            |byte, heads| WireState::from_byte(byte).next(heads) as u8,
+        );
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+}
+
+impl Wireworld {
+    /// Set each listed `(row, column)` cell to `state`, e.g. to lay a wire.
+    ///
+    /// Fails without changing anything when a cell is outside the grid.
+    pub fn set_cells(&mut self, cells: &[(u32, u32)], state: WireState) -> Result<(), Error> {
+        self.grid.set_cells(cells, state as u8)
+    }
+}
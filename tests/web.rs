@@ -7,7 +7,35 @@ use std::assert_eq;
 
 use wasm_bindgen_test::*;
 
-use wasm_game_of_life::Universe;
+#[cfg(feature = "elementary")]
+use wasm_game_of_life::Elementary;
+#[cfg(feature = "hashlife")]
+use wasm_game_of_life::HashLifeUniverse;
+#[cfg(feature = "render")]
+use wasm_game_of_life::Palette;
+#[cfg(feature = "sparse")]
+use wasm_game_of_life::SparseUniverse;
+#[cfg(feature = "analysis")]
+use wasm_game_of_life::{
+    analyze_rule, compare_engines, compare_rules, run_ensemble, run_ensemble_shard,
+    ComparisonConfig, EnsembleConfig, Fitness, GeneticSearch, GrowthClass, SearchConfig,
+    StopCondition, StopReason,
+};
+#[cfg(feature = "rules")]
+use wasm_game_of_life::{complement_rule, Neighborhood};
+use wasm_game_of_life::{
+    expand_to_metapixels, simd_enabled, Anchor, BoundaryTraffic, BrushShape, Engine, Entropy,
+    EntropySource, Error, EventKind, MergeOp, Pattern, Placement, PlacementPolicy, Rotation, Rule,
+    Status, StatusKind, StorageOrder, Topology, Transform, Universe, EVENT_RECORD_WORDS,
+};
+#[cfg(feature = "langton")]
+use wasm_game_of_life::{Direction, Langton};
+#[cfg(feature = "generations")]
+use wasm_game_of_life::{GenerationsRule, GenerationsUniverse};
+#[cfg(feature = "ltl")]
+use wasm_game_of_life::{LargerThanLifeUniverse, LtlRule};
+#[cfg(feature = "wireworld")]
+use wasm_game_of_life::{WireState, Wireworld};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -16,7 +44,9 @@ pub fn input_spaceship() -> Universe {
     let height = 6;
     let width = 6;
     let mut universe = Universe::new(height, width);
-    universe.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+    universe
+        .set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)])
+        .unwrap();
     universe
 }
 
@@ -25,7 +55,9 @@ pub fn expected_spaceship() -> Universe {
     let height = 6;
     let width = 6;
     let mut universe = Universe::new(height, width);
-    universe.set_cells(&[(2, 1), (2, 3), (3, 2), (3, 3), (4, 2)]);
+    universe
+        .set_cells(&[(2, 1), (2, 3), (3, 2), (3, 3), (4, 2)])
+        .unwrap();
     universe
 }
 
@@ -37,3 +69,3622 @@ pub fn test_tick() {
     input_universe.tick();
     assert_eq!(expected_universe.get_cells(), input_universe.get_cells());
 }
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_run_until_period_detected() {
+    let mut universe = Universe::new(5, 5);
+    // blinker
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+
+    let report = universe
+        .run_until(&StopCondition::period_detected(), 10)
+        .unwrap();
+    assert_eq!(report.reason, StopReason::PeriodDetected);
+    assert_eq!(report.generations, 2);
+    assert_eq!(report.period, 2);
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_run_until_max_generations() {
+    let mut universe = input_spaceship();
+
+    let report = universe.run_until(&StopCondition::extinct(), 3).unwrap();
+    assert_eq!(report.reason, StopReason::MaxGenerations);
+    assert_eq!(report.generations, 3);
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_run_until_hibernated() {
+    // a blinker touching the top edge
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(0, 1), (0, 2), (0, 3)]).unwrap();
+    universe.hibernate();
+    let report = universe.run_until(&StopCondition::extinct(), 10).unwrap();
+    assert_eq!(report.reason, StopReason::MaxGenerations);
+    assert_eq!(report.generations, 10);
+
+    universe.hibernate();
+    let edge = StopCondition::bounding_box_touches_edge();
+    assert_eq!(universe.run_until(&edge, 10).unwrap().generations, 0);
+    universe.hibernate();
+    let crowded = StopCondition::population_above(2);
+    assert_eq!(universe.run_until(&crowded, 10).unwrap().generations, 0);
+    universe.hibernate();
+    assert_eq!(universe.run_until_stable(10).unwrap().population, 3);
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_run_until_stable() {
+    let mut universe = Universe::new(8, 8);
+    // a "pre-block" that settles into a block after one tick
+    universe.set_cells(&[(3, 3), (3, 4), (4, 3)]).unwrap();
+
+    let report = universe.run_until_stable(10).unwrap();
+    assert!(report.stabilized);
+    assert_eq!(report.period, 1);
+    assert_eq!(report.stabilized_at, 1);
+    assert_eq!(report.population, 4);
+    #[cfg(feature = "formats")]
+    {
+        let census = report.census();
+        assert_eq!(census.names(), vec!["block"]);
+        assert_eq!(census.count("block"), 1);
+    }
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_run_until_respects_generation_limit() {
+    let mut universe = input_spaceship();
+    let mut limits = universe.limits();
+    limits.max_generations_per_call = 5;
+    universe.set_limits(limits);
+
+    let err = universe
+        .run_until(&StopCondition::extinct(), 6)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Error::GenerationLimit {
+            requested: 6,
+            limit: 5
+        }
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_hook() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut universe = input_spaceship();
+    let record = Rc::clone(&seen);
+    universe.set_tick_hook(Box::new(move |universe| {
+        let stats = universe.last_tick();
+        record
+            .borrow_mut()
+            .push((universe.generation(), stats.population));
+    }));
+    universe.tick();
+    universe.tick_with_stats();
+    assert_eq!(*seen.borrow(), vec![(1, 5), (2, 5)]);
+
+    universe.clear_tick_hook();
+    universe.tick();
+    assert_eq!(seen.borrow().len(), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_cancel_long_operations() {
+    use wasm_game_of_life::CancelToken;
+
+    let token = CancelToken::new();
+    let mut universe = input_spaceship();
+    universe.set_cancel_token(&token);
+    universe.tick_many(4).unwrap();
+    assert_eq!(universe.generation(), 4);
+
+    // cancelled from inside the loop, as a worker sharing memory would
+    let canceller = token.clone();
+    universe.set_tick_hook(Box::new(move |universe| {
+        if universe.generation() == 7 {
+            canceller.cancel();
+        }
+    }));
+    assert_eq!(
+        universe.tick_many(10),
+        Err(Error::Cancelled { generations: 3 })
+    );
+    assert_eq!(universe.generation(), 7);
+    assert!(token.is_cancelled());
+    assert_eq!(
+        universe.goto_generation(20),
+        Err(Error::Cancelled { generations: 0 })
+    );
+
+    token.reset();
+    universe.clear_tick_hook();
+    universe.goto_generation(20).unwrap();
+    universe.clear_cancel_token();
+    token.cancel();
+    universe.tick_many(2).unwrap();
+}
+
+#[wasm_bindgen_test]
+pub fn test_progress_reporting() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let sink = Rc::clone(&reports);
+    let mut universe = input_spaceship();
+    universe.set_progress_hook(
+        Box::new(move |done, total| sink.borrow_mut().push((done, total))),
+        4,
+    );
+    universe.tick_many(10).unwrap();
+    assert_eq!(*reports.borrow(), vec![(0, 10), (4, 10), (8, 10), (10, 10)]);
+    let progress = universe.progress();
+    assert_eq!((progress.done, progress.total), (10, 10));
+    assert_eq!(progress.fraction(), 1.0);
+
+    reports.borrow_mut().clear();
+    universe.set_checkpoint_interval(5);
+    universe.goto_generation(13).unwrap();
+    assert_eq!(*reports.borrow(), vec![(0, 3), (3, 3)]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_universe_manager() {
+    use wasm_game_of_life::UniverseManager;
+
+    let mut manager = UniverseManager::new();
+    let a = manager.create(6, 6).unwrap();
+    let b = manager.insert(input_spaceship());
+    assert_eq!(manager.ids(), vec![a, b]);
+    assert_eq!(
+        manager.create(1 << 16, 1 << 16),
+        Err(Error::DimensionsTooLarge {
+            width: 1 << 16,
+            height: 1 << 16
+        })
+    );
+    assert_eq!(manager.len(), 2);
+
+    let glider = manager.add_pattern(&Pattern::from_plaintext(".O.\n..O\nOOO\n").unwrap());
+    manager
+        .place(a, glider, 1, 1, PlacementPolicy::Error)
+        .unwrap();
+    assert_eq!(
+        manager.universe(a).unwrap().get_cells(),
+        manager.universe(b).unwrap().get_cells()
+    );
+
+    manager.tick(&[a]).unwrap();
+    assert_eq!(manager.universe(a).unwrap().generation(), 1);
+    assert_eq!(manager.universe(b).unwrap().generation(), 0);
+    let unknown = Error::UnknownHandle {
+        kind: "universe",
+        id: 7,
+    };
+    assert_eq!(manager.tick(&[b, 7]), Err(unknown));
+    assert_eq!(manager.universe(b).unwrap().generation(), 0);
+    manager.tick_all();
+
+    let universe = manager.take(b).unwrap();
+    assert_eq!(universe.generation(), 1);
+    assert!(!manager.contains(b));
+    assert_eq!(manager.len(), 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_series() {
+    // a glider and a lone cell that dies straight away
+    let mut universe = input_spaceship();
+    universe.toggle_cell(0, 0).unwrap();
+    for _ in 0..4 {
+        universe.tick();
+    }
+    assert_eq!((universe.series_start(), universe.series_len()), (0, 5));
+    assert_eq!(universe.population_series(1, 5), vec![5.0; 4]);
+    assert_eq!(universe.births_series(0, 2), vec![0.0, 2.0]);
+    assert_eq!(universe.deaths_series(0, 2), vec![0.0, 3.0]);
+    // windows are clipped to the recorded generations
+    assert_eq!(universe.population_series(3, 100).len(), 2);
+    assert!(universe.population_series(7, 9).is_empty());
+
+    // going back in time rewrites the series from there on
+    universe.set_checkpoint_interval(2);
+    universe.checkpoint();
+    universe.tick();
+    universe.goto_generation(4).unwrap();
+    universe.tick();
+    assert_eq!(universe.series_len(), 6);
+
+    universe.reset_cells();
+    assert_eq!(universe.population_series(0, 10), vec![0.0]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_history() {
+    // a glider and a blinker
+    let mut universe = Universe::new(12, 12);
+    universe
+        .set_cells(&[
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (3, 2),
+            (3, 3),
+            (9, 8),
+            (9, 9),
+            (9, 10),
+        ])
+        .unwrap();
+    universe.tick();
+    assert_eq!(universe.population_history()[1..], [8]);
+
+    universe.set_series_capacity(3);
+    for _ in 0..4 {
+        universe.tick();
+    }
+    assert_eq!(universe.population_history(), vec![8, 8, 8]);
+    assert_eq!(universe.series_start(), 3);
+
+    // shrinking drops the oldest generations straight away
+    universe.set_series_capacity(1);
+    assert_eq!(universe.population_history(), vec![8]);
+    assert_eq!(universe.series_start(), 5);
+    universe.reset_cells();
+    assert_eq!(universe.series_capacity(), 1);
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_population_periods() {
+    use wasm_game_of_life::population_periods;
+
+    // an exact period 3, without its multiples
+    let history = [48, 56, 72, 48, 56, 72, 48, 56, 72, 48, 56, 72];
+    let candidates = population_periods(&history, 6);
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].period, 3);
+    assert_eq!(candidates[0].confidence, 1.0);
+
+    // noise lowers the confidence without hiding the period
+    let noisy = [10, 20, 10, 21, 10, 20, 11, 20, 10, 20, 10, 19];
+    let candidates = population_periods(&noisy, 4);
+    assert_eq!(candidates[0].period, 2);
+    assert!(candidates[0].confidence > 0.9 && candidates[0].confidence < 1.0);
+
+    assert_eq!(population_periods(&[5, 5, 5, 5], 2)[0].period, 1);
+    assert!(population_periods(&[], 2).is_empty());
+
+    // beacon: 8 cells, then 6, then 8 again
+    let mut universe = Universe::new(6, 6);
+    universe
+        .set_cells(&[(1, 1), (1, 2), (2, 1), (3, 4), (4, 3), (4, 4)])
+        .unwrap();
+    let candidates = universe.estimate_periods(9, 4).unwrap();
+    assert_eq!((candidates[0].period, candidates[0].confidence), (2, 1.0));
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_with_stats() {
+    let mut universe = input_spaceship();
+
+    let stats = universe.tick_with_stats();
+    assert_eq!(stats.generation, 1);
+    assert_eq!(stats.births, 2);
+    assert_eq!(stats.deaths, 2);
+    assert_eq!(stats.population, 5);
+    assert!(stats.changed);
+}
+
+#[wasm_bindgen_test]
+pub fn test_event_stream() {
+    let mut universe = Universe::new(8, 8);
+    // blinker plus a lone cell that dies straight away
+    universe
+        .set_cells(&[(2, 1), (2, 2), (2, 3), (6, 6)])
+        .unwrap();
+    universe
+        .set_event_mask(1 << EventKind::Birth as u32 | 1 << EventKind::ObjectDisappeared as u32);
+
+    universe.tick();
+    let events = universe.drain_events();
+    let records: Vec<&[u32]> = events.chunks(EVENT_RECORD_WORDS).collect();
+
+    let births: Vec<u32> = records
+        .iter()
+        .filter(|r| r[0] == EventKind::Birth as u32)
+        .map(|r| r[2])
+        .collect();
+    assert_eq!(births, vec![8 + 2, 3 * 8 + 2]);
+    assert!(records
+        .iter()
+        .any(|r| r[0] == EventKind::ObjectDisappeared as u32 && r[2] == 6 * 8 + 6));
+    assert!(records.iter().all(|r| r[1] == 1));
+    assert!(universe.drain_events().is_empty());
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_snapshot_round_trip() {
+    let mut universe = input_spaceship();
+    universe.tick();
+
+    let restored = Universe::from_snapshot_bytes(&universe.to_snapshot_bytes()).unwrap();
+    assert_eq!(restored.width(), 6);
+    assert_eq!(restored.height(), 6);
+    assert_eq!(restored.get_cells(), universe.get_cells());
+    assert_eq!(
+        Universe::from_snapshot_bytes(b"nope").err(),
+        Some(Error::InvalidSnapshot("not a universe snapshot"))
+    );
+
+    universe.set_rulestring("B36/S23").unwrap();
+    let restored = Universe::from_snapshot_bytes(&universe.to_snapshot_bytes()).unwrap();
+    assert_eq!(restored.rule().to_string(), "B36/S23");
+
+    // version 1 snapshots have no rule and mean Life
+    let mut v1 = b"GOLS".to_vec();
+    for word in [1u32, 8, 1, 5] {
+        v1.extend_from_slice(&word.to_le_bytes());
+    }
+    v1.push(0b101);
+    let restored = Universe::from_snapshot_bytes(&v1).unwrap();
+    assert_eq!(restored.rule(), Rule::conway());
+    assert_eq!(restored.generation(), 5);
+    assert_eq!(restored.get_cells().count_ones(..), 2);
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_share_string_round_trip() {
+    let mut universe = Universe::new(40, 30);
+    universe
+        .set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3), (39, 29)])
+        .unwrap();
+    universe.set_rulestring("B36/S23").unwrap();
+
+    let shared = universe.to_share_string();
+    assert!(shared
+        .bytes()
+        .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_'));
+    // a sparse board is far smaller than its packed cells
+    assert!(shared.len() < 40);
+    let restored = Universe::from_share_string(&format!("#{}", shared)).unwrap();
+    assert_eq!((restored.height(), restored.width()), (40, 30));
+    assert_eq!(restored.get_cells(), universe.get_cells());
+    assert_eq!(restored.rule().to_string(), "B36/S23");
+
+    // dense soups fall back to packed cells
+    let soup = Universe::new_seeded(17, 19, 0.5, 3).unwrap();
+    let restored = Universe::from_share_string(&soup.to_share_string()).unwrap();
+    assert_eq!(restored.get_cells(), soup.get_cells());
+
+    for broken in ["", "!!", &shared[..shared.len() - 2]].iter() {
+        assert!(Universe::from_share_string(broken).is_err());
+    }
+
+    // a few characters claiming a 65535 x 65535 board
+    assert_eq!(
+        Universe::from_share_string("Af__A___A4iAMAAA").err(),
+        Some(Error::DimensionsTooLarge {
+            width: 65535,
+            height: 65535
+        })
+    );
+    let mut limits = restored.limits();
+    limits.max_decoded_cells = 1000;
+    assert!(Universe::from_share_string_with_limits(&shared, &limits).is_err());
+    limits.max_decoded_cells = 1200;
+    let restored = Universe::from_share_string_with_limits(&shared, &limits).unwrap();
+    assert_eq!(restored.limits(), limits);
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen_test]
+pub fn test_serde_round_trip() {
+    let mut universe = input_spaceship();
+    universe.set_rulestring("B36/S23").unwrap();
+    universe.tick();
+
+    let json = universe.to_json();
+    assert!(json.starts_with(r#"{"width":6,"height":6,"generation":1,"rule":"B36/S23","cells":["#));
+    let restored = Universe::from_json(&json).unwrap();
+    assert_eq!(restored.get_cells(), universe.get_cells());
+    assert_eq!(restored.generation(), 1);
+    assert_eq!(restored.rule().to_string(), "B36/S23");
+    assert_eq!(
+        Universe::from_json(r#"{"width":6}"#).err(),
+        Some(Error::InvalidSnapshot("not a JSON universe"))
+    );
+
+    let restored = Universe::from_bytes(&universe.to_bytes()).unwrap();
+    assert_eq!(restored.get_cells(), universe.get_cells());
+    assert_eq!(restored.population(), universe.population());
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_buffer_metadata() {
+    let universe = Universe::new(10, 10);
+    let bits = universe.word_bits() as usize;
+
+    assert_eq!(universe.cells_len_words(), 100usize.div_ceil(bits));
+    assert_eq!(universe.cells_byte_len(), 13);
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_byte_layout() {
+    let mut universe = Universe::new(4, 4);
+    universe.set_cells(&[(0, 0), (0, 3), (2, 1)]).unwrap();
+
+    let bytes = unsafe { std::slice::from_raw_parts(universe.cells(), universe.cells_byte_len()) };
+    assert_eq!(bytes, &[0b0000_1001, 0b0000_0010]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_column_major_storage() {
+    let mut row_major = input_spaceship();
+    let mut column_major = input_spaceship();
+    column_major.set_storage_order(StorageOrder::ColumnMajor);
+    assert_eq!(column_major.storage_order(), StorageOrder::ColumnMajor);
+
+    row_major.tick();
+    column_major.tick();
+    column_major.set_storage_order(StorageOrder::RowMajor);
+    assert_eq!(row_major.get_cells(), column_major.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_hibernate_and_wake() {
+    let mut universe = Universe::new(64, 64);
+    universe
+        .set_cells(&[(10, 10), (10, 11), (11, 10), (11, 11)])
+        .unwrap();
+    universe.set_track_occupancy(true);
+    universe.set_track_ages(true);
+    universe.tick();
+    let cells = universe.get_cells().clone();
+    let (occupancy, max_age) = (universe.occupancy(), universe.max_age());
+    let awake = universe.cell_memory_byte_len();
+    assert!(awake > 64 * 64 * 6);
+
+    universe.hibernate();
+    assert!(universe.is_hibernating());
+    assert!(universe.hibernated_byte_len() < 64 * 64 / 8);
+    // nothing per-cell is left besides the compressed state
+    assert_eq!(
+        universe.cell_memory_byte_len(),
+        universe.hibernated_byte_len()
+    );
+    assert_eq!(universe.occupancy(), occupancy);
+    assert_eq!(universe.max_age(), max_age);
+    assert!(universe.is_tracking_ages());
+
+    universe.wake();
+    assert!(!universe.is_hibernating());
+    assert_eq!(universe.get_cells(), &cells);
+    assert_eq!(universe.occupancy(), occupancy);
+    assert_eq!(universe.envelope_population(), 4);
+    universe.tick();
+    assert_eq!(universe.get_cells(), &cells);
+    assert_eq!(universe.max_age(), max_age + 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_goto_generation_from_checkpoints() {
+    let mut reference = input_spaceship();
+    let mut states = vec![reference.get_cells().clone()];
+    for _ in 0..12 {
+        reference.tick();
+        states.push(reference.get_cells().clone());
+    }
+
+    let mut universe = input_spaceship();
+    universe.set_checkpoint_interval(4);
+    universe.checkpoint();
+    universe.goto_generation(12).unwrap();
+    assert_eq!(universe.checkpoint_count(), 4);
+
+    universe.goto_generation(5).unwrap();
+    assert_eq!(universe.get_cells(), &states[5]);
+    universe.goto_generation(1).unwrap();
+    assert_eq!(universe.get_cells(), &states[1]);
+    universe.goto_generation(11).unwrap();
+    assert_eq!(universe.get_cells(), &states[11]);
+
+    // editing rewrites history from the current generation on
+    universe.toggle_cell(0, 0).unwrap();
+    assert_eq!(universe.checkpoint_count(), 3);
+
+    let mut fresh = input_spaceship();
+    fresh.tick();
+    assert_eq!(fresh.goto_generation(0), Err(Error::NoCheckpoint(0)));
+}
+
+#[wasm_bindgen_test]
+pub fn test_changes_since_generation() {
+    let mut universe = Universe::new(6, 6);
+    // blinker, vertical at even generations
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]).unwrap();
+    universe.set_checkpoint_interval(4);
+    universe.checkpoint();
+    for _ in 0..7 {
+        universe.tick();
+    }
+    assert_eq!(universe.changes_since(7).unwrap(), vec![0; 36]);
+    assert_eq!(universe.changes_since(5).unwrap(), vec![0; 36]);
+
+    // horizontal now, so the tips of both phases differ
+    let changed = universe.changes_since(6).unwrap();
+    let changed: Vec<usize> = (0..36).filter(|&idx| changed[idx] == 1).collect();
+    assert_eq!(changed, vec![6 + 2, 2 * 6 + 1, 2 * 6 + 3, 3 * 6 + 2]);
+    // the universe itself is still at generation 7
+    assert!(universe.get_cells()[2 * 6 + 1]);
+    assert_eq!(universe.changes_since(7).unwrap(), vec![0; 36]);
+
+    assert_eq!(universe.changes_since(8), Err(Error::NoCheckpoint(8)));
+
+    // noisy runs draw differently when replayed
+    universe.set_noise(0.5, 0.5, 1).unwrap();
+    universe.checkpoint();
+    universe.tick();
+    assert!(matches!(
+        universe.changes_since(7),
+        Err(Error::InvalidConfig(_))
+    ));
+    assert_eq!(universe.changes_since(8).unwrap(), vec![0; 36]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_envelope() {
+    let mut universe = Universe::new(6, 6);
+    // blinker: both phases together make a plus sign
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+    assert_eq!(universe.envelope_population(), 3);
+    for _ in 0..5 {
+        universe.tick();
+    }
+    let envelope = universe.envelope();
+    let cells: Vec<usize> = (0..36).filter(|&idx| envelope[idx] == 1).collect();
+    assert_eq!(
+        cells,
+        vec![6 + 2, 2 * 6 + 1, 2 * 6 + 2, 2 * 6 + 3, 3 * 6 + 2]
+    );
+
+    // the layout follows the storage order
+    universe.set_storage_order(StorageOrder::ColumnMajor);
+    assert_eq!(universe.envelope_population(), 5);
+    assert_eq!(universe.envelope()[6 + 2], 1);
+    assert_eq!(universe.envelope()[2 * 6 + 1], 1);
+
+    universe.reset_envelope();
+    assert_eq!(universe.envelope_population(), 3);
+    universe.reset_cells();
+    assert_eq!(universe.envelope_population(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_occupancy() {
+    let mut universe = Universe::new(5, 5);
+    // blinker: the centre is always alive, the tips half of the time
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+    assert!(universe.occupancy().is_empty());
+    universe.set_track_occupancy(true);
+    for _ in 0..3 {
+        universe.tick();
+    }
+    assert_eq!(universe.occupancy_generations(), 4);
+    let occupancy = universe.occupancy();
+    assert_eq!(occupancy.len(), 25);
+    assert_eq!(occupancy[2 * 5 + 2], 1.0);
+    assert_eq!(occupancy[2 * 5 + 1], 0.5);
+    assert_eq!(occupancy[5 + 2], 0.5);
+    assert_eq!(occupancy[0], 0.0);
+
+    universe.reset_cells();
+    assert_eq!(universe.occupancy_generations(), 1);
+    universe.set_track_occupancy(false);
+    assert!(!universe.is_tracking_occupancy());
+}
+
+#[wasm_bindgen_test]
+pub fn test_resize_reuses_storage() {
+    let mut universe = input_spaceship();
+    for width in [10, 4, 10, 6] {
+        universe.set_width(width).unwrap();
+        assert_eq!(universe.get_cells().len(), (width * 6) as usize);
+        assert_eq!(universe.get_cells().count_ones(..), 0);
+
+        universe.set_cells(&[(1, 1), (1, 2), (1, 3)]).unwrap();
+        universe.tick();
+        assert_eq!(universe.get_cells().count_ones(..), 3);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_event_capacity_shrinks_full_log() {
+    let mut universe = Universe::new_preallocated(8, 8, 64).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+    universe.set_event_mask(1 << EventKind::Birth as u32);
+    universe.set_event_capacity(4);
+
+    // the blinker has two births a generation
+    for _ in 0..3 {
+        universe.tick();
+    }
+    assert_eq!(universe.events_dropped(), 2);
+
+    universe.set_event_capacity(1);
+    assert_eq!(universe.events_dropped(), 5);
+    let events = universe.drain_events();
+    assert_eq!(events.len(), EVENT_RECORD_WORDS);
+    assert_eq!(events[1], 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_preallocated_universe() {
+    let mut universe = Universe::new_preallocated(6, 6, 100).unwrap();
+    assert!(universe.is_preallocated());
+    let cells_ptr = universe.cells();
+
+    universe.set_width(16).unwrap();
+    assert_eq!(universe.cells(), cells_ptr);
+    assert_eq!(
+        universe.set_height(7),
+        Err(Error::CapacityExceeded {
+            requested: 112,
+            capacity: 100
+        })
+    );
+    assert_eq!(universe.height(), 6);
+
+    // ticks alternate between the two preallocated cell buffers
+    universe.set_cells(&[(1, 1), (1, 2), (1, 3)]).unwrap();
+    universe.tick();
+    universe.tick();
+    assert_eq!(universe.cells(), cells_ptr);
+    assert!(Universe::new_preallocated(20, 20, 100).is_err());
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_compare_engines() {
+    let config = ComparisonConfig::new(32, 32, 0.4, 7, 20);
+
+    let report = compare_engines(&config).unwrap();
+    assert!(report.consistent());
+    assert_eq!(report.results().len(), Engine::available().len());
+    assert_eq!(
+        report
+            .results()
+            .iter()
+            .any(|result| result.engine == Engine::HashLife),
+        cfg!(feature = "hashlife")
+    );
+
+    // long enough for the soup to wrap around the board twice
+    let config = ComparisonConfig::new(24, 20, 0.4, 3, 50);
+    assert!(compare_engines(&config).unwrap().consistent());
+
+    let config = ComparisonConfig::new(1 << 16, 1 << 16, 0.4, 7, 20);
+    assert!(matches!(
+        compare_engines(&config),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_run_ensemble() {
+    let config = EnsembleConfig::new(16, 16, 0.35, 11, 500);
+
+    let report = run_ensemble(6, &config).unwrap();
+    assert_eq!(report.len(), 6);
+    let seeds: Vec<u64> = report.replicates().iter().map(|r| r.seed).collect();
+    assert_eq!(seeds, (11..17).collect::<Vec<u64>>());
+    let classes = [
+        GrowthClass::Extinct,
+        GrowthClass::Stable,
+        GrowthClass::Oscillating,
+        GrowthClass::Growing,
+        GrowthClass::Unsettled,
+    ];
+    let total: u32 = classes.iter().map(|&c| report.class_count(c)).sum();
+    assert_eq!(total, 6);
+    assert!((report.class_fractions().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+    let lifespan = report.lifespan();
+    assert!(lifespan.min <= lifespan.median && lifespan.median <= lifespan.max);
+    assert!(lifespan.max <= 500.0);
+    for replicate in report.replicates() {
+        assert_eq!(
+            replicate.final_population == 0,
+            replicate.class == GrowthClass::Extinct
+        );
+    }
+
+    // shards run separately merge into the same report
+    let mut merged = run_ensemble_shard(&config, 3, 3).unwrap();
+    merged.merge(&run_ensemble_shard(&config, 0, 3).unwrap());
+    assert_eq!(merged, report);
+
+    let too_long = EnsembleConfig::new(4, 4, 0.5, 1, u32::MAX);
+    assert!(matches!(
+        run_ensemble(1, &too_long),
+        Err(Error::GenerationLimit { .. })
+    ));
+    let too_large = EnsembleConfig::new(1 << 16, 1 << 16, 0.5, 1, 10);
+    assert_eq!(
+        run_ensemble(1, &too_large).err(),
+        Some(Error::DimensionsTooLarge {
+            width: 1 << 16,
+            height: 1 << 16
+        })
+    );
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_genetic_search() {
+    let config = SearchConfig::new(16, 16, 4, 4, 8, 100, 3);
+    let mut search = GeneticSearch::new(&config, Fitness::Lifespan).unwrap();
+    assert_eq!(search.generation(), 0);
+    search.evolve(5).unwrap();
+    assert_eq!(search.generation(), 5);
+
+    // the fittest genome always survives, so the best never gets worse
+    let history = search.best_fitness_history();
+    assert_eq!(history.len(), 6);
+    assert!(history.windows(2).all(|pair| pair[0] <= pair[1]));
+    let best = search.best();
+    assert_eq!(best.fitness, history[5]);
+    let top = search.top(3);
+    assert_eq!(top.len(), 3);
+    assert_eq!(top[0], best);
+    assert!(best.pattern().width() <= 4 && best.pattern().height() <= 4);
+    assert_eq!(best.pattern().cells().len() as u32, best.population());
+
+    // on a 16x16 torus nothing gets further than 8 cells in each direction
+    let mut displacement = GeneticSearch::new(&config, Fitness::Displacement).unwrap();
+    displacement.evolve(2).unwrap();
+    assert!(displacement.best().fitness <= (8.0f64 * 8.0 * 2.0).sqrt());
+
+    let too_big = SearchConfig::new(4, 4, 8, 8, 8, 10, 1);
+    assert!(matches!(
+        GeneticSearch::new(&too_big, Fitness::Lifespan),
+        Err(Error::InvalidConfig(_))
+    ));
+    let too_large = SearchConfig::new(1 << 16, 1 << 16, 4, 4, 8, 10, 1);
+    assert_eq!(
+        GeneticSearch::new(&too_large, Fitness::Lifespan).err(),
+        Some(Error::DimensionsTooLarge {
+            width: 1 << 16,
+            height: 1 << 16
+        })
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_paced_stepping() {
+    let mut universe = input_spaceship();
+    assert_eq!(universe.advance(0.0), Ok(0));
+
+    universe.set_target_gps(30.0).unwrap();
+    assert_eq!(universe.target_gps(), Some(30.0));
+    assert_eq!(
+        universe.advance(1000.0),
+        Ok(0),
+        "the first call starts the clock"
+    );
+    // 60 frames a second are two frames per generation
+    let ticked: u32 = (1..=60)
+        .map(|frame| {
+            universe
+                .advance(1000.0 + frame as f64 * 1000.0 / 60.0)
+                .unwrap()
+        })
+        .sum();
+    assert_eq!(ticked, 30);
+    assert_eq!(universe.generation(), 30);
+    // a slow frame catches up
+    assert_eq!(universe.advance(2100.0), Ok(3));
+    // time going backwards restarts the clock
+    assert_eq!(universe.advance(1500.0), Ok(0));
+    assert_eq!(universe.advance(1600.0), Ok(3));
+
+    universe.clear_target_gps();
+    assert_eq!(universe.advance(5000.0), Ok(0));
+    assert!(matches!(
+        universe.set_target_gps(0.0),
+        Err(Error::InvalidConfig(_))
+    ));
+    assert!(universe.set_target_gps(f64::NAN).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_boundary_traffic() {
+    let mut universe = Universe::new(5, 5);
+    // a blinker straddling the west/east seam
+    universe.set_cells(&[(2, 4), (2, 0), (2, 1)]).unwrap();
+    universe.tick();
+    assert_eq!(universe.boundary_traffic(), BoundaryTraffic::default());
+
+    universe.reset_cells();
+    universe.set_cells(&[(2, 4), (2, 0), (2, 1)]).unwrap();
+    universe.set_track_boundary_traffic(true);
+    universe.tick();
+    let traffic = universe.boundary_traffic();
+    // (1, 0) and (3, 0) are born from (2, 4) across the west edge
+    assert_eq!(traffic.west_births, 2);
+    // (2, 4) dies although (2, 0) is its neighbour across the east edge
+    assert_eq!(traffic.east_deaths, 1);
+    assert_eq!(traffic.west_deaths + traffic.east_births, 0);
+    assert_eq!(traffic.north_births + traffic.south_births, 0);
+    // both births and the survival of (2, 0) depend on the wrap
+    assert_eq!(traffic.distorted, 3);
+
+    // back to horizontal: (2, 4) is born from the column across the east
+    // edge
+    universe.tick();
+    assert_eq!(universe.boundary_traffic().east_births, 1);
+    assert_eq!(
+        universe.boundary_traffic_total().west_births
+            + universe.boundary_traffic_total().east_births,
+        3
+    );
+    universe.set_track_boundary_traffic(false);
+    assert!(!universe.is_tracking_boundary_traffic());
+}
+
+#[wasm_bindgen_test]
+pub fn test_metapixels() {
+    // stand-in unit cells: a block for "on", a single cell for "off"
+    let on = Pattern::from_cells("on", vec![(1, 1), (1, 2), (2, 1), (2, 2)]);
+    let off = Pattern::from_cells("off", vec![(0, 0)]);
+    let glider = Pattern::from_cells("glider", vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+
+    let mega = expand_to_metapixels(&glider, &on, &off, 8).unwrap();
+    assert_eq!(mega.population(), 5 * 4 + 4);
+    assert_eq!(mega.width(), 2 * 8 + 2);
+    assert!(mega.cells().contains(&(0, 0)));
+    assert!(mega.cells().contains(&(1, 9)));
+
+    let mut universe = Universe::new(24, 24);
+    universe
+        .place_pattern(&mega, 0, 0, PlacementPolicy::Error)
+        .unwrap();
+    assert_eq!(
+        universe.downsample_metapixels(8, 1),
+        vec![0, 1, 0, 0, 0, 1, 1, 1, 1]
+    );
+    assert_eq!(universe.downsample_metapixels(10, 1).len(), 4);
+    assert!(universe.downsample_metapixels(0, 1).is_empty());
+
+    assert!(matches!(
+        expand_to_metapixels(&glider, &on, &off, u32::MAX),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+}
+
+#[wasm_bindgen_test]
+pub fn test_incremental_state_hash() {
+    let mut ticked = input_spaceship();
+    let empty = Universe::new(6, 6).state_hash();
+    assert_eq!(empty, 0);
+    assert_ne!(ticked.state_hash(), empty);
+
+    // ticking updates the hash to the one of the resulting cells
+    ticked.tick();
+    assert_eq!(ticked.state_hash(), expected_spaceship().state_hash());
+
+    // editing updates it too, and setting a live cell again changes nothing
+    let mut edited = Universe::new(6, 6);
+    edited
+        .set_cells(&[(2, 1), (2, 3), (3, 2), (3, 3), (4, 2), (4, 2)])
+        .unwrap();
+    assert_eq!(edited.state_hash(), ticked.state_hash());
+    edited.toggle_cell(0, 0).unwrap();
+    assert_ne!(edited.state_hash(), ticked.state_hash());
+    edited.toggle_cell(0, 0).unwrap();
+    assert_eq!(edited.state_hash(), ticked.state_hash());
+
+    // independent of the storage order
+    let mut transposed = input_spaceship();
+    transposed.set_storage_order(StorageOrder::ColumnMajor);
+    assert_eq!(transposed.state_hash(), input_spaceship().state_hash());
+    transposed.tick();
+    assert_eq!(transposed.state_hash(), ticked.state_hash());
+
+    ticked.reset_cells();
+    assert_eq!(ticked.state_hash(), empty);
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_universe_rules() {
+    assert_eq!(Universe::new(4, 4).rule(), Rule::conway());
+    assert!(matches!(
+        Universe::with_rule(4, 4, "B3/X"),
+        Err(Error::InvalidRule(_))
+    ));
+
+    // the HighLife replicator's six-neighbour births do not happen in Life
+    let replicator = [
+        (0, 2),
+        (0, 3),
+        (0, 4),
+        (1, 1),
+        (1, 4),
+        (2, 0),
+        (2, 4),
+        (3, 0),
+        (3, 3),
+        (4, 0),
+        (4, 1),
+        (4, 2),
+    ];
+    let mut highlife = Universe::with_rule(24, 24, "B36/S23").unwrap();
+    let mut life = Universe::new(24, 24);
+    for universe in [&mut highlife, &mut life] {
+        universe
+            .set_cells(&replicator.map(|(r, c)| (r + 10, c + 10)))
+            .unwrap();
+        universe.tick_many(12).unwrap();
+    }
+    assert_ne!(highlife.get_cells(), life.get_cells());
+
+    // switching back reproduces Life from the same state
+    let mut switched = Universe::with_rule(6, 6, "B36/S23").unwrap();
+    switched
+        .set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)])
+        .unwrap();
+    switched.set_rulestring("B3/S23").unwrap();
+    switched.tick();
+    assert_eq!(switched.get_cells(), expected_spaceship().get_cells());
+
+    assert!(switched.set_rulestring("nonsense").is_err());
+    assert_eq!(switched.rule(), Rule::conway());
+    switched.set_rule(&Rule::parse("B2/S").unwrap());
+    assert_eq!(switched.rule().to_string(), "B2/S");
+}
+
+// also in builds without rulestrings
+#[wasm_bindgen_test]
+pub fn test_builtin_rule() {
+    let mut universe = Universe::new(5, 5);
+    assert_eq!(universe.rule(), Rule::conway());
+    assert_eq!(universe.rule().to_string(), "B3/S23");
+    assert!(universe.rule().is_totalistic());
+
+    universe.complement_state();
+    assert_eq!(universe.rule(), Rule::conway().complement());
+    assert_eq!(universe.rule().to_string(), "B0123478/S01234678");
+    universe.complement_state();
+    assert_eq!(universe.rule(), Rule::conway());
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_rule_parsing() {
+    let conway = Rule::conway();
+    assert_eq!(Rule::parse("B3/S23"), Ok(conway));
+    assert_eq!(Rule::parse("b3s23"), Ok(conway));
+    assert_eq!(Rule::parse("S23/B3"), Ok(conway));
+    assert_eq!(Rule::parse("23/3"), Ok(conway));
+    assert_eq!(Rule::parse("b63/s32").unwrap().to_string(), "B36/S23");
+    assert_eq!(Rule::parse("B/S").unwrap().to_string(), "B/S");
+
+    let highlife = Rule::parse("B36/S23").unwrap();
+    assert!(highlife.births(6) && !highlife.survives(6));
+    assert!(!highlife.births(9));
+
+    for invalid in ["", "B39/S23", "B3", "B3/S23/B4", "X3/S23", "3"] {
+        assert!(
+            matches!(Rule::parse(invalid), Err(Error::InvalidRule(_))),
+            "{:?}",
+            invalid
+        );
+    }
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_rule_complement() {
+    let life = Rule::conway();
+    assert_eq!(life.complement().to_string(), "B0123478/S01234678");
+    assert_eq!(life.complement().complement(), life);
+    assert!(!life.is_self_complementary());
+    assert!(Rule::parse("B3678/S34678").unwrap().is_self_complementary());
+    assert_eq!(complement_rule("B36/S23").unwrap(), "B0123478/S0134678");
+    assert!(complement_rule("B3").is_err());
+
+    // evolving the inverse video under the complement and inverting back
+    // matches evolving the original
+    let mut original = input_spaceship();
+    let mut inverse = input_spaceship();
+    inverse.complement_state();
+    let size = (inverse.width() * inverse.height()) as usize;
+    assert_eq!(inverse.get_cells().count_ones(..), size - 5);
+    for _ in 0..4 {
+        original.tick();
+        inverse.tick();
+    }
+    inverse.complement_state();
+    assert_eq!(inverse.get_cells(), original.get_cells());
+
+    // the generations before ran under the other rule
+    let mut universe = input_spaceship();
+    universe.set_history_capacity(8);
+    universe.set_track_ages(true);
+    universe.tick();
+    universe.complement_state();
+    assert_eq!(universe.history_len(), 0);
+    assert!(!universe.step_back());
+    assert_eq!(universe.max_age(), 1);
+    assert_eq!(universe.envelope_population(), size as u32 - 5);
+
+    // mirrored edges invert along with the cells, dead ones do not
+    for (topology, equivalent) in [(Topology::Mirror, true), (Topology::Dead, false)] {
+        let mut original = Universe::new_seeded(12, 10, 0.4, 3).unwrap();
+        let mut inverse = Universe::new_seeded(12, 10, 0.4, 3).unwrap();
+        original.set_topology(topology);
+        inverse.set_topology(topology);
+        inverse.complement_state();
+        for _ in 0..6 {
+            original.tick();
+            inverse.tick();
+        }
+        inverse.complement_state();
+        assert_eq!(inverse.get_cells() == original.get_cells(), equivalent);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_components() {
+    let mut universe = Universe::new(5, 6);
+    // a block joined across the corner by (4, 5), and a lone cell
+    universe
+        .set_cells(&[(0, 0), (0, 1), (1, 0), (1, 1), (4, 5), (3, 3)])
+        .unwrap();
+    let components = universe.components();
+    assert_eq!(components.count(), 2);
+    assert_eq!(components.sizes(), vec![5, 1]);
+    assert_eq!(components.anchors(), vec![0, 21]);
+    let labels = components.labels();
+    assert_eq!(labels.len(), 30);
+    assert_eq!((labels[0], labels[29], labels[21], labels[2]), (1, 1, 2, 0));
+
+    assert_eq!(Universe::new(3, 3).components().count(), 0);
+
+    // dead edges join nothing across them
+    universe.set_topology(Topology::Dead);
+    assert_eq!(universe.components().sizes(), vec![4, 1, 1]);
+
+    // across the Klein bottle's north edge (0, 1) meets (4, 4), not (4, 1)
+    for order in [StorageOrder::RowMajor, StorageOrder::ColumnMajor] {
+        let mut klein = Universe::new(5, 6);
+        klein.set_storage_order(order);
+        klein.set_topology(Topology::KleinBottle);
+        klein.set_cells(&[(0, 1), (4, 4), (2, 3)]).unwrap();
+        let components = klein.components();
+        assert_eq!(components.sizes(), vec![2, 1]);
+        let labels = components.labels();
+        let label = |row: usize, column: usize| match order {
+            StorageOrder::RowMajor => labels[row * 6 + column],
+            StorageOrder::ColumnMajor => labels[column * 5 + row],
+        };
+        assert_eq!(label(0, 1), label(4, 4));
+        assert_ne!(label(0, 1), label(2, 3));
+    }
+}
+
+#[cfg(all(feature = "analysis", feature = "formats"))]
+#[wasm_bindgen_test]
+pub fn test_census() {
+    let mut universe = Universe::new(24, 24);
+    let stamp = |universe: &mut Universe, code: &str, row: u32, column: u32, transform| {
+        let pattern = Pattern::from_apgcode(code).unwrap();
+        universe
+            .insert_pattern(&pattern, row, column, transform)
+            .unwrap();
+    };
+    stamp(&mut universe, "xs4_33", 1, 1, Transform::Identity);
+    // a cell apart, yet two blocks
+    stamp(&mut universe, "xs4_33", 1, 4, Transform::Identity);
+    stamp(&mut universe, "xs6_696", 6, 1, Transform::Rotate90);
+    stamp(&mut universe, "xq4_153", 12, 12, Transform::FlipVertical);
+    // a blinker across the wrap
+    universe.set_cells(&[(23, 20), (0, 20), (1, 20)]).unwrap();
+    let census = universe.census().unwrap();
+    assert_eq!(
+        census.names(),
+        vec!["beehive", "blinker", "block", "glider"]
+    );
+    assert_eq!(census.count("block"), 2);
+    assert_eq!(census.count("glider"), 1);
+    assert_eq!(census.total(), 5);
+
+    // a glider is recognised in every phase
+    for _ in 0..3 {
+        universe.tick();
+        assert_eq!(universe.census().unwrap().count("glider"), 1);
+    }
+
+    // unnamed objects get an apgcode prefix, named ones too in other rules
+    let mut universe = Universe::new(16, 16);
+    stamp(&mut universe, "xs6_bd", 1, 1, Transform::Identity);
+    stamp(&mut universe, "xs8_25ak8", 6, 6, Transform::Identity);
+    universe.toggle_cell(14, 1).unwrap();
+    let census = universe.census().unwrap();
+    assert_eq!(census.names(), vec!["other", "snake", "xs8"]);
+    universe.set_rule(&Rule::parse("B3/S234").unwrap());
+    assert_eq!(
+        universe.census().unwrap().names(),
+        vec!["other", "xs6", "xs8"]
+    );
+
+    assert_eq!(Universe::new(4, 4).census().unwrap().total(), 0);
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_analyze_rule() {
+    let life = analyze_rule("b3s23").unwrap();
+    assert_eq!(life.rule(), Rule::conway());
+    assert_eq!(life.name(), "Life");
+    assert_eq!(life.equivalents(), vec!["Life".to_string()]);
+    assert!(!life.has_b0() && !life.is_self_complementary());
+    assert!(life.explosiveness() < 0.5);
+
+    let seeds = analyze_rule("B2/S").unwrap();
+    assert_eq!(seeds.name(), "Seeds");
+    assert_eq!(seeds.explosiveness(), 1.0);
+
+    let inverse = analyze_rule("B0123478/S01234678").unwrap();
+    assert!(inverse.has_b0());
+    assert_eq!(inverse.name(), "");
+    assert_eq!(
+        inverse.equivalents(),
+        vec!["Life (black/white complement)".to_string()]
+    );
+
+    let day_and_night = analyze_rule("B3678/S34678").unwrap();
+    assert!(day_and_night.is_self_complementary());
+    assert_eq!(day_and_night.equivalents().len(), 1);
+
+    assert!(matches!(analyze_rule("B9"), Err(Error::InvalidRule(_))));
+}
+
+#[cfg(feature = "analysis")]
+#[wasm_bindgen_test]
+pub fn test_compare_rules() {
+    // HighLife only differs from Life on six neighbours, which a blinker
+    // never has
+    let mut blinker = Universe::new(5, 5);
+    blinker.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+    let same = compare_rules(&mut blinker, "B3/S23", "B36/S23", 4).unwrap();
+    assert_eq!(same.generation(), 4);
+    assert_eq!(same.divergence(), vec![0.0; 5]);
+    assert_eq!(same.cells_a(), same.cells_b());
+    assert_eq!(blinker.generation(), 0);
+
+    // without death the blinker's ends stay alive
+    let mut diverging = compare_rules(&mut blinker, "B3/S23", "B3/S012345678", 1).unwrap();
+    assert_eq!(diverging.differing_cells(), 2);
+    let difference = diverging.difference();
+    assert_eq!(difference[2 * 5 + 1], 1);
+    assert_eq!(difference[2 * 5 + 3], 1);
+    assert_eq!(diverging.divergence(), vec![0.0, 2.0 / 25.0]);
+    diverging.tick();
+    assert_eq!(diverging.divergence().len(), 3);
+    assert_eq!(diverging.rule_b().to_string(), "B3/S012345678");
+
+    assert!(matches!(
+        compare_rules(&mut blinker, "B3/S23", "nonsense", 1),
+        Err(Error::InvalidRule(_))
+    ));
+
+    // the seed's edges and neighbourhood carry over to both sides
+    let mut edged = Universe::new(8, 8);
+    edged.randomize(0.4, &mut Entropy::seeded(9));
+    edged.set_topology(Topology::Dead);
+    edged.set_neighborhood(Neighborhood::Hexagonal);
+    edged.set_rulestring("B2/S34").unwrap();
+    let comparison = compare_rules(&mut edged, "B2/S34", "B2/S34", 5).unwrap();
+    for _ in 0..5 {
+        edged.tick();
+    }
+    let cells = edged.get_cells();
+    let expected: Vec<u8> = (0..64).map(|idx| cells.contains(idx) as u8).collect();
+    assert_eq!(comparison.cells_a(), expected);
+    assert_eq!(comparison.cells_b(), expected);
+}
+
+#[cfg(feature = "timing")]
+#[wasm_bindgen_test]
+pub fn timings_are_aggregated() {
+    use wasm_game_of_life::{timing_summary, PhaseTimer};
+
+    let mut universe = Universe::new(8, 8);
+    let before = timing_summary("Universe::tick").map_or(0, |t| t.count);
+    for _ in 0..3 {
+        universe.tick();
+    }
+    let tick = timing_summary("Universe::tick").unwrap();
+    assert_eq!(tick.count, before + 3);
+    assert!(tick.min_ms <= tick.max_ms);
+    assert!(tick.mean_ms() <= tick.max_ms);
+
+    let elapsed = PhaseTimer::start("test::phase").end();
+    let phase = timing_summary("test::phase").unwrap();
+    assert_eq!(phase.count, 1);
+    assert_eq!(phase.last_ms, elapsed);
+    assert!(timing_summary("test::missing").is_none());
+}
+
+#[cfg(feature = "logging")]
+#[wasm_bindgen_test]
+pub fn log_level_round_trip() {
+    use wasm_game_of_life::{log_level, set_log_level, LogLevel};
+
+    assert_eq!(log_level(), LogLevel::Error);
+    set_log_level(LogLevel::Trace);
+    assert_eq!(log_level(), LogLevel::Trace);
+
+    // every engine traces the cells it changes
+    for engine in Engine::available() {
+        let mut universe = input_spaceship();
+        universe.set_engine(engine);
+        universe.tick();
+    }
+
+    set_log_level(LogLevel::Error);
+    assert_eq!(log_level(), LogLevel::Error);
+}
+
+#[wasm_bindgen_test]
+pub fn test_randomize_with_entropy_sources() {
+    let mut a = Universe::new(16, 16);
+    let mut b = Universe::new(16, 16);
+    a.tick();
+    a.randomize(0.5, &mut Entropy::seeded(7));
+    b.randomize(0.5, &mut Entropy::seeded(7));
+    assert_eq!(a.get_cells(), b.get_cells());
+
+    b.randomize(0.5, &mut Entropy::seeded(8));
+    assert_ne!(a.get_cells(), b.get_cells());
+
+    struct Constant(f64);
+    impl EntropySource for Constant {
+        fn next_f64(&mut self) -> f64 {
+            self.0
+        }
+    }
+    a.randomize(0.5, &mut Entropy::from_source(Constant(0.25)));
+    assert_eq!(a.get_cells().count_ones(..), 16 * 16);
+    a.randomize(0.5, &mut Entropy::from_source(Constant(0.75)));
+    assert!(a.get_cells().is_clear());
+}
+
+#[wasm_bindgen_test]
+pub fn test_new_seeded() {
+    let a = Universe::new_seeded(24, 16, 0.3, 42).unwrap();
+    let mut b = Universe::new_seeded(24, 16, 0.3, 42).unwrap();
+    assert_eq!((b.height(), b.width()), (24, 16));
+    assert_eq!(a.get_cells(), b.get_cells());
+    assert_eq!(a.population(), a.get_cells().count_ones(..) as u32);
+
+    b.tick();
+    b.randomize_seeded(0.3, 42);
+    assert_eq!(a.get_cells(), b.get_cells());
+    assert_eq!(b.generation(), 0);
+    b.randomize_seeded(0.3, 43);
+    assert_ne!(a.get_cells(), b.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_randomize_region() {
+    let mut universe = Universe::new(16, 16);
+    universe.set_cells(&[(0, 0), (15, 15)]).unwrap();
+    universe
+        .randomize_region(4, 4, 8, 6, 1.0, &mut Entropy::seeded(1))
+        .unwrap();
+    assert_eq!(universe.population(), 2 + 8 * 6);
+    assert!(universe.get_cells()[0]);
+    for row in 0..16 {
+        for col in 0..16 {
+            let inside = (4..12).contains(&row) && (4..10).contains(&col);
+            let corner = (row, col) == (0, 0) || (row, col) == (15, 15);
+            let idx = (row * 16 + col) as usize;
+            assert_eq!(universe.get_cells()[idx], inside || corner);
+        }
+    }
+
+    universe
+        .randomize_region(4, 4, 8, 6, 0.0, &mut Entropy::seeded(1))
+        .unwrap();
+    assert_eq!(universe.population(), 2);
+
+    let err = universe
+        .randomize_region(10, 10, 6, 7, 0.5, &mut Entropy::seeded(1))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Error::OutOfBounds {
+            row: 15,
+            column: 16,
+            height: 16,
+            width: 16
+        }
+    );
+    assert_eq!(universe.population(), 2);
+}
+
+#[cfg(feature = "patterns")]
+#[wasm_bindgen_test]
+pub fn test_pattern_catalog() {
+    use wasm_game_of_life::{PatternCatalog, PatternCategory};
+
+    let catalog = PatternCatalog::builtin();
+    for category in [
+        PatternCategory::StillLife,
+        PatternCategory::Oscillator,
+        PatternCategory::Spaceship,
+        PatternCategory::Gun,
+        PatternCategory::Methuselah,
+    ] {
+        assert!(!catalog.in_category(category).is_empty());
+    }
+
+    // exact match before substring matches
+    let spaceships = catalog.search("SPACESHIP");
+    assert_eq!(spaceships.len(), 3);
+    let glider = catalog.search("glider");
+    assert_eq!(catalog.names()[glider[0] as usize], "Glider");
+    assert_eq!(glider.len(), 2);
+    assert!(catalog.search("no such thing").is_empty());
+
+    // the glider's thumbnail scales each cell to 4x4 pixels
+    let thumbnail = catalog.thumbnail(glider[0], 12).unwrap().unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (12, 12));
+    let pixels = thumbnail.pixels();
+    assert_eq!(pixels.len(), 12 * 12 * 4);
+    assert_eq!(&pixels[..4], &[0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(&pixels[4 * 4..4 * 4 + 4], &[0, 0, 0, 0xff]);
+    assert_eq!(catalog.thumbnail(u32::MAX, 12), Ok(None));
+    assert!(matches!(
+        catalog.thumbnail(glider[0], 40_000),
+        Err(Error::InvalidConfig(_))
+    ));
+
+    // places like the hand-built glider does
+    let mut universe = Universe::new(6, 6);
+    universe
+        .place_pattern(
+            &catalog.pattern(glider[0]).unwrap(),
+            1,
+            1,
+            PlacementPolicy::Error,
+        )
+        .unwrap();
+    universe.tick();
+    assert_eq!(universe.get_cells(), expected_spaceship().get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_pattern_plaintext() {
+    let pattern = Pattern::from_plaintext("!Name: Boat\n!\nOO.\nO.O\n.O.\n").unwrap();
+    assert_eq!(pattern.name(), "Boat");
+    assert_eq!((pattern.width(), pattern.height()), (3, 3));
+    assert_eq!(pattern.population(), 5);
+    assert_eq!(
+        Pattern::from_plaintext(&pattern.to_plaintext()).unwrap(),
+        pattern
+    );
+    assert_eq!(
+        Pattern::from_plaintext("OO\nOx").err(),
+        Some(Error::InvalidPattern {
+            line: 2,
+            reason: "expected 'O' or '.'"
+        })
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_placement_is_bounds_checked() {
+    let mut universe = Universe::new(4, 6);
+    let outside = |row, column| Error::OutOfBounds {
+        row,
+        column,
+        width: 6,
+        height: 4,
+    };
+    assert_eq!(universe.set_cells(&[(0, 0), (4, 0)]), Err(outside(4, 0)));
+    assert_eq!(universe.toggle_cell(0, 6), Err(outside(0, 6)));
+
+    let glider = Pattern::from_plaintext(".O.\n..O\nOOO\n").unwrap();
+    assert_eq!(
+        universe.place_pattern(&glider, 1, 4, PlacementPolicy::Error),
+        Err(outside(3, 6))
+    );
+    assert_eq!(
+        universe.place_pattern(&glider, u32::MAX, 0, PlacementPolicy::Error),
+        Err(outside(u32::MAX as u64 + 2, 2))
+    );
+    // failed placements leave the universe untouched
+    assert_eq!(universe.get_cells().count_ones(..), 0);
+
+    universe
+        .place_pattern(&glider, 1, 3, PlacementPolicy::Error)
+        .unwrap();
+    assert_eq!(universe.get_cells().count_ones(..), 5);
+}
+
+#[wasm_bindgen_test]
+pub fn test_placement_policies() {
+    let glider = Pattern::from_plaintext(".O.\n..O\nOOO\n").unwrap();
+    let mut clipped = Universe::new(4, 6);
+    clipped
+        .place_pattern(&glider, 2, 4, PlacementPolicy::Clip)
+        .unwrap();
+    let mut expected = Universe::new(4, 6);
+    expected.set_cells(&[(2, 5)]).unwrap();
+    assert_eq!(clipped.get_cells(), expected.get_cells());
+
+    let mut wrapped = Universe::new(4, 6);
+    wrapped
+        .place_pattern(&glider, 2, 4, PlacementPolicy::Wrap)
+        .unwrap();
+    expected
+        .set_cells(&[(3, 0), (0, 4), (0, 5), (0, 0)])
+        .unwrap();
+    assert_eq!(wrapped.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_pattern_transforms() {
+    // an L tetromino
+    let pattern = Pattern::from_plaintext("OO\nO.\nO.\n").unwrap();
+    let quarter = pattern.transformed(Rotation::Quarter, false);
+    assert_eq!((quarter.width(), quarter.height()), (3, 2));
+    assert_eq!(quarter.to_plaintext(), "OOO\n..O\n");
+    let flipped = pattern.transformed(Rotation::None, true);
+    assert_eq!(flipped.to_plaintext(), "OO\n.O\n.O\n");
+    assert_eq!(
+        pattern.transformed(Rotation::Half, true).to_plaintext(),
+        "O.\nO.\nOO\n"
+    );
+    assert_eq!(quarter.transformed(Rotation::ThreeQuarters, false), pattern);
+}
+
+#[wasm_bindgen_test]
+pub fn test_place_many_is_atomic() {
+    let glider = Pattern::from_plaintext(".O.\n..O\nOOO\n").unwrap();
+    let mut universe = Universe::new(8, 8);
+
+    let mut flipped = Placement::new(&glider, 4, 4);
+    flipped.flip = true;
+    flipped.rotation = Rotation::Half;
+    let mut outside = Placement::new(&glider, 6, 0);
+    assert_eq!(
+        universe.place_many(&[Placement::new(&glider, 0, 0), outside.clone()]),
+        Err(Error::OutOfBounds {
+            row: 8,
+            column: 2,
+            width: 8,
+            height: 8,
+        })
+    );
+    assert_eq!(universe.get_cells().count_ones(..), 0);
+
+    outside.policy = PlacementPolicy::Clip;
+    universe
+        .place_many(&[Placement::new(&glider, 0, 0), flipped, outside])
+        .unwrap();
+    let mut expected = Universe::new(8, 8);
+    expected
+        .place_pattern(&glider, 0, 0, PlacementPolicy::Error)
+        .unwrap();
+    expected
+        .set_cells(&[(4, 4), (4, 5), (4, 6), (5, 6), (6, 5), (6, 1), (7, 2)])
+        .unwrap();
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_fit_to_pattern() {
+    let glider = Pattern::from_plaintext(".O.\n..O\nOOO\n").unwrap();
+    let mut universe = Universe::new(64, 64);
+    universe.fit_to_pattern(&glider, 2).unwrap();
+    assert_eq!((universe.width(), universe.height()), (7, 7));
+    let mut expected = Universe::new(7, 7);
+    expected
+        .place_pattern(&glider, 2, 2, PlacementPolicy::Error)
+        .unwrap();
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    // a diagonal ship gets a square torus, one period per column and row
+    let periods = universe.fit_to_spaceship(&glider, 1, 1, 1).unwrap();
+    assert_eq!((universe.width(), universe.height(), periods), (5, 5, 5));
+    let lwss = Pattern::from_plaintext(".O..O\nO....\nO...O\nOOOO.\n").unwrap();
+    let periods = universe.fit_to_spaceship(&lwss, 2, -2, 0).unwrap();
+    assert_eq!((universe.width(), universe.height(), periods), (10, 8, 5));
+    let start = universe.get_cells().clone();
+    for _ in 0..4 * periods {
+        universe.tick();
+    }
+    assert_eq!(universe.get_cells(), &start);
+
+    assert_eq!(
+        universe.fit_to_pattern(&glider, u32::MAX / 2),
+        Err(Error::DimensionsTooLarge {
+            width: u32::MAX as u64 + 2,
+            height: u32::MAX as u64 + 2,
+        })
+    );
+}
+
+#[cfg(feature = "patterns")]
+#[wasm_bindgen_test]
+pub fn test_lexicon() {
+    use wasm_game_of_life::Lexicon;
+
+    let text = "LIFE LEXICON\n\
+                Introductory text is skipped.\n\
+                \n\
+                :block: (p1) The most common {still life}.\n\
+                \n\
+                \tOO\n\
+                \tOO\n\
+                \n\
+                :glider: (c/4 diagonally, p4) The smallest, most common\n\
+                and first discovered {spaceship}.\n\
+                \n\
+                \t.O.\n\
+                \t..O\n\
+                \tOOO\n\
+                \n\
+                :still life: A stable pattern, such as the {block}.\n";
+    let lexicon = Lexicon::parse(text);
+    assert_eq!(lexicon.len(), 3);
+    assert_eq!(lexicon.term(1).unwrap(), "glider");
+    assert_eq!(
+        lexicon.definition(1).unwrap(),
+        "(c/4 diagonally, p4) The smallest, most common and first discovered {spaceship}."
+    );
+
+    let glider = lexicon.diagram(1, 0).unwrap();
+    assert_eq!(glider.name(), "glider");
+    assert_eq!(glider.cell_coords(), &[0, 1, 1, 2, 2, 0, 2, 1, 2, 2]);
+    assert_eq!(lexicon.diagram_count(2), 0);
+    assert!(lexicon.diagram(2, 0).is_none());
+
+    assert_eq!(lexicon.lookup("Still Life"), Some(2));
+    // term matches before definition matches
+    assert_eq!(lexicon.search("BLOCK"), &[0, 2]);
+    assert!(lexicon.search("oscillator").is_empty());
+}
+
+#[cfg(feature = "patterns")]
+#[wasm_bindgen_test]
+pub fn test_search_patterns_by_metadata() {
+    use wasm_game_of_life::{PatternCatalog, PatternCategory};
+
+    let mut catalog = PatternCatalog::builtin();
+    let names = |indices: Vec<u32>| -> Vec<String> {
+        let all = catalog.names();
+        indices.iter().map(|&i| all[i as usize].clone()).collect()
+    };
+
+    assert_eq!(
+        names(catalog.search_patterns("speed:c/4").unwrap()),
+        ["Glider"]
+    );
+    assert_eq!(
+        names(catalog.search_patterns("speed:2c/4 name:middle").unwrap()),
+        ["Middleweight spaceship"]
+    );
+    assert_eq!(
+        names(catalog.search_patterns("period:>=15").unwrap()),
+        ["Pentadecathlon", "Gosper glider gun"]
+    );
+    assert_eq!(
+        names(catalog.search_patterns("discoverer:gosper").unwrap()),
+        ["Gosper glider gun"]
+    );
+    assert_eq!(
+        names(catalog.search_patterns("period:2 bbox:<=3x3").unwrap()),
+        ["Blinker"]
+    );
+    assert_eq!(catalog.speed(catalog.search("glider")[0]).unwrap(), "c/4");
+    assert_eq!(
+        catalog.search_patterns("colour:red").err(),
+        Some(Error::InvalidQuery("unknown search field"))
+    );
+    assert!(catalog.search_patterns("speed:fast").is_err());
+
+    // patterns added at runtime are searchable once described
+    let index = catalog.add(
+        Pattern::from_plaintext("!Name: Custom\nOO\n").unwrap(),
+        PatternCategory::Spaceship,
+    );
+    catalog.set_motion(index, 7, 0, 3);
+    catalog.set_discoverer(index, "Someone");
+    assert_eq!(
+        catalog
+            .search_patterns("speed:3c/7 discoverer:someone")
+            .unwrap(),
+        [index]
+    );
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_pattern_formats() {
+    let glider = Pattern::from_plaintext(".O.\n..O\nOOO\n").unwrap();
+
+    let rle = "#N Glider\n#C A comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+    let parsed = Pattern::from_rle(rle).unwrap();
+    assert_eq!(parsed.name(), "Glider");
+    assert_eq!(parsed.cells(), glider.cells());
+    assert_eq!(
+        parsed.to_rle(),
+        "#N Glider\n#C A comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n"
+    );
+    assert_eq!(Pattern::from_rle(&parsed.to_rle()).unwrap(), parsed);
+
+    // runs spanning several rows and lines
+    let wide = Pattern::from_rle("x = 12, y = 3\n12o2$\n12o!").unwrap();
+    assert_eq!(
+        (wide.width(), wide.height(), wide.population()),
+        (12, 3, 24)
+    );
+    assert_eq!(Pattern::from_rle(&wide.to_rle()).unwrap(), wide);
+
+    let life106 = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+    assert_eq!(
+        Pattern::from_life106(life106).unwrap().cells(),
+        glider.cells()
+    );
+    assert_eq!(Pattern::from_life106(&glider.to_life106()).unwrap(), glider);
+
+    assert_eq!(Pattern::parse(rle).unwrap(), parsed);
+    assert_eq!(Pattern::parse(life106).unwrap().cells(), glider.cells());
+    assert_eq!(Pattern::parse(".O.\n..O\nOOO").unwrap(), glider);
+
+    assert_eq!(
+        Pattern::from_rle("x = 2, y = 1\n2o?!").err(),
+        Some(Error::InvalidPattern {
+            line: 2,
+            reason: "unexpected character in RLE data"
+        })
+    );
+    assert_eq!(
+        Pattern::from_rle("x = 1, y = 1\n2o!").err(),
+        Some(Error::InvalidPattern {
+            line: 2,
+            reason: "cells outside the 'x = .., y = ..' size"
+        })
+    );
+    assert_eq!(
+        Pattern::from_rle("x = 1, y = 1\n4000000000b4000000000b!").err(),
+        Some(Error::InvalidPattern {
+            line: 2,
+            reason: "run too long"
+        })
+    );
+    assert!(matches!(
+        Pattern::from_rle("x = 4000000000, y = 1\n4000000000o!"),
+        Err(Error::MemoryLimit { .. })
+    ));
+    assert!(Pattern::from_rle("x = 3\no!").is_err());
+    assert!(Pattern::from_life106("#Life 1.06\n1 two\n").is_err());
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_universe_rle() {
+    let mut universe = Universe::from_rle("x = 3, y = 3, rule = B36/S23\nbo$2bo$3o!").unwrap();
+    assert_eq!((universe.width(), universe.height()), (3, 3));
+    assert_eq!(universe.rule().to_string(), "B36/S23");
+    assert_eq!(
+        universe.to_rle(),
+        "x = 3, y = 3, rule = B36/S23\nbo$2bo$3o!\n"
+    );
+
+    // exported trimmed to the live cells, wherever they are
+    let mut spaceship = input_spaceship();
+    spaceship.tick();
+    let copy = Universe::from_rle(&spaceship.to_rle()).unwrap();
+    assert_eq!(copy.rule(), Rule::conway());
+    assert_eq!(copy.to_rle(), spaceship.to_rle());
+    assert_eq!((copy.width(), copy.height()), (3, 3));
+
+    universe.reset_cells();
+    assert_eq!(universe.to_rle(), "x = 0, y = 0, rule = B36/S23\n!\n");
+
+    assert!(matches!(
+        Universe::from_rle("x = 1, y = 1, rule = LifeHistory\no!"),
+        Err(Error::InvalidRule(_))
+    ));
+    assert!(matches!(
+        Universe::from_rle("x = 1, y = 1\n2o?!"),
+        Err(Error::InvalidPattern { .. })
+    ));
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_read_rle_streaming() {
+    let text = "#N Glider\nx = 3, y = 3\nbo$2bo$3o!\nignored after the end\n";
+    let pattern = Pattern::read_rle(text.as_bytes()).unwrap();
+    assert_eq!(pattern, Pattern::from_rle(text).unwrap());
+    assert_eq!(pattern.name(), "Glider");
+    assert_eq!(pattern.population(), 5);
+    assert_eq!(
+        Pattern::read_rle("#C only a comment\n".as_bytes()),
+        Err(Error::InvalidPattern {
+            line: 1,
+            reason: "missing 'x = .., y = ..' header"
+        })
+    );
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_pattern_from_apgcode() {
+    let block = Pattern::from_apgcode("xs4_33").unwrap();
+    assert_eq!(block.name(), "xs4_33");
+    assert_eq!(block.cell_coords(), &[0, 0, 0, 1, 1, 0, 1, 1]);
+
+    let glider = Pattern::from_apgcode("xq4_153").unwrap();
+    assert_eq!(glider.cell_coords(), &[0, 0, 0, 1, 0, 2, 1, 2, 2, 1]);
+
+    // 'w' skips two columns, 'z' starts the next strip of five rows
+    let spaced = Pattern::from_apgcode("xs2_1w1z1").unwrap();
+    assert_eq!(spaced.cell_coords(), &[0, 0, 0, 3, 5, 0]);
+    let wide = Pattern::from_apgcode("xs2_1y21").unwrap();
+    assert_eq!(wide.width(), 8);
+
+    assert!(Pattern::from_apgcode("yl144_1_16_afb5f3db909e60548f086e22ee3353ac").is_err());
+    assert!(Pattern::from_apgcode("xs4_3!").is_err());
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_pattern_meta_round_trip() {
+    let rle = "#N Gosper glider gun\n#O Bill Gosper, 1970\n#C The first known gun.\n\
+               #C A second line.\nx = 2, y = 2, rule = B36/S23\n2o$2o!\n";
+    let pattern = Pattern::from_rle(rle).unwrap();
+    let meta = pattern.meta();
+    assert_eq!(meta.name(), "Gosper glider gun");
+    assert_eq!(meta.author(), "Bill Gosper");
+    assert_eq!(meta.discovered(), "1970");
+    assert_eq!(meta.rule(), "B36/S23");
+    assert_eq!(meta.comments(), ["The first known gun.", "A second line."]);
+
+    assert_eq!(pattern.to_rle(), rle);
+    let plaintext = pattern.to_plaintext();
+    assert!(plaintext.starts_with("!Name: Gosper glider gun\n!Author: Bill Gosper\n"));
+    assert_eq!(Pattern::from_plaintext(&plaintext).unwrap(), pattern);
+
+    // credits without a date stay whole
+    let plain = Pattern::from_rle("#O Someone, somewhere\nx = 1, y = 1\no!").unwrap();
+    assert_eq!(plain.meta().author(), "Someone, somewhere");
+    assert_eq!(plain.meta().discovered(), "");
+}
+
+#[cfg(feature = "hashlife")]
+#[wasm_bindgen_test]
+pub fn test_hashlife_universe() {
+    // an R-pentomino, against ticking a torus big enough never to wrap
+    let r_pentomino = [(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)];
+    let side = 256;
+    let mut universe = Universe::new(side, side);
+    let cells: Vec<(u32, u32)> = r_pentomino
+        .iter()
+        .map(|&(row, col)| (row + side / 2, col + side / 2))
+        .collect();
+    universe.set_cells(&cells).unwrap();
+    let mut hashlife = HashLifeUniverse::new();
+    for &(row, col) in &r_pentomino {
+        hashlife.set_cell(row as i64, col as i64, true).unwrap();
+    }
+    assert_eq!(hashlife.population(), 5);
+    universe.tick_many(200).unwrap();
+    hashlife.step(7).unwrap();
+    hashlife.step_by(72).unwrap();
+    assert_eq!(hashlife.generation(), 200);
+    let half = (side / 2) as i64;
+    let window = hashlife.window(-half, -half, side, side);
+    let expected: Vec<u8> = (0..(side * side) as usize)
+        .map(|idx| universe.get_cells()[idx] as u8)
+        .collect();
+    assert_eq!(window, expected);
+    assert_eq!(
+        hashlife.population(),
+        universe.get_cells().count_ones(..) as u64
+    );
+
+    // a glider travels a quarter cell per generation, however big the step
+    let glider = Pattern::from_cells("glider", vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    let mut hashlife = HashLifeUniverse::from_pattern(&glider);
+    hashlife.step(20).unwrap();
+    assert_eq!(hashlife.generation(), 1 << 20);
+    let shift = 1 << 18;
+    assert_eq!(
+        hashlife.bounding_box(),
+        vec![shift, shift, shift + 3, shift + 3]
+    );
+    assert_eq!(hashlife.to_pattern().unwrap().cells(), glider.cells());
+    hashlife.collect_garbage();
+    assert!(hashlife.get_cell(shift + 2, shift + 2));
+
+    let mut empty = HashLifeUniverse::new();
+    empty.step(40).unwrap();
+    assert_eq!(empty.population(), 0);
+    assert!(empty.bounding_box().is_empty());
+
+    #[cfg(feature = "rules")]
+    {
+        // non-totalistic rules look at the arrangement of each 3 x 3 block
+        let mut domino = HashLifeUniverse::new();
+        domino.set_rule(&Rule::parse("B2a/S").unwrap()).unwrap();
+        domino.set_cell(0, 0, true).unwrap();
+        domino.set_cell(0, 1, true).unwrap();
+        domino.tick().unwrap();
+        assert_eq!(domino.population(), 4);
+        assert!(domino.get_cell(-1, 0) && domino.get_cell(1, 1));
+
+        assert!(matches!(
+            hashlife.set_rule(&Rule::parse("B0/S8").unwrap()),
+            Err(Error::InvalidRule(_))
+        ));
+        hashlife.set_rule(&Rule::parse("B36/S23").unwrap()).unwrap();
+        assert_eq!(hashlife.rule().to_string(), "B36/S23");
+    }
+}
+
+#[cfg(feature = "hashlife")]
+#[wasm_bindgen_test]
+pub fn test_hashlife_limits() {
+    let glider = Pattern::from_cells("glider", vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    let mut hashlife = HashLifeUniverse::from_pattern(&glider);
+    assert!(matches!(hashlife.step(64), Err(Error::InvalidConfig(_))));
+    assert!(matches!(hashlife.step(60), Err(Error::InvalidConfig(_))));
+    // the largest step takes the glider to the far reaches of the plane
+    hashlife.step(59).unwrap();
+    let shift = 1 << 57;
+    assert!(hashlife.get_cell(shift + 2, shift + 2));
+    assert!(!hashlife.get_cell(i64::MAX, i64::MIN));
+    assert_eq!(hashlife.window(i64::MAX, i64::MAX, 2, 2), vec![0; 4]);
+    // until it would leave the plane
+    let steps = (1..10).find(|_| hashlife.step(59).is_err()).unwrap();
+    assert_eq!(hashlife.generation(), steps << 59);
+    assert_eq!(hashlife.population(), 5);
+
+    let mut hashlife = HashLifeUniverse::new();
+    assert!(hashlife.set_cell(i64::MAX, 0, true).is_err());
+    assert!(hashlife.set_cell(0, -(1 << 61) - 1, true).is_err());
+    hashlife.set_cell(-(1 << 61), (1 << 61) - 1, true).unwrap();
+    assert_eq!(hashlife.population(), 1);
+
+    // a tight budget is met by smaller steps, with the same outcome
+    let r_pentomino = Pattern::from_cells("", vec![(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)]);
+    let mut unlimited = HashLifeUniverse::from_pattern(&r_pentomino);
+    unlimited.step(9).unwrap();
+    let mut limited = HashLifeUniverse::from_pattern(&r_pentomino);
+    let mut limits = limited.limits();
+    limits.max_cache_bytes = 400 << 10;
+    limited.set_limits(limits);
+    limited.step(9).unwrap();
+    assert_eq!(limited.generation(), 512);
+    assert_eq!(limited.to_pattern(), unlimited.to_pattern());
+    assert!(limited.node_count() < unlimited.node_count());
+
+    limits.max_cache_bytes = 1 << 10;
+    limited.set_limits(limits);
+    assert!(matches!(limited.step(4), Err(Error::MemoryLimit { .. })));
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_bit_parallel_engine() {
+    assert_eq!(Universe::new(4, 4).engine(), Engine::BitParallel);
+    let sizes = [(1, 1), (2, 3), (5, 70), (64, 64), (33, 129)];
+    for &(height, width) in &sizes {
+        for &order in &[StorageOrder::RowMajor, StorageOrder::ColumnMajor] {
+            for &rule in &["B3/S23", "B36/S125", "B0/S8"] {
+                let soup = |engine| {
+                    let mut universe = Universe::new(height, width);
+                    universe.set_storage_order(order);
+                    universe.set_rulestring(rule).unwrap();
+                    universe.set_engine(engine);
+                    universe.randomize(0.4, &mut Entropy::seeded(99));
+                    universe
+                };
+                let (mut reference, mut universe) =
+                    (soup(Engine::Naive), soup(Engine::BitParallel));
+                for _ in 0..10 {
+                    reference.tick();
+                    universe.tick();
+                    assert_eq!(universe.get_cells(), reference.get_cells());
+                }
+                assert_eq!(universe.state_hash(), reference.state_hash());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_active_tiles_engine() {
+    let sizes = [(3, 5), (40, 70), (100, 130)];
+    for &(height, width) in &sizes {
+        for &topology in &[Topology::Torus, Topology::Dead, Topology::Mirror] {
+            let soup = |engine| {
+                let mut universe = Universe::new(height, width);
+                universe.set_topology(topology);
+                universe.set_engine(engine);
+                // a sparse soup that settles into mostly static regions
+                universe.randomize(0.2, &mut Entropy::seeded(21));
+                universe
+            };
+            let (mut reference, mut universe) = (soup(Engine::Naive), soup(Engine::ActiveTiles));
+            for generation in 0..40 {
+                // edits between ticks have to wake their tiles up
+                match generation {
+                    10 => {
+                        for u in [&mut reference, &mut universe].iter_mut() {
+                            u.set_cells(&[(1, 1), (1, 2), (1, 3)]).unwrap();
+                        }
+                    }
+                    20 => {
+                        for u in [&mut reference, &mut universe].iter_mut() {
+                            u.set_rulestring("B36/S23").unwrap();
+                        }
+                    }
+                    30 => {
+                        for u in [&mut reference, &mut universe].iter_mut() {
+                            u.toggle_cell(height - 1, width - 1).unwrap();
+                        }
+                    }
+                    _ => {}
+                }
+                reference.tick();
+                universe.tick();
+                assert_eq!(universe.get_cells(), reference.get_cells());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "native-parallel")]
+#[wasm_bindgen_test]
+pub fn test_parallel_tick() {
+    // large enough to be split between threads
+    for &topology in &[Topology::Torus, Topology::Dead, Topology::KleinBottle] {
+        let soup = |engine| {
+            let mut universe = Universe::new(300, 301);
+            universe.set_topology(topology);
+            universe.set_engine(engine);
+            universe.randomize(0.4, &mut Entropy::seeded(5));
+            universe
+        };
+        let (mut reference, mut universe) = (soup(Engine::Naive), soup(Engine::BitParallel));
+        for _ in 0..5 {
+            reference.tick();
+            universe.tick();
+            assert_eq!(universe.get_cells(), reference.get_cells());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_simd_engine() {
+    let mut universe = Universe::new_with_simd(5, 5, true).unwrap();
+    let expected = if simd_enabled() {
+        Engine::Simd
+    } else {
+        Engine::BitParallel
+    };
+    assert_eq!(universe.engine(), expected);
+    assert_eq!(
+        Universe::new_with_simd(5, 5, false).unwrap().engine(),
+        Engine::BitParallel
+    );
+    assert_eq!(Engine::available().contains(&Engine::Simd), simd_enabled());
+
+    // builds without it fall back, with the same generations either way
+    universe.set_engine(Engine::Simd);
+    assert_eq!(universe.engine(), expected);
+    let mut reference = Universe::new(40, 37);
+    reference.set_engine(Engine::Naive);
+    let mut universe = Universe::new_with_simd(40, 37, true).unwrap();
+    reference.randomize(0.35, &mut Entropy::seeded(3));
+    universe.randomize(0.35, &mut Entropy::seeded(3));
+    for _ in 0..20 {
+        reference.tick();
+        universe.tick();
+    }
+    assert_eq!(universe.get_cells(), reference.get_cells());
+}
+
+#[cfg(all(feature = "simd", feature = "rules", target_feature = "simd128"))]
+#[wasm_bindgen_test]
+pub fn test_simd_matches_bit_parallel() {
+    assert!(simd_enabled());
+    // widths below, at and past the 16 lanes of a vector, with a tail
+    for &(height, width) in &[(9, 7), (16, 16), (33, 50), (64, 130)] {
+        for rule in &["B3/S23", "B36/S23", "B2/S", "B3678/S34678"] {
+            let seeded = |engine| {
+                let mut universe = Universe::new(height, width);
+                universe.set_rulestring(rule).unwrap();
+                universe.randomize(0.4, &mut Entropy::seeded(width as u64));
+                universe.set_engine(engine);
+                universe
+            };
+            let (mut simd, mut reference) = (seeded(Engine::Simd), seeded(Engine::BitParallel));
+            assert_eq!(simd.engine(), Engine::Simd);
+            for _ in 0..30 {
+                simd.tick();
+                reference.tick();
+                assert_eq!(simd.get_cells(), reference.get_cells(), "{}", rule);
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_statistics() {
+    let mut universe = Universe::new(5, 5);
+    assert_eq!(universe.population(), 0);
+    universe
+        .set_cells(&[(2, 1), (2, 2), (2, 3), (2, 2)])
+        .unwrap();
+    assert_eq!(universe.population(), 3);
+
+    // a blinker flips two cells each way every tick
+    universe.tick();
+    assert_eq!(universe.generation(), 1);
+    assert_eq!(universe.population(), 3);
+    assert_eq!(
+        (universe.births_last_tick(), universe.deaths_last_tick()),
+        (2, 2)
+    );
+
+    universe.toggle_cell(0, 0).unwrap();
+    assert_eq!(universe.population(), 4);
+    universe.toggle_cell(0, 0).unwrap();
+    assert_eq!(universe.population(), 3);
+    universe.reset_cells();
+    assert_eq!(universe.population(), 0);
+    assert_eq!(universe.births_last_tick(), 0);
+
+    universe.randomize(0.5, &mut Entropy::seeded(5));
+    universe.tick_many(10).unwrap();
+    assert_eq!(
+        universe.population(),
+        universe.get_cells().count_ones(..) as u32
+    );
+    assert_eq!(universe.population(), universe.last_tick().population);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_deltas() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+    assert!(universe.deltas().is_empty());
+
+    // the blinker turns vertical: its ends die and cells above and below
+    // the middle are born
+    universe.tick();
+    assert_eq!(universe.deltas(), vec![7, 11, 13, 17]);
+    assert_eq!(universe.deltas_len(), 4);
+
+    let mut still = Universe::new(4, 4);
+    still.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]).unwrap();
+    still.tick();
+    assert_eq!(still.deltas_len(), 0);
+
+    universe.reset_cells();
+    assert!(universe.deltas().is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_topologies() {
+    let bar = [(4, 0), (4, 1), (4, 2)];
+    // the bar's births across the south edge land at row 0: above its
+    // middle on a torus, mirrored on a Klein bottle, nowhere when bounded
+    let expected = [
+        (Topology::Torus, vec![(0, 1), (3, 1), (4, 1)]),
+        (Topology::Dead, vec![(3, 1), (4, 1)]),
+        (Topology::KleinBottle, vec![(0, 4), (3, 1), (4, 1)]),
+    ];
+    for (topology, cells) in expected.iter() {
+        for &order in &[StorageOrder::RowMajor, StorageOrder::ColumnMajor] {
+            for &engine in &[Engine::Naive, Engine::BitParallel] {
+                let mut universe = Universe::new(5, 6);
+                universe.set_storage_order(order);
+                universe.set_engine(engine);
+                universe.set_topology(*topology);
+                universe.set_cells(&bar).unwrap();
+                universe.tick();
+                let mut reference = Universe::new(5, 6);
+                reference.set_storage_order(order);
+                reference.set_cells(cells).unwrap();
+                assert_eq!(universe.get_cells(), reference.get_cells());
+            }
+        }
+    }
+
+    // every engine and storage order agree on soups
+    let sizes = [(1, 1), (1, 7), (6, 2), (33, 70)];
+    let topologies = [
+        Topology::Torus,
+        Topology::Dead,
+        Topology::Mirror,
+        Topology::KleinBottle,
+    ];
+    for &(height, width) in &sizes {
+        for &topology in &topologies {
+            let soup = |engine, order| {
+                let mut universe = Universe::new(height, width);
+                universe.set_topology(topology);
+                universe.set_engine(engine);
+                universe.randomize(0.4, &mut Entropy::seeded(21));
+                universe.set_storage_order(order);
+                universe.tick_many(8).unwrap();
+                universe.set_storage_order(StorageOrder::RowMajor);
+                universe.get_cells().clone()
+            };
+            let reference = soup(Engine::Naive, StorageOrder::RowMajor);
+            assert_eq!(soup(Engine::Naive, StorageOrder::ColumnMajor), reference);
+            assert_eq!(soup(Engine::BitParallel, StorageOrder::RowMajor), reference);
+            assert_eq!(
+                soup(Engine::BitParallel, StorageOrder::ColumnMajor),
+                reference
+            );
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_step_back() {
+    let mut universe = input_spaceship();
+    assert!(!universe.step_back());
+    universe.set_history_capacity(3);
+    let mut states = vec![universe.get_cells().clone()];
+    for _ in 0..5 {
+        universe.tick();
+        states.push(universe.get_cells().clone());
+    }
+    assert_eq!(universe.history_len(), 3);
+    assert!(universe.history_bytes() > 0);
+
+    // back through the last three generations, and no further
+    for generation in (2..5).rev() {
+        assert!(universe.step_back());
+        assert_eq!(universe.generation(), generation);
+        assert_eq!(universe.get_cells(), &states[generation as usize]);
+    }
+    assert!(!universe.step_back());
+    assert_eq!(universe.state_hash(), {
+        let mut replay = input_spaceship();
+        replay.tick_many(2).unwrap();
+        replay.state_hash()
+    });
+
+    universe.step_forward();
+    assert_eq!(universe.generation(), 3);
+    assert_eq!(universe.get_cells(), &states[3]);
+    // stepping back flips the cells the tick flipped
+    let flipped = universe.deltas();
+    assert!(universe.step_back());
+    assert_eq!(universe.deltas(), flipped);
+
+    universe.set_history_capacity(0);
+    assert_eq!(universe.history_len(), 0);
+    universe.tick();
+    assert!(!universe.step_back());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_pattern() {
+    // an L: a vertical bar with a foot to the right
+    let l = Pattern::from_cells("L", vec![(0, 0), (1, 0), (2, 0), (2, 1)]);
+    let cells = |transform| l.oriented(transform).cells().to_vec();
+    assert_eq!(cells(Transform::Identity), l.cells().to_vec());
+    assert_eq!(
+        cells(Transform::Rotate90),
+        vec![(0, 0), (0, 1), (0, 2), (1, 0)]
+    );
+    assert_eq!(
+        cells(Transform::FlipVertical),
+        vec![(0, 0), (0, 1), (1, 0), (2, 0)]
+    );
+    assert_eq!(
+        cells(Transform::Transpose),
+        vec![(0, 0), (0, 1), (0, 2), (1, 2)]
+    );
+    // the L has no symmetry, so all eight orientations differ
+    let mut all: Vec<Vec<(u32, u32)>> = Transform::ALL.iter().map(|&t| cells(t)).collect();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), 8);
+
+    // stamped across the corner of the torus
+    let mut universe = Universe::new(5, 5);
+    universe
+        .insert_pattern(&l, 4, 4, Transform::Rotate180)
+        .unwrap();
+    let mut expected = Universe::new(5, 5);
+    expected
+        .set_cells(&[(4, 4), (4, 0), (0, 0), (1, 0)])
+        .unwrap();
+    assert_eq!(universe.get_cells(), expected.get_cells());
+    assert_eq!(
+        universe.insert_pattern(&l, 5, 0, Transform::Identity),
+        Err(Error::OutOfBounds {
+            row: 5,
+            column: 0,
+            height: 5,
+            width: 5
+        })
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_status() {
+    let mut universe = Universe::new(6, 6);
+    assert_eq!(universe.status().kind, StatusKind::Extinct);
+
+    // a blinker repeats every other generation
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+    assert_eq!(universe.status().kind, StatusKind::Running);
+    universe.tick();
+    assert_eq!(universe.status().kind, StatusKind::Running);
+    universe.tick();
+    assert_eq!(
+        universe.status(),
+        Status {
+            kind: StatusKind::Oscillating,
+            period: 2
+        }
+    );
+
+    // an edit starts over
+    universe.reset_cells();
+    universe
+        .set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)])
+        .unwrap();
+    assert_eq!(universe.status().kind, StatusKind::Running);
+    universe.tick();
+    assert_eq!(
+        universe.status(),
+        Status {
+            kind: StatusKind::StillLife,
+            period: 1
+        }
+    );
+
+    // a glider never repeats on a torus this big within the window
+    let mut glider = Universe::new(40, 40);
+    glider
+        .set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)])
+        .unwrap();
+    glider.tick_many(100).unwrap();
+    assert_eq!(glider.status().kind, StatusKind::Running);
+
+    let mut dying = Universe::new(6, 6);
+    dying.set_cells(&[(0, 0)]).unwrap();
+    dying.tick();
+    assert_eq!(dying.status().kind, StatusKind::Extinct);
+}
+
+#[wasm_bindgen_test]
+pub fn test_run_stops_early() {
+    // a blinker is recognized after one full period
+    let mut universe = Universe::new(6, 6);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+    assert_eq!(universe.run(1000), 2);
+    assert_eq!(universe.generation(), 2);
+    assert_eq!(universe.run(1000), 0);
+
+    let mut dying = Universe::new(6, 6);
+    dying.set_cells(&[(0, 0)]).unwrap();
+    assert_eq!(dying.run(10), 1);
+    assert_eq!(dying.status().kind, StatusKind::Extinct);
+
+    let mut glider = Universe::new(40, 40);
+    glider
+        .set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)])
+        .unwrap();
+    assert_eq!(glider.run(50), 50);
+
+    // at most the per call limit
+    let mut limits = glider.limits();
+    limits.max_generations_per_call = 5;
+    glider.set_limits(limits);
+    assert_eq!(glider.run(50), 5);
+    assert_eq!(glider.generation(), 55);
+}
+
+#[wasm_bindgen_test]
+pub fn test_cell_ages() {
+    let mut universe = Universe::new(8, 8);
+    assert!(universe.ages().is_null());
+    // a blinker next to a block
+    universe
+        .set_cells(&[(1, 1), (1, 2), (1, 3), (5, 5), (5, 6), (6, 5), (6, 6)])
+        .unwrap();
+    universe.set_track_ages(true);
+    assert_eq!(universe.ages_len(), 64);
+    assert_eq!(universe.max_age(), 1);
+
+    universe.tick_many(3).unwrap();
+    let ages = |universe: &Universe| {
+        let ptr = universe.ages();
+        (0..universe.ages_len())
+            .map(|idx| unsafe { *ptr.add(idx) })
+            .collect::<Vec<u16>>()
+    };
+    let current = ages(&universe);
+    // the block and the middle of the blinker survived every tick, the
+    // blinker's ends were born last tick
+    assert_eq!(current[5 * 8 + 5], 4);
+    assert_eq!(current[8 + 2], 4);
+    assert_eq!((current[2], current[2 * 8 + 2]), (1, 1));
+    assert_eq!(current[8 + 1], 0);
+    assert_eq!(universe.max_age(), 4);
+
+    // edits and layout changes keep the ages attached to their cells
+    universe.toggle_cell(3, 0).unwrap();
+    universe.set_storage_order(StorageOrder::ColumnMajor);
+    let transposed = ages(&universe);
+    assert_eq!(transposed[5 * 8 + 5], 4);
+    assert_eq!(transposed[3], 1);
+    assert_eq!(transposed[2 * 8 + 1], 4);
+
+    universe.reset_cells();
+    assert_eq!(universe.max_age(), 0);
+    universe.set_track_ages(false);
+    assert_eq!(universe.ages_len(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_resize_keeps_cells() {
+    let mut universe = input_spaceship();
+    universe.tick();
+    universe.resize(10, 8, Anchor::TopLeft).unwrap();
+    assert_eq!((universe.width(), universe.height()), (10, 8));
+    assert_eq!(universe.generation(), 0);
+    let mut expected = Universe::new(8, 10);
+    expected
+        .set_cells(&[(2, 1), (2, 3), (3, 2), (3, 3), (4, 2)])
+        .unwrap();
+    assert_eq!(universe.get_cells(), expected.get_cells());
+    assert_eq!(universe.population(), 5);
+
+    // centred growth and cropping in column-major order
+    let mut universe = Universe::new(3, 3);
+    universe.set_storage_order(StorageOrder::ColumnMajor);
+    universe.set_cells(&[(0, 0), (1, 1), (2, 2)]).unwrap();
+    universe.resize(5, 5, Anchor::Center).unwrap();
+    let mut expected = Universe::new(5, 5);
+    expected.set_storage_order(StorageOrder::ColumnMajor);
+    expected.set_cells(&[(1, 1), (2, 2), (3, 3)]).unwrap();
+    assert_eq!(universe.get_cells(), expected.get_cells());
+    universe.resize(1, 1, Anchor::Center).unwrap();
+    assert_eq!(universe.population(), 1);
+
+    // the initial state moves with the cells
+    let mut universe = Universe::new(0, 0);
+    universe
+        .fit_to_pattern(&Pattern::from_cells("dot", vec![(0, 0)]), 1)
+        .unwrap();
+    universe.resize(5, 5, Anchor::Center).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 0);
+    universe.reset_init_state();
+    assert_eq!(universe.get_cells().ones().collect::<Vec<_>>(), vec![12]);
+
+    let mut small = Universe::new_preallocated(4, 4, 16).unwrap();
+    assert!(small.resize(5, 5, Anchor::Center).is_err());
+}
+
+#[cfg(feature = "render")]
+#[wasm_bindgen_test]
+pub fn test_render_rgba() {
+    let mut universe = Universe::new(2, 3);
+    universe.set_cells(&[(0, 1), (1, 2)]).unwrap();
+    universe.set_storage_order(StorageOrder::ColumnMajor);
+
+    let pixels = |universe: &mut Universe| {
+        let ptr = universe.render_rgba();
+        unsafe { std::slice::from_raw_parts(ptr, universe.rgba_len()) }.to_vec()
+    };
+    let rgba = pixels(&mut universe);
+    assert_eq!(rgba.len(), 2 * 3 * 4);
+    // row by row whatever the storage order
+    let white = [255, 255, 255, 255];
+    let black = [0, 0, 0, 255];
+    let expected: Vec<u8> = [white, black, white, white, white, black].concat();
+    assert_eq!(rgba, expected);
+
+    // live cells fade from red to blue over two generations
+    let mut block = Universe::new(4, 4);
+    block.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]).unwrap();
+    block.set_track_ages(true);
+    let mut palette = Palette::new();
+    palette.alive = 0xff00_00ff;
+    palette.old = 0x0000_ffff;
+    palette.gradient_span = 2;
+    block.set_palette(palette);
+    let at = |rgba: &[u8]| rgba[(4 + 1) * 4..][..4].to_vec();
+    assert_eq!(at(&pixels(&mut block)), [255, 0, 0, 255]);
+    block.tick();
+    assert_eq!(at(&pixels(&mut block)), [127, 0, 127, 255]);
+    block.tick_many(5).unwrap();
+    assert_eq!(at(&pixels(&mut block)), [0, 0, 255, 255]);
+    assert_eq!(pixels(&mut block)[..4], [255, 255, 255, 255]);
+}
+
+#[cfg(feature = "sparse")]
+#[wasm_bindgen_test]
+pub fn test_sparse_universe() {
+    // the same soup on dense and sparse boards, with partial edge tiles
+    for &(height, width) in &[(70, 130), (64, 64), (5, 9)] {
+        let dense = Universe::new_seeded(height, width, 0.35, 11).unwrap();
+        let mut sparse = SparseUniverse::new(height, width);
+        let live: Vec<(u32, u32)> = dense
+            .get_cells()
+            .ones()
+            .map(|idx| (idx as u32 / width, idx as u32 % width))
+            .collect();
+        sparse.set_cells(&live).unwrap();
+        let mut dense = dense;
+        dense.set_rulestring("B36/S23").unwrap();
+        sparse.set_rulestring("B36/S23").unwrap();
+        for _ in 0..12 {
+            dense.tick();
+            sparse.tick();
+            let cells = sparse.cells();
+            let bytes = unsafe { std::slice::from_raw_parts(cells, sparse.cells_byte_len()) };
+            for idx in 0..(height * width) as usize {
+                assert_eq!(bytes[idx / 8] >> (idx % 8) & 1 == 1, dense.get_cells()[idx]);
+            }
+        }
+        assert_eq!(sparse.population(), dense.population());
+    }
+
+    // a glider on a huge board only ever touches a few tiles
+    let mut huge = SparseUniverse::new(10_000, 10_000);
+    huge.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)])
+        .unwrap();
+    huge.tick_many(400);
+    assert_eq!(huge.population(), 5);
+    assert!(huge.tile_count() <= 4);
+    assert_eq!(huge.get_cell(101, 102), Ok(true));
+    assert_eq!(huge.live_cells().len(), 10);
+
+    assert!(huge.set_rulestring("B03/S23").is_err());
+    assert!(huge.set_rulestring("B2-a/S12").is_err());
+    assert!(huge.toggle_cell(10_000, 0).is_err());
+    assert_eq!(
+        huge.get_cell(0, 10_000),
+        Err(Error::OutOfBounds {
+            row: 0,
+            column: 10_000,
+            width: 10_000,
+            height: 10_000
+        })
+    );
+    assert!(SparseUniverse::new(0, 5).get_cell(0, 0).is_err());
+}
+
+#[cfg(feature = "generations")]
+#[wasm_bindgen_test]
+pub fn test_generations_rules() {
+    let brain = GenerationsRule::parse("/2/3").unwrap();
+    assert_eq!(brain, GenerationsRule::brians_brain());
+    assert_eq!(brain.to_string(), "B2/S/C3");
+    let star_wars = GenerationsRule::parse("345/2/4").unwrap();
+    assert_eq!(star_wars, GenerationsRule::parse("B2/S345/C4").unwrap());
+    assert_eq!(star_wars.states(), 4);
+    assert!(GenerationsRule::parse("23/3").is_err());
+    assert!(GenerationsRule::parse("/2/1").is_err());
+
+    // two live cells give birth to four, and fade out themselves
+    let mut universe = GenerationsUniverse::new(6, 6, &brain).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3)]).unwrap();
+    universe.tick();
+    let states = |u: &GenerationsUniverse| {
+        unsafe { std::slice::from_raw_parts(u.cell_states(), u.cell_states_len()) }.to_vec()
+    };
+    let mut expected = vec![0; 36];
+    for &idx in &[8, 9, 20, 21] {
+        expected[idx] = 1;
+    }
+    expected[14] = 2;
+    expected[15] = 2;
+    assert_eq!(states(&universe), expected);
+    assert_eq!(universe.population(), 4);
+    universe.tick();
+    assert_eq!(universe.get_state(2, 2).unwrap(), 0);
+
+    assert!(universe.set_state(0, 0, 3).is_err());
+    assert!(universe.get_state(6, 0).is_err());
+}
+
+#[cfg(feature = "wireworld")]
+#[wasm_bindgen_test]
+pub fn test_wireworld() {
+    // an electron running east along a wire, off the end of it
+    let mut world = Wireworld::new(3, 6).unwrap();
+    let wire: Vec<_> = (0..6).map(|col| (1, col)).collect();
+    world.set_cells(&wire, WireState::Conductor).unwrap();
+    world.set_cell(1, 0, WireState::ElectronTail).unwrap();
+    world.set_cell(1, 1, WireState::ElectronHead).unwrap();
+    world.tick();
+    assert_eq!(world.get_cell(1, 0).unwrap(), WireState::Conductor);
+    assert_eq!(world.get_cell(1, 1).unwrap(), WireState::ElectronTail);
+    assert_eq!(world.get_cell(1, 2).unwrap(), WireState::ElectronHead);
+    assert_eq!(world.get_cell(0, 2).unwrap(), WireState::Empty);
+    world.tick_many(4);
+    assert_eq!(world.electron_count(), 0);
+    assert_eq!(world.get_cell(1, 5).unwrap(), WireState::ElectronTail);
+    assert_eq!(world.generation(), 5);
+
+    // a conductor next to three heads stays put
+    let mut world = Wireworld::new(3, 3).unwrap();
+    world.set_cell(1, 1, WireState::Conductor).unwrap();
+    for col in 0..3 {
+        world.set_cell(0, col, WireState::ElectronHead).unwrap();
+    }
+    world.tick();
+    assert_eq!(world.get_cell(1, 1).unwrap(), WireState::Conductor);
+    world.clear_electrons();
+    assert_eq!(world.get_cell(0, 0).unwrap(), WireState::Conductor);
+    assert!(world.set_cell(3, 0, WireState::Conductor).is_err());
+}
+
+#[cfg(feature = "langton")]
+#[wasm_bindgen_test]
+pub fn test_langton_ant() {
+    let mut langton = Langton::new(5, 5).unwrap();
+    assert_eq!(langton.add_ant(2, 2, Direction::North).unwrap(), 0);
+    assert!(langton.add_ant(5, 0, Direction::North).is_err());
+
+    // four right turns on white cells lead back to the start, now black
+    langton.tick_many(4);
+    assert_eq!(langton.ant_positions(), vec![2, 2]);
+    assert_eq!(langton.ant_direction(0).unwrap(), Direction::North);
+    for &(row, col) in [(2, 2), (2, 3), (3, 3), (3, 2)].iter() {
+        assert!(langton.get_cell(row, col).unwrap());
+    }
+    langton.tick();
+    assert_eq!(langton.ant_direction(0).unwrap(), Direction::West);
+    assert_eq!(
+        (langton.ant_row(0).unwrap(), langton.ant_column(0).unwrap()),
+        (2, 1)
+    );
+    assert!(!langton.get_cell(2, 2).unwrap());
+    assert!(langton.ant_row(1).is_err());
+
+    // the ant walks off the west edge onto the east one
+    let mut langton = Langton::new(3, 3).unwrap();
+    langton.toggle_cell(1, 0).unwrap();
+    langton.add_ant(1, 0, Direction::North).unwrap();
+    langton.tick();
+    assert_eq!(langton.ant_positions(), vec![1, 2]);
+    let cells = unsafe { std::slice::from_raw_parts(langton.cells(), langton.cells_byte_len()) };
+    assert_eq!(cells, &[0, 0]);
+}
+
+#[cfg(feature = "elementary")]
+#[wasm_bindgen_test]
+pub fn test_elementary_rule_90() {
+    // Rule 90 draws a Sierpinski triangle from a single cell
+    let mut elementary = Elementary::new(4, 7, 90).unwrap();
+    elementary.tick_many(3);
+    let rows: Vec<String> = (0..4)
+        .map(|row| {
+            (0..7)
+                .map(|col| {
+                    if elementary.get_cell(row, col).unwrap() {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(rows, vec!["...#...", "..#.#..", ".#...#.", "#.#.#.#"]);
+
+    let bytes = unsafe {
+        std::slice::from_raw_parts(elementary.cells(), elementary.cells_byte_len()).to_vec()
+    };
+    assert_eq!(bytes.len(), 4);
+    assert_eq!(bytes[0], 0b1000); // cell 3 of the oldest row
+
+    // a new rule applies from the next tick, scrolling the oldest row out
+    elementary.set_rule(0);
+    elementary.tick();
+    assert!(elementary.get_cell(0, 2).unwrap());
+    assert!((0..7).all(|col| !elementary.get_cell(3, col).unwrap()));
+    assert!(elementary.set_cell(7, true).is_err());
+}
+
+#[cfg(feature = "ltl")]
+#[wasm_bindgen_test]
+pub fn test_larger_than_life() {
+    let bugs = LtlRule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+    assert_eq!(bugs, LtlRule::bugs());
+    assert_eq!(bugs.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+    assert!(LtlRule::parse("R5,C3,M1,S34..58,B34..45,NM").is_err());
+    assert!(LtlRule::parse("R0,S1,B1").is_err());
+    assert!(LtlRule::parse("R2,S3..1,B1").is_err());
+
+    // radius 1 with B3 and S2..3 is Conway's Life
+    let life = LtlRule::parse("R1,M0,S2..3,B3").unwrap();
+    let (height, width) = (9, 11);
+    let mut dense = Universe::new_seeded(height, width, 0.4, 3).unwrap();
+    let mut ltl = LargerThanLifeUniverse::new(height, width, &life).unwrap();
+    let live: Vec<(u32, u32)> = dense
+        .get_cells()
+        .ones()
+        .map(|idx| (idx as u32 / width, idx as u32 % width))
+        .collect();
+    ltl.set_cells(&live).unwrap();
+    for _ in 0..8 {
+        dense.tick();
+        ltl.tick();
+        for idx in 0..(height * width) {
+            let cell = ltl.get_cell(idx / width, idx % width).unwrap();
+            assert_eq!(cell, dense.get_cells()[idx as usize]);
+        }
+    }
+
+    // radius 2 against counting the neighbours one by one
+    let rule = LtlRule::new(2, 6, 9, 5, 12, true).unwrap();
+    let mut ltl = LargerThanLifeUniverse::new(7, 8, &rule).unwrap();
+    ltl.randomize(0.4, &mut Entropy::seeded(5));
+    for _ in 0..4 {
+        let mut expected = Vec::new();
+        for row in 0..7i32 {
+            for col in 0..8i32 {
+                let mut count = 0;
+                for dr in -2..=2 {
+                    for dc in -2..=2 {
+                        let (r, c) = ((row + dr).rem_euclid(7), (col + dc).rem_euclid(8));
+                        count += ltl.get_cell(r as u32, c as u32).unwrap() as u32;
+                    }
+                }
+                let range = if ltl.get_cell(row as u32, col as u32).unwrap() {
+                    5..=12
+                } else {
+                    6..=9
+                };
+                expected.push(range.contains(&count));
+            }
+        }
+        ltl.tick();
+        let actual: Vec<bool> = (0..56)
+            .map(|idx| ltl.get_cell(idx / 8, idx % 8).unwrap())
+            .collect();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_neighborhoods() {
+    let live = |universe: &Universe| -> Vec<(u32, u32)> {
+        let width = universe.width();
+        universe
+            .get_cells()
+            .ones()
+            .map(|idx| (idx as u32 / width, idx as u32 % width))
+            .collect()
+    };
+    let mut universe = Universe::with_rule(5, 5, "B2/S").unwrap();
+    universe.set_topology(Topology::Dead);
+    assert_eq!(universe.neighborhood(), Neighborhood::Moore);
+
+    // diagonal neighbours stop counting
+    universe.set_cells(&[(2, 1), (2, 3)]).unwrap();
+    universe.set_neighborhood(Neighborhood::VonNeumann);
+    universe.tick();
+    assert_eq!(live(&universe), vec![(2, 2)]);
+
+    // in odd rows, the neighbours above and below lie to the east
+    universe.reset_cells();
+    universe.set_cells(&[(1, 1), (1, 2)]).unwrap();
+    universe.set_neighborhood(Neighborhood::Hexagonal);
+    assert_eq!(universe.neighborhood(), Neighborhood::Hexagonal);
+    universe.tick();
+    assert_eq!(live(&universe), vec![(0, 2), (2, 2)]);
+
+    // Moore again, with the default engine
+    universe.set_neighborhood(Neighborhood::Moore);
+    universe.tick();
+    assert_eq!(live(&universe), vec![(1, 1), (1, 2), (1, 3)]);
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_non_totalistic_rules() {
+    let rule = Rule::parse("B2-a/S12").unwrap();
+    assert!(!rule.is_totalistic());
+    assert_eq!(rule.to_string(), "B2-a/S12");
+    assert_eq!(Rule::parse("b2ceikn/s12").unwrap(), rule);
+    assert_eq!(Rule::parse("12/2-a").unwrap(), rule);
+    // listing every letter is the totalistic rule
+    let life = Rule::parse("B3/S2ceaikn3").unwrap();
+    assert!(life.is_totalistic());
+    assert_eq!(life, Rule::conway());
+    assert_eq!(Rule::parse("B2ceaik/S").unwrap().to_string(), "B2-n/S");
+    assert_eq!(Rule::parse("B2ce/S4-t").unwrap().to_string(), "B2ce/S4-t");
+    for bad in ["B2x/S", "B1a/S", "B2-/S3", "B8c/S"].iter() {
+        assert!(Rule::parse(bad).is_err(), "{}", bad);
+    }
+    assert_eq!(rule.complement().complement(), rule);
+    assert!(!rule.complement().is_totalistic());
+
+    // a domino's neighbours above and below see it as 2a, those at its
+    // ends as 1e
+    let born = |rulestring: &str| {
+        let mut universe = Universe::with_rule(6, 6, rulestring).unwrap();
+        universe.set_topology(Topology::Dead);
+        universe.set_cells(&[(2, 2), (2, 3)]).unwrap();
+        universe.tick();
+        let cells = universe.get_cells();
+        (0..36).filter(|&idx| cells[idx]).collect::<Vec<_>>()
+    };
+    assert_eq!(born("B2a/S"), vec![8, 9, 20, 21]);
+    assert_eq!(born("B2/S"), vec![8, 9, 20, 21]);
+    assert!(born("B2-a/S").is_empty());
+    assert_eq!(born("B1e/S"), vec![13, 16]);
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_non_totalistic_snapshots() {
+    let mut universe = Universe::with_rule(5, 7, "B2-a/S12").unwrap();
+    universe.set_cells(&[(1, 1), (2, 2), (3, 5)]).unwrap();
+    let restored = Universe::from_snapshot_bytes(&universe.to_snapshot_bytes()).unwrap();
+    assert_eq!(restored.rule(), universe.rule());
+    assert_eq!(restored.get_cells(), universe.get_cells());
+    let shared = Universe::from_share_string(&universe.to_share_string()).unwrap();
+    assert_eq!(shared.rule(), universe.rule());
+    assert_eq!(shared.get_cells(), universe.get_cells());
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_transition_tables_follow_the_rule() {
+    // the naive engine's tables are rebuilt when the rule or the
+    // neighbourhood changes between ticks
+    let run = |engine| {
+        let mut universe = Universe::new(24, 30);
+        universe.set_engine(engine);
+        universe.randomize(0.35, &mut Entropy::seeded(5));
+        universe.tick_many(3).unwrap();
+        universe.set_rulestring("B36/S23").unwrap();
+        universe.tick_many(3).unwrap();
+        universe.set_neighborhood(Neighborhood::VonNeumann);
+        universe.set_rulestring("B1/S1").unwrap();
+        universe.tick();
+        universe.set_neighborhood(Neighborhood::Moore);
+        universe.tick_many(2).unwrap();
+        universe.get_cells().clone()
+    };
+    assert_eq!(run(Engine::Naive), run(Engine::BitParallel));
+}
+
+#[wasm_bindgen_test]
+pub fn test_noise() {
+    let blinker = || {
+        let mut universe = Universe::new(5, 5);
+        universe.set_cells(&[(2, 1), (2, 2), (2, 3)]).unwrap();
+        universe
+    };
+    // certain births and survivals are the deterministic rule
+    let (mut reference, mut universe) = (blinker(), blinker());
+    universe.set_noise(1.0, 1.0, 3).unwrap();
+    reference.tick_many(4).unwrap();
+    universe.tick_many(4).unwrap();
+    assert_eq!(universe.get_cells(), reference.get_cells());
+
+    // without births the blinker dies, keeping only its middle once
+    universe.set_noise(0.0, 1.0, 3).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 1);
+    universe.tick();
+    assert_eq!(universe.population(), 0);
+
+    // the same seed gives the same run
+    let noisy = |seed| {
+        let mut universe = Universe::new(32, 32);
+        universe.randomize(0.4, &mut Entropy::seeded(8));
+        universe.set_engine(Engine::ActiveTiles);
+        universe.set_noise(0.9, 0.98, seed).unwrap();
+        universe.tick_many(10).unwrap();
+        universe.get_cells().clone()
+    };
+    assert_eq!(noisy(1), noisy(1));
+    assert_ne!(noisy(1), noisy(2));
+
+    assert!(universe.set_noise(1.5, 1.0, 0).is_err());
+    assert_eq!(universe.birth_probability(), 0.0);
+    universe.clear_noise();
+    assert_eq!(universe.birth_probability(), 1.0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_copy_cut_paste() {
+    let mut universe = Universe::new(10, 12);
+    let glider = [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)];
+    universe.set_cells(&glider).unwrap();
+    universe.set_cells(&[(8, 10)]).unwrap();
+
+    let copied = universe.copy_region(0, 0, 5, 5).unwrap();
+    assert_eq!(
+        (copied.width(), copied.height(), copied.population()),
+        (3, 3, 5)
+    );
+    assert_eq!(universe.population(), 6);
+
+    let cut = universe.cut_region(0, 0, 5, 5).unwrap();
+    assert_eq!(cut, copied);
+    assert_eq!(universe.population(), 1);
+
+    // pasting over live cells replaces them, dead cells included
+    universe.set_cells(&[(5, 5), (6, 6)]).unwrap();
+    universe.paste(&cut, 5, 5).unwrap();
+    let pasted: Vec<_> = glider.iter().map(|&(r, c)| (r + 4, c + 4)).collect();
+    let mut expected = Universe::new(10, 12);
+    expected.set_cells(&pasted).unwrap();
+    expected.set_cells(&[(8, 10)]).unwrap();
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    assert!(universe.copy_region(8, 8, 3, 3).is_err());
+    assert!(universe.paste(&cut, 8, 10).is_err());
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[cfg(feature = "formats")]
+#[wasm_bindgen_test]
+pub fn test_copied_region_round_trips_through_rle() {
+    let mut universe = Universe::new(8, 8);
+    universe
+        .set_cells(&[(2, 3), (3, 4), (4, 2), (4, 3), (4, 4)])
+        .unwrap();
+    let copied = universe.copy_region(1, 1, 6, 6).unwrap();
+    let restored = Pattern::from_rle(&copied.to_rle()).unwrap();
+    assert_eq!(restored.cells(), copied.cells());
+    universe.reset_cells();
+    universe.paste(&restored, 2, 2).unwrap();
+    assert_eq!(universe.population(), 5);
+    assert!(universe.get_cells()[2 * 8 + 3]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_drawing_primitives() {
+    let live = |universe: &Universe| {
+        let cells = universe.get_cells();
+        let width = universe.width() as usize;
+        cells
+            .ones()
+            .map(|idx| ((idx / width) as u32, (idx % width) as u32))
+            .collect::<Vec<_>>()
+    };
+    let mut universe = Universe::new(9, 9);
+
+    universe.draw_line(0, 0, 3, 6, true).unwrap();
+    assert_eq!(
+        live(&universe),
+        vec![(0, 0), (1, 1), (1, 2), (2, 3), (2, 4), (3, 5), (3, 6)]
+    );
+    universe.draw_line(0, 0, 3, 6, false).unwrap();
+    assert_eq!(universe.population(), 0);
+    universe.draw_line(8, 2, 8, 2, true).unwrap();
+    assert_eq!(live(&universe), vec![(8, 2)]);
+    assert!(universe.draw_line(0, 0, 9, 0, true).is_err());
+
+    universe.reset_cells();
+    universe.outline_rect(1, 1, 3, 4, true).unwrap();
+    assert_eq!(universe.population(), 10);
+    assert!(!universe.get_cells()[2 * 9 + 2]);
+    universe.fill_rect(1, 1, 3, 4, true).unwrap();
+    assert_eq!(universe.population(), 12);
+    universe.fill_rect(0, 0, 9, 9, false).unwrap();
+    assert_eq!(universe.population(), 0);
+    assert!(universe.fill_rect(5, 5, 5, 1, true).is_err());
+
+    universe.fill_circle(4, 4, 2, true).unwrap();
+    assert_eq!(universe.population(), 21);
+    universe.fill_circle(4, 4, 0, false).unwrap();
+    assert_eq!(universe.population(), 20);
+    // clipped at the edges
+    universe.reset_cells();
+    universe.fill_circle(0, 0, 2, true).unwrap();
+    assert_eq!(universe.population(), 8);
+}
+
+#[wasm_bindgen_test]
+pub fn test_paint_and_brushes() {
+    let mut universe = Universe::new(8, 8);
+    universe.paint(&[0, 0, 3, 4, 7, 7], true).unwrap();
+    assert_eq!(universe.population(), 3);
+    assert!(universe.get_cells()[3 * 8 + 4]);
+    universe.paint(&[3, 4], false).unwrap();
+    assert_eq!(universe.population(), 2);
+    // nothing changes when a cell is outside or a column is missing
+    assert!(universe.paint(&[1, 1, 8, 0], true).is_err());
+    assert!(universe.paint(&[1, 1, 2], true).is_err());
+    assert_eq!(universe.population(), 2);
+
+    universe.reset_cells();
+    assert_eq!(universe.brush_radius(), 0);
+    universe.paint_at(4, 4, true).unwrap();
+    assert_eq!(universe.population(), 1);
+    universe.set_brush(BrushShape::Square, 1);
+    universe.paint_at(4, 4, true).unwrap();
+    assert_eq!(universe.population(), 9);
+    universe.paint_at(0, 0, true).unwrap();
+    assert_eq!(universe.population(), 13);
+    universe.set_brush(BrushShape::Round, 1);
+    assert_eq!(universe.brush_shape(), BrushShape::Round);
+    universe.paint_at(4, 4, false).unwrap();
+    assert_eq!(universe.population(), 8);
+    assert!(universe.paint_at(8, 0, true).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_from_javascript() {
+    let mut universe = Universe::new(4, 6);
+    universe.set_cells_js(&[0, 1, 3, 5]).unwrap();
+    assert_eq!(universe.cell(0, 1), Ok(true));
+    assert_eq!(universe.cell(3, 5), Ok(true));
+    assert_eq!(universe.cell(1, 1), Ok(false));
+    assert!(universe.cell(4, 0).is_err());
+    assert!(universe.cell(0, 6).is_err());
+    assert!(universe.set_cells_js(&[1, 1, 0, 6]).is_err());
+    assert!(universe.set_cells_js(&[1]).is_err());
+    assert_eq!(universe.population(), 2);
+
+    // hibernating universes answer from their compressed cells
+    universe.hibernate();
+    assert_eq!(universe.cell(3, 5), Ok(true));
+}
+
+#[wasm_bindgen_test]
+pub fn test_dimensions_are_validated() {
+    assert_eq!(
+        Universe::try_new(70_000, 70_000).err(),
+        Some(Error::DimensionsTooLarge {
+            width: 70_000,
+            height: 70_000
+        })
+    );
+    #[cfg(feature = "rules")]
+    assert!(Universe::with_rule(70_000, 70_000, "B3/S23").is_err());
+    assert!(matches!(
+        Universe::new_seeded(70_000, 70_000, 0.5, 1),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+    assert!(matches!(
+        Universe::new_with_simd(70_000, 70_000, true),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+    #[cfg(feature = "elementary")]
+    assert!(matches!(
+        Elementary::new(70_000, 70_000, 90),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+    #[cfg(feature = "langton")]
+    assert!(matches!(
+        Langton::new(70_000, 70_000),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+    #[cfg(feature = "ltl")]
+    assert!(matches!(
+        LargerThanLifeUniverse::with_rule(70_000, 70_000, "R1,C0,M0,S2..3,B3..3,NM"),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+    #[cfg(feature = "wireworld")]
+    assert!(matches!(
+        Wireworld::new(70_000, 70_000),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+    #[cfg(feature = "generations")]
+    assert!(matches!(
+        GenerationsUniverse::with_rule(70_000, 70_000, "/2/3"),
+        Err(Error::DimensionsTooLarge { .. })
+    ));
+    let mut universe = Universe::try_new(70_000, 1).unwrap();
+    assert!(universe.set_width(70_000).is_err());
+    assert_eq!(universe.width(), 1);
+    assert!(universe.resize(70_000, 70_000, Anchor::TopLeft).is_err());
+
+    // empty universes tick and edit without panicking
+    let mut empty = Universe::try_new(0, 5).unwrap();
+    empty.tick();
+    assert!(empty.toggle_cell(0, 0).is_err());
+    assert!(empty.cell(0, 0).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_views() {
+    let mut universe = Universe::new(5, 7);
+    universe.set_cells(&[(0, 0), (1, 2), (4, 6)]).unwrap();
+    let bytes = universe.cells_u8();
+    assert_eq!(bytes.len(), universe.cells_byte_len());
+    assert_eq!(bytes[0], 1);
+    assert_eq!(bytes[1], 1 << 1);
+    assert_eq!(bytes[4], 1 << 2);
+
+    let view = universe.cells_view();
+    assert_eq!(view.ptr(), universe.cells() as usize);
+    assert_eq!(view.byte_len(), bytes.len());
+    assert_eq!(
+        view.memory_generation(),
+        wasm_game_of_life::memory_generation()
+    );
+
+    // copies survive hibernation
+    universe.hibernate();
+    assert_eq!(universe.cells_u8(), bytes);
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_save_states() {
+    let mut universe = Universe::new(12, 12);
+    universe
+        .set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)])
+        .unwrap();
+    universe.tick_many(4).unwrap();
+    universe.save_snapshot("glider at 4");
+    let saved = universe.get_cells().clone();
+    universe.tick_many(6).unwrap();
+    universe.set_rulestring("B36/S23").unwrap();
+    universe.save_snapshot("later");
+    assert_eq!(universe.list_snapshots(), vec!["glider at 4", "later"]);
+    assert!(universe.snapshots_byte_len() > 0);
+
+    universe.load_snapshot("glider at 4").unwrap();
+    assert_eq!(universe.generation(), 4);
+    assert_eq!(universe.get_cells(), &saved);
+    assert_eq!(universe.population(), 5);
+    assert_eq!(universe.rule(), Rule::conway());
+
+    // save-states survive a change of storage order
+    universe.set_storage_order(StorageOrder::ColumnMajor);
+    universe.load_snapshot("glider at 4").unwrap();
+    universe.set_storage_order(StorageOrder::RowMajor);
+    assert_eq!(universe.get_cells(), &saved);
+
+    assert!(universe.delete_snapshot("later"));
+    assert!(!universe.delete_snapshot("later"));
+    assert_eq!(
+        universe.load_snapshot("later"),
+        Err(Error::NoSaveState("later".to_owned()))
+    );
+    universe.resize(13, 12, Anchor::TopLeft).unwrap();
+    assert!(universe.load_snapshot("glider at 4").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_commit_init_state() {
+    let mut universe = Universe::new_seeded(16, 16, 0.3, 4).unwrap();
+    let soup = universe.get_cells().clone();
+    universe.reset_cells();
+    universe
+        .set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)])
+        .unwrap();
+    // reset still goes back to the soup
+    universe.reset_init_state();
+    assert_eq!(universe.get_cells(), &soup);
+
+    universe.reset_cells();
+    universe
+        .set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)])
+        .unwrap();
+    universe.commit_init_state();
+    universe.tick_many(3).unwrap();
+    assert_eq!(universe.generation(), 3);
+    universe.set_cells(&[(8, 8)]).unwrap();
+    universe.reset_init_state();
+    assert_eq!(universe.population(), 4);
+
+    // with edits following, the baseline is curated as it is drawn
+    universe.set_edits_update_init_state(true);
+    universe.toggle_cell(1, 1).unwrap();
+    universe.draw_line(5, 5, 5, 7, true).unwrap();
+    universe.set_cells(&[(10, 10)]).unwrap();
+    universe.tick_many(2).unwrap();
+    universe.reset_init_state();
+    assert_eq!(universe.population(), 7);
+    assert!(!universe.cell(1, 1).unwrap());
+}
+
+#[wasm_bindgen_test]
+pub fn test_live_cells() {
+    let mut universe = Universe::new(40, 70);
+    assert!(universe.live_cells().is_empty());
+    universe.set_cells(&[(0, 69), (3, 1), (39, 0)]).unwrap();
+    assert_eq!(universe.live_cells(), vec![0, 69, 3, 1, 39, 0]);
+    universe.set_storage_order(StorageOrder::ColumnMajor);
+    assert_eq!(universe.live_cells(), vec![39, 0, 3, 1, 0, 69]);
+    universe.hibernate();
+    assert_eq!(universe.live_cells().len(), 6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_bounding_box() {
+    let mut universe = Universe::new(20, 30);
+    assert!(universe.bounding_box().is_empty());
+    universe.set_cells(&[(4, 17)]).unwrap();
+    assert_eq!(universe.bounding_box(), vec![4, 17, 5, 18]);
+    universe.set_cells(&[(2, 20), (9, 3)]).unwrap();
+    assert_eq!(universe.bounding_box(), vec![2, 3, 10, 21]);
+    universe.set_storage_order(StorageOrder::ColumnMajor);
+    assert_eq!(universe.bounding_box(), vec![2, 3, 10, 21]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_shift_rotate_and_flip() {
+    let live = |universe: &Universe| {
+        let mut cells = universe
+            .live_cells()
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>();
+        cells.sort_unstable();
+        cells
+    };
+    let mut universe = Universe::new(4, 6);
+    universe.set_cells(&[(0, 0), (1, 5), (3, 2)]).unwrap();
+    universe.commit_init_state();
+
+    universe.shift(1, -1, true);
+    assert_eq!(live(&universe), vec![(0, 1), (1, 5), (2, 4)]);
+    universe.shift(-1, 1, true);
+    assert_eq!(live(&universe), vec![(0, 0), (1, 5), (3, 2)]);
+    universe.shift(1, 1, false);
+    assert_eq!(live(&universe), vec![(1, 1)]);
+    assert_eq!(universe.population(), 1);
+    // the initial state moved along
+    universe.set_cells(&[(0, 0)]).unwrap();
+    universe.reset_init_state();
+    assert_eq!(live(&universe), vec![(1, 1)]);
+
+    universe.set_cells(&[(0, 5), (3, 0)]).unwrap();
+    universe.rotate_cw();
+    assert_eq!((universe.width(), universe.height()), (4, 6));
+    assert_eq!(live(&universe), vec![(0, 0), (1, 2), (5, 3)]);
+    universe.flip_horizontal();
+    assert_eq!(live(&universe), vec![(0, 3), (1, 1), (5, 0)]);
+    universe.flip_vertical();
+    assert_eq!(live(&universe), vec![(0, 0), (4, 1), (5, 3)]);
+
+    // four quarter turns are the identity, in either storage order
+    let mut soup = Universe::new_seeded(5, 9, 0.4, 2).unwrap();
+    soup.set_storage_order(StorageOrder::ColumnMajor);
+    let before = live(&soup);
+    for _ in 0..4 {
+        soup.rotate_cw();
+    }
+    assert_eq!(live(&soup), before);
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_crop() {
+    let mut universe = Universe::with_rule(10, 12, "B36/S23").unwrap();
+    universe.set_topology(Topology::Dead);
+    universe.set_storage_order(StorageOrder::ColumnMajor);
+    universe
+        .set_cells(&[(2, 3), (3, 4), (4, 2), (4, 3), (4, 4), (9, 11)])
+        .unwrap();
+
+    let mut cropped = universe.crop(1, 1, 5, 6).unwrap();
+    assert_eq!((cropped.width(), cropped.height()), (6, 5));
+    assert_eq!(cropped.population(), 5);
+    assert_eq!(cropped.cell(1, 2), Ok(true));
+    assert_eq!(cropped.rule(), universe.rule());
+    assert_eq!(cropped.topology(), Topology::Dead);
+    // the crop is also its initial state
+    cropped.reset_cells();
+    cropped.reset_init_state();
+    assert_eq!(cropped.population(), 5);
+
+    assert_eq!(universe.crop(0, 0, 0, 0).unwrap().population(), 0);
+    assert!(universe.crop(6, 6, 5, 5).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_merge() {
+    let universe = |cells: &[(u32, u32)]| {
+        let mut universe = Universe::new(6, 6);
+        universe.set_cells(cells).unwrap();
+        universe
+    };
+    let a = &[(0, 0), (1, 1), (2, 2)];
+    let b = &[(1, 1), (2, 2), (5, 5)];
+    let merged = |op| {
+        let mut result = universe(a);
+        result.merge(&universe(b), op).unwrap();
+        result.live_cells()
+    };
+    assert_eq!(merged(MergeOp::Union), vec![0, 0, 1, 1, 2, 2, 5, 5]);
+    assert_eq!(merged(MergeOp::Intersect), vec![1, 1, 2, 2]);
+    assert_eq!(merged(MergeOp::Difference), vec![0, 0]);
+    assert_eq!(merged(MergeOp::Xor), vec![0, 0, 5, 5]);
+
+    // storage orders are reconciled
+    let mut transposed = universe(b);
+    transposed.set_storage_order(StorageOrder::ColumnMajor);
+    let mut result = universe(a);
+    result.merge(&transposed, MergeOp::Xor).unwrap();
+    assert_eq!(result.live_cells(), vec![0, 0, 5, 5]);
+    assert_eq!(result.population(), 2);
+
+    assert!(result.merge(&Universe::new(6, 7), MergeOp::Union).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_diff() {
+    let universe = || {
+        let mut universe = Universe::new(4, 5);
+        universe.set_cells(&[(0, 0), (1, 2), (3, 4)]).unwrap();
+        universe
+    };
+    let a = universe();
+    let mut b = universe();
+    assert!(a.equals(&b));
+    assert_eq!(a.diff(&b).unwrap(), Vec::<u32>::new());
+
+    b.toggle_cell(1, 2).unwrap();
+    b.toggle_cell(2, 1).unwrap();
+    assert!(!a.equals(&b));
+    assert_eq!(a.diff(&b).unwrap(), vec![7, 11]);
+    assert_eq!(b.diff(&a).unwrap(), vec![7, 11]);
+
+    // indices follow the universe diff is called on
+    let mut transposed = universe();
+    transposed.toggle_cell(1, 2).unwrap();
+    transposed.toggle_cell(2, 1).unwrap();
+    transposed.set_storage_order(StorageOrder::ColumnMajor);
+    assert!(b.equals(&transposed));
+    assert_eq!(a.diff(&transposed).unwrap(), vec![7, 11]);
+
+    assert!(!a.equals(&Universe::new(5, 4)));
+    assert!(a.diff(&Universe::new(5, 4)).is_err());
+}
+
+/// Names declared in `wit`: the functions, and the cases of each enum,
+/// variant and record under its type name.
+#[cfg(feature = "rules")]
+fn wit_declarations(wit: &str) -> (Vec<String>, Vec<(String, Vec<String>)>) {
+    let mut functions = Vec::new();
+    let mut types: Vec<(String, Vec<String>)> = Vec::new();
+    let mut in_type = false;
+    for line in wit.lines().map(str::trim) {
+        if line.starts_with("//") || line.is_empty() {
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if let ["enum" | "variant" | "record", name, "{"] = words[..] {
+            types.push((name.to_string(), Vec::new()));
+            in_type = true;
+        } else if in_type && line == "}" {
+            in_type = false;
+        } else if in_type {
+            let case = line.split(['(', ':', ',']).next().unwrap_or_default();
+            types.last_mut().unwrap().1.push(case.to_string());
+        } else if line.starts_with("constructor(") {
+            functions.push("constructor".to_string());
+        } else if let Some((name, rest)) = line.split_once(": ") {
+            if rest.starts_with("func(") || rest.starts_with("static func(") {
+                functions.push(name.to_string());
+            }
+        }
+    }
+    (functions, types)
+}
+
+/// The case of the WIT `error` variant each `Error` crosses over as.
+#[cfg(feature = "rules")]
+fn wit_error_case(error: &Error) -> &'static str {
+    match error {
+        Error::GenerationLimit { .. } => "generation-limit",
+        Error::MemoryLimit { .. } => "memory-limit",
+        Error::CapacityExceeded { .. } => "capacity-exceeded",
+        Error::DimensionsTooLarge { .. } => "dimensions-too-large",
+        Error::OutOfBounds { .. } => "out-of-bounds",
+        Error::NoCheckpoint(_) => "no-checkpoint",
+        Error::InvalidSnapshot(_) => "invalid-snapshot",
+        Error::InvalidPattern { .. } => "invalid-pattern",
+        Error::InvalidRule(_) => "invalid-rule",
+        Error::InvalidConfig(_) => "invalid-config",
+        Error::Cancelled { .. } => "cancelled",
+        Error::NoSaveState(_)
+        | Error::InvalidQuery(_)
+        | Error::UnknownHandle { .. }
+        | Error::Io(_)
+        | Error::Fetch(_)
+        | Error::Gpu(_) => "other",
+    }
+}
+
+#[cfg(feature = "rules")]
+#[wasm_bindgen_test]
+pub fn test_wit_matches_the_rust_api() {
+    use std::any::Any;
+    use wasm_game_of_life::TickStats;
+
+    let (functions, types) = wit_declarations(include_str!("../wit/game-of-life.wit"));
+
+    // every function of the world with the Rust call behind it, the casts
+    // spelling out the WIT signatures
+    let bindings: Vec<(&str, Box<dyn Any>)> = vec![
+        (
+            "constructor",
+            Box::new(Universe::new as fn(u32, u32) -> Universe),
+        ),
+        (
+            "new-seeded",
+            Box::new(Universe::new_seeded as fn(u32, u32, f64, u64) -> Result<Universe, Error>),
+        ),
+        ("width", Box::new(Universe::width as fn(&Universe) -> u32)),
+        ("height", Box::new(Universe::height as fn(&Universe) -> u32)),
+        (
+            "set-width",
+            Box::new(Universe::set_width as fn(&mut Universe, u32) -> Result<(), Error>),
+        ),
+        (
+            "set-height",
+            Box::new(Universe::set_height as fn(&mut Universe, u32) -> Result<(), Error>),
+        ),
+        (
+            "resize",
+            Box::new(Universe::resize as fn(&mut Universe, u32, u32, Anchor) -> Result<(), Error>),
+        ),
+        (
+            "storage-order",
+            Box::new(Universe::storage_order as fn(&Universe) -> StorageOrder),
+        ),
+        (
+            "set-storage-order",
+            Box::new(Universe::set_storage_order as fn(&mut Universe, StorageOrder)),
+        ),
+        (
+            "topology",
+            Box::new(Universe::topology as fn(&Universe) -> Topology),
+        ),
+        (
+            "set-topology",
+            Box::new(Universe::set_topology as fn(&mut Universe, Topology)),
+        ),
+        (
+            "neighborhood",
+            Box::new(Universe::neighborhood as fn(&Universe) -> Neighborhood),
+        ),
+        (
+            "set-neighborhood",
+            Box::new(Universe::set_neighborhood as fn(&mut Universe, Neighborhood)),
+        ),
+        ("tick", Box::new(Universe::tick as fn(&mut Universe))),
+        (
+            "tick-with-stats",
+            Box::new(Universe::tick_with_stats as fn(&mut Universe) -> TickStats),
+        ),
+        (
+            "tick-many",
+            Box::new(Universe::tick_many as fn(&mut Universe, u32) -> Result<(), Error>),
+        ),
+        (
+            "run",
+            Box::new(Universe::run as fn(&mut Universe, u32) -> u32),
+        ),
+        (
+            "last-tick",
+            Box::new(Universe::last_tick as fn(&Universe) -> TickStats),
+        ),
+        (
+            "generation",
+            Box::new(Universe::generation as fn(&Universe) -> u32),
+        ),
+        (
+            "population",
+            Box::new(Universe::population as fn(&Universe) -> u32),
+        ),
+        (
+            "births-last-tick",
+            Box::new(Universe::births_last_tick as fn(&Universe) -> u32),
+        ),
+        (
+            "deaths-last-tick",
+            Box::new(Universe::deaths_last_tick as fn(&Universe) -> u32),
+        ),
+        // the glue copies `cells_byte_len()` bytes from the pointer
+        (
+            "cells",
+            Box::new(Universe::cells as fn(&Universe) -> *const u8),
+        ),
+        (
+            "cell",
+            Box::new(Universe::cell as fn(&Universe, u32, u32) -> Result<bool, Error>),
+        ),
+        (
+            "live-cells",
+            Box::new(Universe::live_cells as fn(&Universe) -> Vec<u32>),
+        ),
+        (
+            "toggle-cell",
+            Box::new(Universe::toggle_cell as fn(&mut Universe, u32, u32) -> Result<(), Error>),
+        ),
+        (
+            "set-cells",
+            Box::new(Universe::set_cells as fn(&mut Universe, &[(u32, u32)]) -> Result<(), Error>),
+        ),
+        (
+            "reset-cells",
+            Box::new(Universe::reset_cells as fn(&mut Universe)),
+        ),
+        (
+            "reset-init-state",
+            Box::new(Universe::reset_init_state as fn(&mut Universe)),
+        ),
+        (
+            "commit-init-state",
+            Box::new(Universe::commit_init_state as fn(&mut Universe)),
+        ),
+        (
+            "randomize-seeded",
+            Box::new(Universe::randomize_seeded as fn(&mut Universe, f64, u64)),
+        ),
+        (
+            "rule",
+            Box::new(
+                (|universe: &Universe| universe.rule().to_string()) as fn(&Universe) -> String,
+            ),
+        ),
+        (
+            "set-rulestring",
+            Box::new(Universe::set_rulestring as fn(&mut Universe, &str) -> Result<(), Error>),
+        ),
+        (
+            "set-noise",
+            Box::new(Universe::set_noise as fn(&mut Universe, f64, f64, u64) -> Result<(), Error>),
+        ),
+        (
+            "clear-noise",
+            Box::new(Universe::clear_noise as fn(&mut Universe)),
+        ),
+        (
+            "state-hash",
+            Box::new(Universe::state_hash as fn(&Universe) -> u64),
+        ),
+        (
+            "complement-state",
+            Box::new(Universe::complement_state as fn(&mut Universe)),
+        ),
+        (
+            "parse-rule",
+            Box::new(
+                (|rulestring: &str| Rule::parse(rulestring).map(|rule| rule.to_string()))
+                    as fn(&str) -> Result<String, Error>,
+            ),
+        ),
+        (
+            "complement-rule",
+            Box::new(complement_rule as fn(&str) -> Result<String, Error>),
+        ),
+    ];
+    let mut bound: Vec<&str> = bindings.iter().map(|(name, _)| *name).collect();
+    let mut declared: Vec<&str> = functions.iter().map(String::as_str).collect();
+    bound.sort_unstable();
+    declared.sort_unstable();
+    assert_eq!(declared, bound);
+
+    let cases = |name: &str| -> Vec<String> {
+        let (_, cases) = types
+            .iter()
+            .find(|(type_name, _)| type_name == name)
+            .unwrap();
+        cases.clone()
+    };
+    let names = |names: &[&str]| -> Vec<String> { names.iter().map(|n| n.to_string()).collect() };
+    assert_eq!(
+        cases("topology"),
+        names(
+            &[
+                Topology::Torus,
+                Topology::Dead,
+                Topology::Mirror,
+                Topology::KleinBottle
+            ]
+            .map(Topology::name)
+        )
+    );
+    assert_eq!(
+        cases("neighborhood"),
+        names(
+            &[
+                Neighborhood::Moore,
+                Neighborhood::VonNeumann,
+                Neighborhood::Hexagonal
+            ]
+            .map(Neighborhood::name)
+        )
+    );
+    assert_eq!(
+        cases("storage-order"),
+        names(&["row-major", "column-major"])
+    );
+    assert_eq!(cases("anchor"), names(&["top-left", "center"]));
+
+    // destructured without `..`, so new fields show up here
+    let TickStats {
+        generation: _,
+        births: _,
+        deaths: _,
+        population: _,
+        changed: _,
+    } = Universe::new(1, 1).last_tick();
+    assert_eq!(
+        cases("tick-stats"),
+        names(&["generation", "births", "deaths", "population", "changed"])
+    );
+
+    let errors = [
+        Error::GenerationLimit {
+            requested: 0,
+            limit: 0,
+        },
+        Error::MemoryLimit { what: "", limit: 0 },
+        Error::CapacityExceeded {
+            requested: 0,
+            capacity: 0,
+        },
+        Error::DimensionsTooLarge {
+            width: 0,
+            height: 0,
+        },
+        Error::OutOfBounds {
+            row: 0,
+            column: 0,
+            width: 0,
+            height: 0,
+        },
+        Error::NoCheckpoint(0),
+        Error::InvalidSnapshot(""),
+        Error::InvalidPattern {
+            line: 0,
+            reason: "",
+        },
+        Error::InvalidRule(""),
+        Error::InvalidConfig(""),
+        Error::Cancelled { generations: 0 },
+        Error::Io(String::new()),
+    ];
+    assert_eq!(
+        cases("error"),
+        names(&errors.each_ref().map(wit_error_case))
+    );
+}
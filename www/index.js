@@ -1,5 +1,5 @@
 import { memory } from "wasm-game-of-life/wasm_game_of_life_bg.wasm";
-import { Universe } from "wasm-game-of-life";
+import { Universe, PlacementPolicy, PhaseTimer, StatusKind, load_pattern_from_url } from "wasm-game-of-life";
 
 const CELL_SIZE = 5 // pixels
 const GRID_COLOR = "#CCCCCC";
@@ -21,6 +21,10 @@ let frameId = null
 const tickSlider = document.getElementById("tick-slider")
 const resetButton = document.getElementById("reset-uni-button")
 const deadButton = document.getElementById("dead-uni-button")
+const patternUrl = document.getElementById("pattern-url")
+const loadPatternButton = document.getElementById("load-pattern-button")
+const rulestring = document.getElementById("rulestring")
+const setRuleButton = document.getElementById("set-rule-button")
 
 const drawGrid = () => {
     ctx.beginPath();
@@ -53,7 +57,7 @@ const isBitSet = (n, arr) => {
 
 const drawCells = () => {
     const cellsPtr = universe.cells()
-    const cells = new Uint8Array(memory.buffer, cellsPtr, width * height / 8)
+    const cells = new Uint8Array(memory.buffer, cellsPtr, universe.cells_byte_len())
 
     ctx.beginPath();
 
@@ -155,6 +159,31 @@ deadButton.addEventListener("click", event => {
     drawBoard()
 })
 
+loadPatternButton.addEventListener("click", async event => {
+    try {
+        const pattern = await load_pattern_from_url(patternUrl.value)
+        universe.reset_cells()
+        universe.place_pattern(
+            pattern,
+            Math.max(0, Math.floor((height - pattern.height()) / 2)),
+            Math.max(0, Math.floor((width - pattern.width()) / 2)),
+            PlacementPolicy.Clip
+        )
+        drawBoard()
+    } catch (error) {
+        console.error(error)
+    }
+})
+
+setRuleButton.addEventListener("click", event => {
+    try {
+        universe.set_rulestring(rulestring.value)
+    } catch (error) {
+        console.error(error)
+    }
+    rulestring.value = universe.rule().toString()
+})
+
 const fps = new class {
     constructor() {
         this.fps = document.getElementById("fps")
@@ -202,8 +231,15 @@ const renderLoop = () => {
         universe.tick()
     }
 
+    const render = PhaseTimer.start("render")
     drawBoard()
+    render.end()
 
+    // nothing left to watch: dead, or a still life or oscillator
+    if (universe.status().kind !== StatusKind.Running) {
+        pause()
+        return
+    }
     frameId = requestAnimationFrame(renderLoop)
 }
 